@@ -0,0 +1,15 @@
+-- Client frame IDs remembered for idempotent retries
+CREATE TABLE IF NOT EXISTS ingested_frames (
+                                               vessel_id VARCHAR(50) NOT NULL,
+                                               frame_id VARCHAR(100) NOT NULL,
+                                               correlation_id UUID NOT NULL,
+                                               response JSONB NOT NULL,
+                                               created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                               PRIMARY KEY (vessel_id, frame_id),
+                                               FOREIGN KEY (vessel_id) REFERENCES vessel_register_table(vessel_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_ingested_frames_created ON ingested_frames(created_at);
+
+-- Lookup path for the telemetry_raw conflict policy
+CREATE INDEX idx_telemetry_sample_key ON telemetry_raw(vessel_id, signal_name, timestamp_utc);
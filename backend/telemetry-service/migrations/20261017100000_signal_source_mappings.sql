@@ -0,0 +1,15 @@
+-- Maps fields decoded from onboard protocols (NMEA 0183, ...) onto registered signals
+CREATE TABLE IF NOT EXISTS signal_source_mappings (
+                                                      source VARCHAR(20) NOT NULL,
+                                                      message_type VARCHAR(20) NOT NULL,
+                                                      field_name VARCHAR(100) NOT NULL,
+                                                      signal_name VARCHAR(100) NOT NULL,
+                                                      created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                      updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                      correlation_id UUID,
+                                                      trace_id VARCHAR(100),
+                                                      PRIMARY KEY (source, message_type, field_name),
+                                                      FOREIGN KEY (signal_name) REFERENCES signal_register_table(signal_name) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_signal_source_mappings_signal ON signal_source_mappings(signal_name);
@@ -0,0 +1,11 @@
+-- Own-ship identity and static/voyage data reported by the AIS transponder
+ALTER TABLE vessel_register_table
+    ADD COLUMN IF NOT EXISTS mmsi INTEGER,
+    ADD COLUMN IF NOT EXISTS ais_callsign VARCHAR(7),
+    ADD COLUMN IF NOT EXISTS ais_ship_type SMALLINT,
+    ADD COLUMN IF NOT EXISTS ais_destination VARCHAR(20),
+    ADD COLUMN IF NOT EXISTS ais_eta TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS ais_draught DECIMAL(4, 1),
+    ADD COLUMN IF NOT EXISTS ais_static_updated_at TIMESTAMPTZ;
+
+CREATE INDEX idx_vessel_mmsi ON vessel_register_table(mmsi);
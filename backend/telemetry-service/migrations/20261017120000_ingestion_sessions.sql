@@ -0,0 +1,18 @@
+-- Persistent ingestion connections (WebSocket); an open row means the vessel is live
+CREATE TABLE IF NOT EXISTS ingestion_sessions (
+                                                  session_id UUID PRIMARY KEY,
+                                                  vessel_id VARCHAR(50) NOT NULL,
+                                                  transport VARCHAR(20) NOT NULL,
+                                                  connected_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                  disconnected_at TIMESTAMPTZ,
+                                                  end_reason VARCHAR(30),
+                                                  frames_received BIGINT NOT NULL DEFAULT 0,
+                                                  frames_accepted BIGINT NOT NULL DEFAULT 0,
+                                                  frames_rejected BIGINT NOT NULL DEFAULT 0,
+                                                  correlation_id UUID NOT NULL,
+                                                  trace_id VARCHAR(100),
+                                                  FOREIGN KEY (vessel_id) REFERENCES vessel_register_table(vessel_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_ingestion_sessions_vessel ON ingestion_sessions(vessel_id, connected_at DESC);
+CREATE INDEX idx_ingestion_sessions_open ON ingestion_sessions(vessel_id) WHERE disconnected_at IS NULL;
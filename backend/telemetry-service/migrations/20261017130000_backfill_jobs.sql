@@ -0,0 +1,35 @@
+-- Historical CSV imports; rows they store carry the job's correlation_id
+CREATE TABLE IF NOT EXISTS backfill_jobs (
+                                             job_id UUID PRIMARY KEY,
+                                             vessel_id VARCHAR(50) NOT NULL,
+                                             file_name VARCHAR(255),
+                                             file_sha256 CHAR(64) NOT NULL,
+                                             column_mapping JSONB NOT NULL,
+                                             status VARCHAR(20) NOT NULL,
+                                             rows_total BIGINT NOT NULL,
+                                             rows_processed BIGINT NOT NULL DEFAULT 0,
+                                             rows_accepted BIGINT NOT NULL DEFAULT 0,
+                                             rows_rejected BIGINT NOT NULL DEFAULT 0,
+                                             valid_signals BIGINT NOT NULL DEFAULT 0,
+                                             invalid_signals BIGINT NOT NULL DEFAULT 0,
+                                             error TEXT,
+                                             created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                             updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                             finished_at TIMESTAMPTZ,
+                                             correlation_id UUID NOT NULL,
+                                             trace_id VARCHAR(100),
+                                             FOREIGN KEY (vessel_id) REFERENCES vessel_register_table(vessel_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_backfill_jobs_vessel ON backfill_jobs(vessel_id, created_at DESC);
+CREATE INDEX idx_backfill_jobs_file ON backfill_jobs(vessel_id, file_sha256);
+
+-- Rows a job could not import, numbered as in the file (the header is row 1)
+CREATE TABLE IF NOT EXISTS backfill_row_errors (
+                                                   job_id UUID NOT NULL,
+                                                   row_number BIGINT NOT NULL,
+                                                   message TEXT NOT NULL,
+                                                   reason VARCHAR(50),
+                                                   PRIMARY KEY (job_id, row_number),
+                                                   FOREIGN KEY (job_id) REFERENCES backfill_jobs(job_id) ON DELETE CASCADE
+);
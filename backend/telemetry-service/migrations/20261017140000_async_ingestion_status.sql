@@ -0,0 +1,16 @@
+-- Final outcome of frames accepted with 202 by the async ingestion queue
+CREATE TABLE IF NOT EXISTS async_ingestion_status (
+                                                      correlation_id UUID PRIMARY KEY,
+                                                      vessel_id VARCHAR(50) NOT NULL,
+                                                      frame_id VARCHAR(100),
+                                                      status VARCHAR(20) NOT NULL,
+                                                      valid_signals INTEGER NOT NULL,
+                                                      invalid_signals INTEGER NOT NULL,
+                                                      error TEXT,
+                                                      accepted_at TIMESTAMPTZ NOT NULL,
+                                                      completed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                      trace_id VARCHAR(100),
+                                                      FOREIGN KEY (vessel_id) REFERENCES vessel_register_table(vessel_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_async_ingestion_status_completed ON async_ingestion_status(completed_at);
@@ -0,0 +1,20 @@
+-- Frames the database refused to store, kept with their original payload for inspection and retry.
+-- No foreign key on vessel_id: a deleted vessel is one of the reasons a frame ends up here.
+CREATE TABLE IF NOT EXISTS dead_letter_frames (
+                                                  id UUID PRIMARY KEY,
+                                                  vessel_id VARCHAR(50) NOT NULL,
+                                                  frame_id VARCHAR(100),
+                                                  payload JSONB NOT NULL,
+                                                  decode_rejections JSONB NOT NULL DEFAULT '[]',
+                                                  error_class VARCHAR(50) NOT NULL,
+                                                  error_message TEXT NOT NULL,
+                                                  sqlstate VARCHAR(5),
+                                                  retry_count INTEGER NOT NULL DEFAULT 0,
+                                                  created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                  last_retried_at TIMESTAMPTZ,
+                                                  correlation_id UUID NOT NULL,
+                                                  trace_id VARCHAR(100)
+);
+
+CREATE INDEX idx_dead_letter_frames_vessel ON dead_letter_frames(vessel_id, created_at DESC);
+CREATE INDEX idx_dead_letter_frames_created ON dead_letter_frames(created_at);
@@ -0,0 +1,3 @@
+-- Unit of measure, and soft retirement so frames naming a retired signal stay traceable
+ALTER TABLE signal_register_table ADD COLUMN IF NOT EXISTS unit VARCHAR(30);
+ALTER TABLE signal_register_table ADD COLUMN IF NOT EXISTS retired_at TIMESTAMPTZ;
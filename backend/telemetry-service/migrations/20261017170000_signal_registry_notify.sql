@@ -0,0 +1,13 @@
+-- Tell every service instance to reload its in-memory signal registry after a change.
+-- Statement-level, so a catalogue import sends a handful of notifications rather than one per row;
+-- PostgreSQL also folds identical notifications raised in one transaction.
+CREATE OR REPLACE FUNCTION notify_signal_registry_changed() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('signal_registry_changed', TG_OP);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER signal_registry_changed
+    AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON signal_register_table
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_signal_registry_changed();
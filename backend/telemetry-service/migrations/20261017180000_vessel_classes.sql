@@ -0,0 +1,54 @@
+-- Vessel classes: templates listing the signals a vessel of the class may send
+CREATE TABLE IF NOT EXISTS vessel_class_table (
+                                                  class_name VARCHAR(50) PRIMARY KEY,
+                                                  description TEXT,
+                                                  created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                  updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                  correlation_id UUID,
+                                                  trace_id VARCHAR(100)
+);
+
+CREATE TABLE IF NOT EXISTS vessel_class_signals (
+                                                    class_name VARCHAR(50) NOT NULL,
+                                                    signal_name VARCHAR(100) NOT NULL,
+                                                    PRIMARY KEY (class_name, signal_name),
+                                                    FOREIGN KEY (class_name) REFERENCES vessel_class_table(class_name) ON DELETE CASCADE,
+                                                    FOREIGN KEY (signal_name) REFERENCES signal_register_table(signal_name) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_vessel_class_signals_signal ON vessel_class_signals(signal_name);
+
+-- Vessels without a class may send every registered signal
+ALTER TABLE vessel_register_table
+    ADD COLUMN IF NOT EXISTS vessel_class VARCHAR(50) REFERENCES vessel_class_table(class_name);
+
+CREATE INDEX idx_vessel_class ON vessel_register_table(vessel_class);
+
+-- Per-vessel ranges replacing the registered min/max of an analog signal
+CREATE TABLE IF NOT EXISTS vessel_signal_overrides (
+                                                       vessel_id VARCHAR(50) NOT NULL,
+                                                       signal_name VARCHAR(100) NOT NULL,
+                                                       min_value DECIMAL(10, 2) NOT NULL,
+                                                       max_value DECIMAL(10, 2) NOT NULL,
+                                                       created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                       updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                                                       correlation_id UUID,
+                                                       trace_id VARCHAR(100),
+                                                       PRIMARY KEY (vessel_id, signal_name),
+                                                       FOREIGN KEY (vessel_id) REFERENCES vessel_register_table(vessel_id) ON DELETE CASCADE,
+                                                       FOREIGN KEY (signal_name) REFERENCES signal_register_table(signal_name) ON DELETE CASCADE
+);
+
+-- Signal sets and overrides are part of the in-memory registry, so changing them reloads it too.
+-- Only vessel inserts, deletes and class changes matter; AIS and MMSI updates do not notify.
+CREATE TRIGGER vessel_class_signals_changed
+    AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON vessel_class_signals
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_signal_registry_changed();
+
+CREATE TRIGGER vessel_signal_overrides_changed
+    AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON vessel_signal_overrides
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_signal_registry_changed();
+
+CREATE TRIGGER vessel_class_assignment_changed
+    AFTER INSERT OR DELETE OR UPDATE OF vessel_class ON vessel_register_table
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_signal_registry_changed();
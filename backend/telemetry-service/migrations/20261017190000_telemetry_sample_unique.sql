@@ -0,0 +1,15 @@
+-- One row per (vessel_id, signal_name, timestamp_utc), so the ignore and overwrite conflict policies hold
+-- under concurrent frames. Extra copies stored under keep_both are flagged and left out of the index.
+ALTER TABLE telemetry_raw ADD COLUMN IF NOT EXISTS duplicate_sample BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Rows stored before the index keep the oldest copy of each sample as the indexed one
+UPDATE telemetry_raw t
+SET duplicate_sample = TRUE
+FROM (
+         SELECT id, ROW_NUMBER() OVER (PARTITION BY vessel_id, signal_name, timestamp_utc ORDER BY id) AS copy
+         FROM telemetry_raw
+     ) d
+WHERE t.id = d.id AND d.copy > 1;
+
+CREATE UNIQUE INDEX idx_telemetry_sample_unique ON telemetry_raw(vessel_id, signal_name, timestamp_utc)
+    WHERE NOT duplicate_sample;
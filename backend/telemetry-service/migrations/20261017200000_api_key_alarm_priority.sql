@@ -0,0 +1,2 @@
+-- Keys allowed to send frames with `x-telemetry-priority: alarm`; claims from other keys count as routine
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS alarm_priority BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,196 @@
+//! Compares telemetry write strategies against the database in `DATABASE_URL`
+//!
+//! ```text
+//! cargo run --release --bin write_bench -- [frames] [signals_per_frame] [frames_per_flush]
+//! ```
+//!
+//! Writes synthetic frames for a temporary vessel three ways: one `INSERT` per signal (how
+//! telemetry_raw used to be written), one `UNNEST` insert per frame, and a `TelemetryWriter`
+//! batching many frames per transaction. The vessel and its rows are removed afterwards.
+//!
+//! Round trips are listed next to the timings: on a database across a network link each one
+//! adds the link's latency, which is where the per-signal path loses most of its time.
+
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::time::Instant;
+use telemetry_service::config::ConflictPolicy;
+use telemetry_service::database::get_pool;
+use telemetry_service::models::telemetry::RawRecord;
+use telemetry_service::repositories::telemetry::TelemetryRepository;
+use uuid::Uuid;
+
+const BENCH_VESSEL: &str = "WRITE_BENCH";
+
+struct Run {
+    name: &'static str,
+    rows: usize,
+    round_trips: usize,
+    secs: f64,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv().ok();
+
+    let mut args = std::env::args().skip(1).map(|a| a.parse::<usize>());
+    let frames = args.next().transpose()?.unwrap_or(200);
+    let signals = args.next().transpose()?.unwrap_or(200);
+    let frames_per_flush = args.next().transpose()?.unwrap_or(50);
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = get_pool(database_url, 5, None).await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+
+    sqlx::query(
+        "INSERT INTO vessel_register_table (vessel_id, vessel_name) VALUES ($1, 'Write benchmark') \
+         ON CONFLICT (vessel_id) DO NOTHING",
+    )
+    .bind(BENCH_VESSEL)
+    .execute(&pool)
+    .await?;
+
+    let repo = TelemetryRepository::new(pool.clone());
+    let workload: Vec<Vec<RawRecord>> = (0..frames).map(|i| frame(i, signals)).collect();
+
+    println!(
+        "{} frames x {} signals = {} rows per run\n",
+        frames,
+        signals,
+        frames * signals
+    );
+
+    let mut runs = Vec::new();
+
+    clear(&pool).await?;
+    let started = Instant::now();
+    for records in &workload {
+        insert_row_by_row(&pool, records).await?;
+    }
+    // BEGIN, one INSERT per signal, COMMIT
+    let round_trips = frames * (signals + 2);
+    runs.push(finish("INSERT per signal", &workload, round_trips, started));
+
+    clear(&pool).await?;
+    let started = Instant::now();
+    for records in &workload {
+        repo.insert_frame(records, &[], ConflictPolicy::KeepBoth, None)
+            .await?;
+    }
+    // BEGIN, one INSERT, COMMIT
+    runs.push(finish("UNNEST per frame", &workload, frames * 3, started));
+
+    clear(&pool).await?;
+    let started = Instant::now();
+    let mut writer = repo.writer(ConflictPolicy::KeepBoth, frames_per_flush);
+    for records in &workload {
+        writer.push(records.clone(), Vec::new(), None);
+        if writer.is_full() {
+            writer.flush().await?;
+        }
+    }
+    writer.flush().await?;
+    let round_trips = frames.div_ceil(frames_per_flush.max(1)) * 3;
+    runs.push(finish("TelemetryWriter", &workload, round_trips, started));
+
+    // Cascades to the vessel's telemetry_raw rows
+    sqlx::query("DELETE FROM vessel_register_table WHERE vessel_id = $1")
+        .bind(BENCH_VESSEL)
+        .execute(&pool)
+        .await?;
+
+    let baseline = runs[0].rows as f64 / runs[0].secs;
+    println!(
+        "{:<20} {:>12} {:>10} {:>14} {:>9}",
+        "strategy", "round trips", "seconds", "rows/s", "speedup"
+    );
+    for run in &runs {
+        let rate = run.rows as f64 / run.secs;
+        println!(
+            "{:<20} {:>12} {:>10.3} {:>14.0} {:>8.1}x",
+            run.name,
+            run.round_trips,
+            run.secs,
+            rate,
+            rate / baseline
+        );
+    }
+    println!(
+        "\nTelemetryWriter flushes every {} frames",
+        frames_per_flush
+    );
+
+    Ok(())
+}
+
+fn frame(index: usize, signals: usize) -> Vec<RawRecord> {
+    let timestamp = Utc::now() - Duration::seconds(index as i64);
+    let correlation_id = Uuid::new_v4();
+
+    (0..signals)
+        .map(|s| {
+            (
+                BENCH_VESSEL.to_string(),
+                timestamp,
+                timestamp.timestamp(),
+                format!("Signal_{}", s + 1),
+                Decimal::from(s as i64 + 1),
+                correlation_id,
+                correlation_id.to_string(),
+            )
+        })
+        .collect()
+}
+
+/// The previous write path: one statement per signal inside a transaction
+async fn insert_row_by_row(pool: &PgPool, records: &[RawRecord]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for (
+        vessel_id,
+        timestamp_utc,
+        epoch_utc,
+        signal_name,
+        signal_value,
+        correlation_id,
+        trace_id,
+    ) in records
+    {
+        sqlx::query(
+            "INSERT INTO telemetry_raw (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(vessel_id)
+        .bind(timestamp_utc)
+        .bind(epoch_utc)
+        .bind(signal_name)
+        .bind(signal_value)
+        .bind(correlation_id)
+        .bind(trace_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+async fn clear(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM telemetry_raw WHERE vessel_id = $1")
+        .bind(BENCH_VESSEL)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+fn finish(
+    name: &'static str,
+    workload: &[Vec<RawRecord>],
+    round_trips: usize,
+    started: Instant,
+) -> Run {
+    Run {
+        name,
+        rows: workload.iter().map(Vec::len).sum(),
+        round_trips,
+        secs: started.elapsed().as_secs_f64(),
+    }
+}
@@ -0,0 +1,199 @@
+//! Subcommands of the service binary, run instead of the server when arguments are given
+//!
+//! ```text
+//! telemetry-service signals import <file> [--format yaml|csv] [--apply]
+//! telemetry-service signals export [--format yaml|csv] [--output <file>]
+//! ```
+//!
+//! They work on the database in `DATABASE_URL` directly, with the same validation and diff
+//! as the `/api/v1/signal-catalogue` endpoints.
+
+use crate::config::CacheConfig;
+use crate::database::get_pool;
+use crate::models::signal::{CatalogueSignal, SignalCatalogueDiff};
+use crate::repositories::signal::SignalRepository;
+use crate::repositories::vessel_class::VesselClassRepository;
+use crate::services::signal::SignalService;
+use crate::services::signal_catalogue::CatalogueFormat;
+use crate::services::signal_registry::SignalRegistryCache;
+use rust_decimal::Decimal;
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+const USAGE: &str = "usage:
+  telemetry-service                      run the server
+  telemetry-service signals import <file> [--format yaml|csv] [--apply]
+  telemetry-service signals export [--format yaml|csv] [--output <file>]";
+
+pub async fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    dotenv::dotenv().ok();
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["signals", "import", rest @ ..] => import_signals(rest).await,
+        ["signals", "export", rest @ ..] => export_signals(rest).await,
+        ["help" | "--help" | "-h"] => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+#[derive(Default)]
+struct Options<'a> {
+    file: Option<&'a str>,
+    format: Option<&'a str>,
+    output: Option<&'a str>,
+    apply: bool,
+}
+
+fn parse_options<'a>(args: &[&'a str]) -> Result<Options<'a>, Box<dyn Error>> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--apply" => options.apply = true,
+            "--format" => options.format = Some(args.next().copied().ok_or(USAGE)?),
+            "--output" | "-o" => options.output = Some(args.next().copied().ok_or(USAGE)?),
+            _ if arg.starts_with('-') || options.file.is_some() => {
+                return Err(format!("unexpected argument '{}'\n{}", arg, USAGE).into());
+            }
+            _ => options.file = Some(arg),
+        }
+    }
+    Ok(options)
+}
+
+async fn signal_service() -> Result<SignalService, Box<dyn Error>> {
+    let database_url = std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL must be set")?;
+    let pool = get_pool(database_url, 1, None).await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+
+    // Running services pick up changes through their own caches' notifications
+    let signal_repo = Arc::new(SignalRepository::new(pool.clone()));
+    let signal_registry = Arc::new(SignalRegistryCache::new(
+        signal_repo.clone(),
+        Arc::new(VesselClassRepository::new(pool.clone())),
+        pool,
+        CacheConfig::default().signal_refresh_interval_secs,
+    ));
+    Ok(SignalService::new(signal_repo, signal_registry))
+}
+
+async fn import_signals(args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let options = parse_options(args)?;
+    let file = options.file.ok_or(USAGE)?;
+    let format = match options.format {
+        Some(name) => CatalogueFormat::from_name(name)?,
+        None => CatalogueFormat::from_path(Path::new(file))
+            .ok_or("cannot tell the format from the file name, pass --format yaml|csv")?,
+    };
+
+    let contents = std::fs::read(file).map_err(|e| format!("cannot read {}: {}", file, e))?;
+    let entries = format.parse(&contents)?;
+    let diff = signal_service()
+        .await?
+        .import_catalogue(entries, options.apply)
+        .await?;
+
+    print_diff(&diff);
+    Ok(())
+}
+
+async fn export_signals(args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let options = parse_options(args)?;
+    if let Some(file) = options.file {
+        return Err(format!("unexpected argument '{}'\n{}", file, USAGE).into());
+    }
+    let format = match (options.format, options.output) {
+        (Some(name), _) => CatalogueFormat::from_name(name)?,
+        (None, Some(output)) => {
+            CatalogueFormat::from_path(Path::new(output)).unwrap_or(CatalogueFormat::Yaml)
+        }
+        (None, None) => CatalogueFormat::Yaml,
+    };
+
+    let signals = signal_service().await?.export_catalogue().await?;
+    let count = signals.len();
+    let rendered = format.render(signals)?;
+
+    match options.output {
+        Some(output) => {
+            std::fs::write(output, rendered)
+                .map_err(|e| format!("cannot write {}: {}", output, e))?;
+            eprintln!("Exported {} signals to {}", count, output);
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+fn print_diff(diff: &SignalCatalogueDiff) {
+    for signal in &diff.added {
+        println!("+ {} {}", signal.signal_name, describe(signal));
+    }
+    for change in &diff.changed {
+        let fields: Vec<String> = change
+            .fields
+            .iter()
+            .map(|field| match field.as_str() {
+                "retired" => "brought back from retirement".to_string(),
+                field => format!(
+                    "{} {} -> {}",
+                    field,
+                    field_value(&change.before, field),
+                    field_value(&change.after, field)
+                ),
+            })
+            .collect();
+        println!("~ {}: {}", change.signal_name, fields.join(", "));
+    }
+    for signal in &diff.removed {
+        println!("- {} {}", signal.signal_name, describe(signal));
+    }
+
+    println!(
+        "{} added, {} changed, {} removed, {} unchanged",
+        diff.added.len(),
+        diff.changed.len(),
+        diff.removed.len(),
+        diff.unchanged
+    );
+    match diff.correlation_id {
+        Some(correlation_id) => println!("Applied (correlation_id {})", correlation_id),
+        None if diff.applied => println!("Nothing to apply"),
+        None => println!("Preview only; run again with --apply to write these changes"),
+    }
+}
+
+fn describe(signal: &CatalogueSignal) -> String {
+    let mut description = format!(
+        "{} [{}, {}]",
+        signal.signal_type,
+        bound(signal.min_value),
+        bound(signal.max_value)
+    );
+    if let Some(unit) = &signal.unit {
+        description.push(' ');
+        description.push_str(unit);
+    }
+    description
+}
+
+fn field_value(signal: &CatalogueSignal, field: &str) -> String {
+    let text = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+    match field {
+        "signalType" => signal.signal_type.clone(),
+        "minValue" => bound(signal.min_value),
+        "maxValue" => bound(signal.max_value),
+        "unit" => text(&signal.unit),
+        "description" => text(&signal.description),
+        _ => String::new(),
+    }
+}
+
+fn bound(value: Option<Decimal>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.normalize().to_string())
+}
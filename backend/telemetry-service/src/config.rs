@@ -0,0 +1,357 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// What to do when a raw sample repeats an existing `(vessel_id, signal_name, timestamp_utc)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the row already stored and drop the new sample
+    Ignore,
+    /// Replace the stored value with the new sample
+    Overwrite,
+    /// Store both rows (the historical behaviour)
+    KeepBoth,
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ignore" => Ok(ConflictPolicy::Ignore),
+            "overwrite" => Ok(ConflictPolicy::Overwrite),
+            "keep_both" => Ok(ConflictPolicy::KeepBoth),
+            other => Err(format!(
+                "Unknown conflict policy '{}', expected ignore, overwrite or keep_both",
+                other
+            )),
+        }
+    }
+}
+
+/// Ingestion settings, read once from the environment at startup
+#[derive(Debug, Clone)]
+pub struct IngestionConfig {
+    /// How long a client frame ID is remembered for replay detection (`TELEMETRY_DEDUP_WINDOW_SECS`)
+    pub dedup_window_secs: f64,
+    /// Duplicate sample handling for telemetry_raw (`TELEMETRY_CONFLICT_POLICY`)
+    pub conflict_policy: ConflictPolicy,
+    /// Allowed difference between `epochUTC` and `timestampUTC` (`TELEMETRY_EPOCH_TOLERANCE_SECS`)
+    pub epoch_tolerance_secs: i64,
+    /// How far ahead of the server clock a frame may be (`TELEMETRY_MAX_FUTURE_SKEW_SECS`)
+    pub max_future_skew_secs: i64,
+    /// Oldest frame accepted, relative to the server clock (`TELEMETRY_MAX_TIMESTAMP_AGE_SECS`)
+    pub max_timestamp_age_secs: i64,
+    /// WebSocket sessions with no traffic for this long are closed (`TELEMETRY_SESSION_IDLE_TIMEOUT_SECS`)
+    pub session_idle_timeout_secs: u64,
+}
+
+impl Default for IngestionConfig {
+    fn default() -> Self {
+        Self {
+            dedup_window_secs: 86_400.0,
+            conflict_policy: ConflictPolicy::KeepBoth,
+            epoch_tolerance_secs: 1,
+            max_future_skew_secs: 300,
+            max_timestamp_age_secs: 30 * 86_400,
+            session_idle_timeout_secs: 120,
+        }
+    }
+}
+
+impl IngestionConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            dedup_window_secs: env_or("TELEMETRY_DEDUP_WINDOW_SECS", defaults.dedup_window_secs),
+            conflict_policy: env_or("TELEMETRY_CONFLICT_POLICY", defaults.conflict_policy),
+            epoch_tolerance_secs: env_or(
+                "TELEMETRY_EPOCH_TOLERANCE_SECS",
+                defaults.epoch_tolerance_secs,
+            ),
+            max_future_skew_secs: env_or(
+                "TELEMETRY_MAX_FUTURE_SKEW_SECS",
+                defaults.max_future_skew_secs,
+            ),
+            max_timestamp_age_secs: env_or(
+                "TELEMETRY_MAX_TIMESTAMP_AGE_SECS",
+                defaults.max_timestamp_age_secs,
+            ),
+            session_idle_timeout_secs: env_or(
+                "TELEMETRY_SESSION_IDLE_TIMEOUT_SECS",
+                defaults.session_idle_timeout_secs,
+            ),
+        }
+    }
+}
+
+/// Async ingestion settings; `POST /telemetry` only answers `202 Accepted` when
+/// `TELEMETRY_ASYNC_INGESTION` is `true`
+#[derive(Debug, Clone)]
+pub struct AsyncIngestionConfig {
+    /// Frames waiting for a worker before new frames are refused (`TELEMETRY_ASYNC_QUEUE_CAPACITY`)
+    pub queue_capacity: usize,
+    /// Background writers (`TELEMETRY_ASYNC_WORKERS`)
+    pub workers: usize,
+    /// Most frames a worker commits in one transaction (`TELEMETRY_ASYNC_BATCH_FRAMES`)
+    pub batch_frames: usize,
+    /// How long final statuses stay queryable (`TELEMETRY_ASYNC_STATUS_RETENTION_SECS`)
+    pub status_retention_secs: u64,
+}
+
+impl AsyncIngestionConfig {
+    pub fn from_env() -> Option<Self> {
+        if !env_or("TELEMETRY_ASYNC_INGESTION", false) {
+            return None;
+        }
+
+        Some(Self {
+            queue_capacity: env_or("TELEMETRY_ASYNC_QUEUE_CAPACITY", 10_000),
+            workers: env_or("TELEMETRY_ASYNC_WORKERS", 2),
+            batch_frames: env_or("TELEMETRY_ASYNC_BATCH_FRAMES", 200),
+            status_retention_secs: env_or("TELEMETRY_ASYNC_STATUS_RETENTION_SECS", 86_400),
+        })
+    }
+}
+
+/// Connection pool settings
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    /// Pool size (`DATABASE_MAX_CONNECTIONS`)
+    pub max_connections: u32,
+}
+
+impl DatabaseConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_connections: env_or("DATABASE_MAX_CONNECTIONS", 5),
+        }
+    }
+}
+
+/// Load shedding in front of the HTTP telemetry routes
+#[derive(Debug, Clone)]
+pub struct AdmissionConfig {
+    /// Requests in progress past which routine frames get 503 (`TELEMETRY_ADMISSION_MAX_IN_FLIGHT`)
+    pub max_in_flight: usize,
+    /// Lower limit for routine frames while every pool connection is in use
+    /// (`TELEMETRY_ADMISSION_SATURATED_IN_FLIGHT`)
+    pub saturated_in_flight: usize,
+    /// Requests in progress past which alarm frames get 503 too (`TELEMETRY_ADMISSION_ALARM_MAX_IN_FLIGHT`)
+    pub alarm_max_in_flight: usize,
+    /// Shortest `Retry-After` sent with a 503; clients are spread over up to twice this
+    /// (`TELEMETRY_ADMISSION_RETRY_AFTER_SECS`)
+    pub retry_after_secs: u64,
+    /// How often shed counters are written to server_metrics (`TELEMETRY_ADMISSION_METRICS_FLUSH_SECS`)
+    pub metrics_flush_secs: u64,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 64,
+            saturated_in_flight: 16,
+            alarm_max_in_flight: 256,
+            retry_after_secs: 2,
+            metrics_flush_secs: 60,
+        }
+    }
+}
+
+impl AdmissionConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            max_in_flight: env_or("TELEMETRY_ADMISSION_MAX_IN_FLIGHT", defaults.max_in_flight),
+            saturated_in_flight: env_or(
+                "TELEMETRY_ADMISSION_SATURATED_IN_FLIGHT",
+                defaults.saturated_in_flight,
+            ),
+            alarm_max_in_flight: env_or(
+                "TELEMETRY_ADMISSION_ALARM_MAX_IN_FLIGHT",
+                defaults.alarm_max_in_flight,
+            ),
+            retry_after_secs: env_or(
+                "TELEMETRY_ADMISSION_RETRY_AFTER_SECS",
+                defaults.retry_after_secs,
+            ),
+            metrics_flush_secs: env_or(
+                "TELEMETRY_ADMISSION_METRICS_FLUSH_SECS",
+                defaults.metrics_flush_secs,
+            ),
+        }
+    }
+}
+
+/// In-memory copies of the signal registry and of API key and vessel lookups
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// How often the signal registry is reloaded in case a change notification was missed
+    /// (`TELEMETRY_SIGNAL_CACHE_REFRESH_SECS`)
+    pub signal_refresh_interval_secs: u64,
+    /// How long a validated API key or active vessel is trusted before it is checked again
+    /// (`TELEMETRY_API_KEY_CACHE_TTL_SECS`)
+    pub api_key_ttl_secs: u64,
+    /// How often `api_keys.last_used_at` is written for keys used since the last write
+    /// (`TELEMETRY_API_KEY_LAST_USED_FLUSH_SECS`)
+    pub last_used_flush_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            signal_refresh_interval_secs: 60,
+            api_key_ttl_secs: 30,
+            last_used_flush_secs: 15,
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            signal_refresh_interval_secs: env_or(
+                "TELEMETRY_SIGNAL_CACHE_REFRESH_SECS",
+                defaults.signal_refresh_interval_secs,
+            ),
+            api_key_ttl_secs: env_or(
+                "TELEMETRY_API_KEY_CACHE_TTL_SECS",
+                defaults.api_key_ttl_secs,
+            ),
+            last_used_flush_secs: env_or(
+                "TELEMETRY_API_KEY_LAST_USED_FLUSH_SECS",
+                defaults.last_used_flush_secs,
+            ),
+        }
+    }
+}
+
+/// Write-ahead log settings; frames are only buffered on disk when `TELEMETRY_WAL_DIR` is set
+#[derive(Debug, Clone)]
+pub struct WalConfig {
+    /// Directory holding the log segments (`TELEMETRY_WAL_DIR`)
+    pub dir: PathBuf,
+    /// Log size at which new frames are refused with 503 (`TELEMETRY_WAL_MAX_BYTES`)
+    pub max_bytes: u64,
+    /// How often buffered frames are retried against the database (`TELEMETRY_WAL_REPLAY_INTERVAL_SECS`)
+    pub replay_interval_secs: u64,
+    /// How long a request waits for a pool connection before its frame is buffered
+    /// (`TELEMETRY_WAL_ACQUIRE_TIMEOUT_SECS`)
+    pub acquire_timeout_secs: u64,
+}
+
+impl WalConfig {
+    pub fn from_env() -> Option<Self> {
+        let dir = std::env::var("TELEMETRY_WAL_DIR").ok()?;
+
+        Some(Self {
+            dir: PathBuf::from(dir),
+            max_bytes: env_or("TELEMETRY_WAL_MAX_BYTES", 1024 * 1024 * 1024),
+            replay_interval_secs: env_or("TELEMETRY_WAL_REPLAY_INTERVAL_SECS", 5),
+            acquire_timeout_secs: env_or("TELEMETRY_WAL_ACQUIRE_TIMEOUT_SECS", 5),
+        })
+    }
+}
+
+/// MQTT bridge settings; the bridge only runs when `MQTT_HOST` is set
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    /// Broker host (`MQTT_HOST`)
+    pub host: String,
+    /// Broker port (`MQTT_PORT`)
+    pub port: u16,
+    /// Client ID; the broker keeps this client's session, including unacknowledged messages (`MQTT_CLIENT_ID`)
+    pub client_id: String,
+    /// Broker credentials (`MQTT_USERNAME`, `MQTT_PASSWORD`)
+    pub credentials: Option<(String, String)>,
+    /// Subscriptions are `{topic_prefix}/+/telemetry` (`MQTT_TOPIC_PREFIX`)
+    pub topic_prefix: String,
+    /// Keep-alive interval in seconds (`MQTT_KEEP_ALIVE_SECS`)
+    pub keep_alive_secs: u64,
+}
+
+impl MqttConfig {
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("MQTT_HOST").ok()?;
+
+        let credentials = match std::env::var("MQTT_USERNAME") {
+            Ok(username) => Some((username, std::env::var("MQTT_PASSWORD").unwrap_or_default())),
+            Err(_) => None,
+        };
+
+        Some(Self {
+            host,
+            port: env_or("MQTT_PORT", 1883),
+            client_id: env_or("MQTT_CLIENT_ID", "telemetry-service".to_string()),
+            credentials,
+            topic_prefix: env_or("MQTT_TOPIC_PREFIX", "vessels".to_string()),
+            keep_alive_secs: env_or("MQTT_KEEP_ALIVE_SECS", 30),
+        })
+    }
+
+    /// Topic filter covering every vessel
+    pub fn topic_filter(&self) -> String {
+        format!("{}/+/telemetry", self.topic_prefix)
+    }
+}
+
+/// UDP listener settings; the listener only runs when `UDP_PORT` is set
+#[derive(Debug, Clone)]
+pub struct UdpConfig {
+    /// Port to bind on all interfaces (`UDP_PORT`)
+    pub port: u16,
+    /// Datagrams processed at the same time (`UDP_MAX_CONCURRENT`)
+    pub max_concurrent: usize,
+    /// How often rejection counters are written to server_metrics (`UDP_METRICS_FLUSH_SECS`)
+    pub metrics_flush_secs: u64,
+}
+
+impl UdpConfig {
+    pub fn from_env() -> Option<Self> {
+        let port = std::env::var("UDP_PORT").ok()?;
+        let port = port
+            .parse()
+            .unwrap_or_else(|e| panic!("UDP_PORT has an invalid value '{}': {}", port, e));
+
+        Some(Self {
+            port,
+            max_concurrent: env_or("UDP_MAX_CONCURRENT", 64),
+            metrics_flush_secs: env_or("UDP_METRICS_FLUSH_SECS", 60),
+        })
+    }
+}
+
+/// gRPC server settings; the server only runs when `GRPC_PORT` is set
+#[derive(Debug, Clone)]
+pub struct GrpcConfig {
+    /// Port to bind on all interfaces (`GRPC_PORT`)
+    pub port: u16,
+}
+
+impl GrpcConfig {
+    pub fn from_env() -> Option<Self> {
+        let port = std::env::var("GRPC_PORT").ok()?;
+        let port = port
+            .parse()
+            .unwrap_or_else(|e| panic!("GRPC_PORT has an invalid value '{}': {}", port, e));
+
+        Some(Self { port })
+    }
+}
+
+/// Parses an environment variable, falling back to `default` when it is unset
+fn env_or<T>(name: &str, default: T) -> T
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|e| panic!("{} has an invalid value '{}': {}", name, value, e)),
+        Err(_) => default,
+    }
+}
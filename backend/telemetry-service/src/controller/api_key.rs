@@ -13,7 +13,11 @@ pub async fn create_api_key(
     let api_key = state
         .services()
         .auth_service()
-        .create_api_key(&payload.vessel_id, payload.expires_at)
+        .create_api_key(
+            &payload.vessel_id,
+            payload.expires_at,
+            payload.alarm_priority,
+        )
         .await?;
 
     Ok(Json(api_key))
@@ -0,0 +1,81 @@
+use crate::error::AppError;
+use crate::models::backfill::{
+    BackfillJobQuery, BackfillJobResponse, BackfillMapping, BackfillUploadQuery,
+};
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::{Multipart, Path, Query, State};
+use axum::http::StatusCode;
+use tracing::info;
+use uuid::Uuid;
+
+/// Accepts a `multipart/form-data` upload with a `mapping` JSON part and a `file` part.
+///
+/// The file is checked and the job recorded before responding; rows are imported in the
+/// background, so the response is `202 Accepted` with the job to poll.
+pub async fn upload_backfill(
+    State(state): State<AppState>,
+    Path(vessel_id): Path<String>,
+    Query(query): Query<BackfillUploadQuery>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<BackfillJobResponse>), AppError> {
+    info!("Backfill upload for vessel: {}", vessel_id);
+
+    let invalid_upload = |e: axum::extract::multipart::MultipartError| {
+        AppError::Validation(format!("Invalid multipart upload: {}", e))
+    };
+
+    let mut mapping = None;
+    let mut file = None;
+    while let Some(field) = multipart.next_field().await.map_err(invalid_upload)? {
+        match field.name() {
+            Some("mapping") => {
+                let bytes = field.bytes().await.map_err(invalid_upload)?;
+                let parsed: BackfillMapping = serde_json::from_slice(&bytes)
+                    .map_err(|e| AppError::Validation(format!("Invalid mapping: {}", e)))?;
+                mapping = Some(parsed);
+            }
+            Some("file") => {
+                let file_name = field.file_name().map(str::to_string);
+                let bytes = field.bytes().await.map_err(invalid_upload)?;
+                file = Some((file_name, bytes));
+            }
+            _ => {}
+        }
+    }
+
+    let mapping =
+        mapping.ok_or_else(|| AppError::Validation("Missing 'mapping' part".to_string()))?;
+    let (file_name, contents) =
+        file.ok_or_else(|| AppError::Validation("Missing 'file' part".to_string()))?;
+
+    let job = state
+        .services()
+        .backfill_service()
+        .start_import(&vessel_id, mapping, file_name, &contents, query.force)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+pub async fn get_backfill_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<BackfillJobResponse>, AppError> {
+    info!("Getting backfill job: {}", job_id);
+
+    let job = state.services().backfill_service().get_job(job_id).await?;
+
+    Ok(Json(job))
+}
+
+pub async fn list_backfill_jobs(
+    State(state): State<AppState>,
+    Query(query): Query<BackfillJobQuery>,
+) -> Result<Json<Vec<BackfillJobResponse>>, AppError> {
+    info!("Listing backfill jobs for vessel: {:?}", query.vessel_id);
+
+    let jobs = state.services().backfill_service().list_jobs(query).await?;
+
+    Ok(Json(jobs))
+}
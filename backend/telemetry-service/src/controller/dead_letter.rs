@@ -0,0 +1,78 @@
+use crate::error::AppError;
+use crate::models::dead_letter::{
+    DeadLetterPurgeQuery, DeadLetterPurgeResponse, DeadLetterQuery, DeadLetterResponse,
+    DeadLetterRetryResponse,
+};
+use crate::models::telemetry::TelemetryRequest;
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use tracing::info;
+use uuid::Uuid;
+
+pub async fn list_dead_letters(
+    State(state): State<AppState>,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<Json<Vec<DeadLetterResponse>>, AppError> {
+    info!("Listing dead letters for vessel: {:?}", query.vessel_id);
+
+    let dead_letters = state.services().dead_letter_service().list(query).await?;
+
+    Ok(Json(dead_letters))
+}
+
+pub async fn get_dead_letter(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DeadLetterResponse>, AppError> {
+    info!("Getting dead letter: {}", id);
+
+    let dead_letter = state.services().dead_letter_service().get(id).await?;
+
+    Ok(Json(dead_letter))
+}
+
+/// Stores a dead letter again; a JSON body replaces the stored payload
+pub async fn retry_dead_letter(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    payload: Option<Json<TelemetryRequest>>,
+) -> Result<Json<DeadLetterRetryResponse>, AppError> {
+    info!(
+        "Retrying dead letter: {} (edited: {})",
+        id,
+        payload.is_some()
+    );
+
+    let response = state
+        .services()
+        .dead_letter_service()
+        .retry(id, payload.map(|Json(request)| request))
+        .await?;
+
+    Ok(Json(response))
+}
+
+pub async fn delete_dead_letter(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    info!("Deleting dead letter: {}", id);
+
+    state.services().dead_letter_service().delete(id).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Dead letter deleted successfully"
+    })))
+}
+
+pub async fn purge_dead_letters(
+    State(state): State<AppState>,
+    Query(query): Query<DeadLetterPurgeQuery>,
+) -> Result<Json<DeadLetterPurgeResponse>, AppError> {
+    info!("Purging dead letters: {:?}", query);
+
+    let response = state.services().dead_letter_service().purge(query).await?;
+
+    Ok(Json(response))
+}
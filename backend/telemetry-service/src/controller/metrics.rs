@@ -1,5 +1,8 @@
 use crate::error::AppError;
-use crate::models::metrics::{MetricsQuery, MetricsResponse, MetricsSummary};
+use crate::models::metrics::{
+    AdmissionMetrics, CompressionSummary, MetricsQuery, MetricsResponse, MetricsSummary,
+    SignalCacheMetrics, UdpRejectionSummary,
+};
 use crate::state::AppState;
 use axum::Json;
 use axum::extract::State;
@@ -60,6 +63,63 @@ pub async fn get_all_vessels_metrics(
     Ok(Json(summaries))
 }
 
+// Get per-vessel compression savings for compressed request bodies
+pub async fn get_compression_metrics(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<MetricsQuery>,
+) -> Result<Json<Vec<CompressionSummary>>, AppError> {
+    info!("Fetching compression metrics");
+
+    let hours = query.hours.unwrap_or(24.0);
+    let summaries = state
+        .services()
+        .metrics_service()
+        .get_compression_summary(hours)
+        .await?;
+
+    Ok(Json(summaries))
+}
+
+// Get UDP datagrams rejected per vessel and reason
+pub async fn get_udp_metrics(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<MetricsQuery>,
+) -> Result<Json<Vec<UdpRejectionSummary>>, AppError> {
+    info!("Fetching UDP rejection metrics");
+
+    let hours = query.hours.unwrap_or(24.0);
+    let summaries = state
+        .services()
+        .metrics_service()
+        .get_udp_rejection_summary(hours)
+        .await?;
+
+    Ok(Json(summaries))
+}
+
+// Get in-flight ingestion requests, pool usage and shed counts
+pub async fn get_admission_metrics(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<MetricsQuery>,
+) -> Result<Json<AdmissionMetrics>, AppError> {
+    info!("Fetching admission metrics");
+
+    let hours = query.hours.unwrap_or(24.0);
+    let metrics = state.services().admission_service().metrics(hours).await?;
+
+    Ok(Json(metrics))
+}
+
+pub async fn get_signal_cache_metrics(
+    State(state): State<AppState>,
+) -> Result<Json<SignalCacheMetrics>, AppError> {
+    info!("Fetching signal cache metrics");
+
+    let metrics = state.services().signal_registry().metrics();
+
+    Ok(Json(metrics))
+}
+
 // Health check that includes basic metrics
 pub async fn health_with_metrics(
     State(state): State<AppState>,
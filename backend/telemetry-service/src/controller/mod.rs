@@ -1,4 +1,13 @@
 pub mod api_key;
+pub mod backfill;
+pub mod dead_letter;
 pub mod metrics;
+pub mod nmea;
+pub mod payload;
+pub mod session;
+pub mod signal;
+pub mod signal_mapping;
 pub mod telemetry;
 pub mod vessel;
+pub mod vessel_class;
+pub mod wal;
@@ -0,0 +1,82 @@
+use crate::error::AppError;
+use crate::models::telemetry::{DecodedIngestQuery, DecodedIngestResponse};
+use crate::state::AppState;
+use axum::extract::Query;
+use axum::{Extension, Json, extract::State};
+use chrono::Utc;
+use tracing::info;
+
+pub async fn ingest_nmea(
+    State(state): State<AppState>,
+    Extension(authenticated_vessel_id): Extension<String>,
+    Query(query): Query<DecodedIngestQuery>,
+    body: String,
+) -> Result<Json<DecodedIngestResponse>, AppError> {
+    info!(
+        "Received NMEA 0183 upload of {} bytes for vessel: {}",
+        body.len(),
+        authenticated_vessel_id
+    );
+
+    let response = state
+        .services()
+        .nmea_service()
+        .ingest_sentences(
+            &authenticated_vessel_id,
+            &body,
+            query.timestamp_utc.unwrap_or_else(Utc::now),
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+pub async fn ingest_nmea2000(
+    State(state): State<AppState>,
+    Extension(authenticated_vessel_id): Extension<String>,
+    Query(query): Query<DecodedIngestQuery>,
+    body: String,
+) -> Result<Json<DecodedIngestResponse>, AppError> {
+    info!(
+        "Received NMEA 2000 capture of {} bytes for vessel: {}",
+        body.len(),
+        authenticated_vessel_id
+    );
+
+    let response = state
+        .services()
+        .nmea_service()
+        .ingest_pgns(
+            &authenticated_vessel_id,
+            &body,
+            query.timestamp_utc.unwrap_or_else(Utc::now),
+        )
+        .await?;
+
+    Ok(Json(response))
+}
+
+pub async fn ingest_ais(
+    State(state): State<AppState>,
+    Extension(authenticated_vessel_id): Extension<String>,
+    Query(query): Query<DecodedIngestQuery>,
+    body: String,
+) -> Result<Json<DecodedIngestResponse>, AppError> {
+    info!(
+        "Received AIS upload of {} bytes for vessel: {}",
+        body.len(),
+        authenticated_vessel_id
+    );
+
+    let response = state
+        .services()
+        .nmea_service()
+        .ingest_ais(
+            &authenticated_vessel_id,
+            &body,
+            query.timestamp_utc.unwrap_or_else(Utc::now),
+        )
+        .await?;
+
+    Ok(Json(response))
+}
@@ -0,0 +1,94 @@
+use crate::error::AppError;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::HeaderMap;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+use serde::de::DeserializeOwned;
+
+/// Wire encodings accepted for telemetry payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadFormat {
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl PayloadFormat {
+    /// Picks the encoding from `Content-Type`; a missing header is treated as JSON
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, AppError> {
+        let content_type = match headers.get(CONTENT_TYPE) {
+            Some(value) => value
+                .to_str()
+                .map_err(|_| AppError::Validation("Invalid Content-Type header".to_string()))?,
+            None => return Ok(PayloadFormat::Json),
+        };
+
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        match mime.as_str() {
+            "application/json" => Ok(PayloadFormat::Json),
+            "application/cbor" => Ok(PayloadFormat::Cbor),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Ok(PayloadFormat::MessagePack)
+            }
+            m if m.starts_with("application/") && m.ends_with("+json") => Ok(PayloadFormat::Json),
+            _ => Err(AppError::UnsupportedMediaType(format!(
+                "Content-Type '{}' is not supported, use application/json, application/cbor or application/msgpack",
+                content_type
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayloadFormat::Json => "JSON",
+            PayloadFormat::Cbor => "CBOR",
+            PayloadFormat::MessagePack => "MessagePack",
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, AppError> {
+        let decoded = match self {
+            PayloadFormat::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            PayloadFormat::Cbor => ciborium::de::from_reader(bytes).map_err(|e| e.to_string()),
+            PayloadFormat::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+        };
+
+        decoded.map_err(|e| {
+            AppError::Validation(format!("Failed to decode {} body: {}", self.name(), e))
+        })
+    }
+}
+
+/// Request body decoded according to its `Content-Type`.
+///
+/// Drop-in replacement for `Json<T>`: JSON, CBOR and MessagePack all deserialize into the same
+/// `T`, so validation downstream cannot tell the encodings apart.
+pub struct Negotiated<T>(pub T);
+
+impl<T, S> FromRequest<S> for Negotiated<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let format =
+            PayloadFormat::from_headers(req.headers()).map_err(IntoResponse::into_response)?;
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        format
+            .decode(&bytes)
+            .map(Negotiated)
+            .map_err(IntoResponse::into_response)
+    }
+}
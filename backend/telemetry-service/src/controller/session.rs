@@ -0,0 +1,143 @@
+use crate::error::AppError;
+use crate::models::session::{
+    SessionCounters, SessionEndReason, SessionQuery, SessionReply, SessionResponse,
+    TRANSPORT_WEBSOCKET,
+};
+use crate::state::AppState;
+use axum::extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code};
+use axum::extract::{Query, State};
+use axum::response::Response;
+use axum::{Extension, Json};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Largest single WebSocket message accepted, matching the NDJSON line limit
+const MAX_MESSAGE_BYTES: usize = 1024 * 1024;
+
+/// Upgrades to a WebSocket session for the vessel the API key belongs to.
+///
+/// Each text message is a JSON `TelemetryRequest` and gets an `ack` or `error` reply in order.
+pub async fn telemetry_websocket(
+    State(state): State<AppState>,
+    Extension(authenticated_vessel_id): Extension<String>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    info!(
+        "WebSocket session requested for vessel: {}",
+        authenticated_vessel_id
+    );
+
+    let session_service = state.services().session_service();
+    let session_id = session_service
+        .open_session(&authenticated_vessel_id, TRANSPORT_WEBSOCKET)
+        .await?;
+
+    let failed_state = state.clone();
+    Ok(ws
+        .max_message_size(MAX_MESSAGE_BYTES)
+        .on_failed_upgrade(move |e| {
+            warn!(session_id = %session_id, error = %e, "WebSocket upgrade failed");
+            tokio::spawn(async move {
+                let _ = failed_state
+                    .services()
+                    .session_service()
+                    .close_session(
+                        session_id,
+                        SessionEndReason::ConnectionError,
+                        SessionCounters::default(),
+                    )
+                    .await;
+            });
+        })
+        .on_upgrade(move |socket| run_session(state, authenticated_vessel_id, session_id, socket)))
+}
+
+async fn run_session(state: AppState, vessel_id: String, session_id: Uuid, mut socket: WebSocket) {
+    let session_service = state.services().session_service();
+    let idle_timeout = session_service.idle_timeout();
+    let mut counters = SessionCounters::default();
+
+    let reason = loop {
+        // Pings count as activity, so a quiet vessel can keep its session open
+        let message = match tokio::time::timeout(idle_timeout, socket.recv()).await {
+            Err(_) => {
+                let _ = socket
+                    .send(Message::Close(Some(CloseFrame {
+                        code: close_code::NORMAL,
+                        reason: "idle timeout".into(),
+                    })))
+                    .await;
+                break SessionEndReason::IdleTimeout;
+            }
+            Ok(None) => break SessionEndReason::ClientClosed,
+            Ok(Some(Err(e))) => {
+                warn!(session_id = %session_id, error = %e, "WebSocket receive failed");
+                break SessionEndReason::ConnectionError;
+            }
+            Ok(Some(Ok(message))) => message,
+        };
+
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break SessionEndReason::ClientClosed,
+            Message::Ping(_) | Message::Pong(_) => continue,
+            Message::Binary(_) => {
+                counters.frames_received += 1;
+                counters.frames_rejected += 1;
+                let reply = SessionReply::Error {
+                    seq: counters.frames_received,
+                    frame_id: None,
+                    message: "Binary messages are not supported, send JSON text frames".to_string(),
+                    reason: None,
+                };
+                if send_reply(&mut socket, &reply).await.is_err() {
+                    break SessionEndReason::ConnectionError;
+                }
+                continue;
+            }
+        };
+
+        counters.frames_received += 1;
+        let reply = session_service
+            .ingest_frame(&vessel_id, counters.frames_received, text.as_str())
+            .await;
+        match reply {
+            SessionReply::Ack { .. } => counters.frames_accepted += 1,
+            SessionReply::Error { .. } => counters.frames_rejected += 1,
+        }
+
+        if send_reply(&mut socket, &reply).await.is_err() {
+            break SessionEndReason::ConnectionError;
+        }
+    };
+
+    if let Err(e) = session_service
+        .close_session(session_id, reason, counters)
+        .await
+    {
+        error!(session_id = %session_id, error = %e, "Failed to record session end");
+    }
+}
+
+async fn send_reply(socket: &mut WebSocket, reply: &SessionReply) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(reply).expect("session replies always serialize");
+    socket.send(Message::Text(json.into())).await
+}
+
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Query(query): Query<SessionQuery>,
+) -> Result<Json<Vec<SessionResponse>>, AppError> {
+    info!(
+        "Listing ingestion sessions for vessel: {:?}",
+        query.vessel_id
+    );
+
+    let sessions = state
+        .services()
+        .session_service()
+        .list_sessions(query)
+        .await?;
+
+    Ok(Json(sessions))
+}
@@ -0,0 +1,150 @@
+use crate::error::AppError;
+use crate::models::signal::{
+    CreateSignalRequest, SignalCatalogueDiff, SignalDefinition, SignalExportQuery,
+    SignalImportQuery, SignalQuery, SignalResponse,
+};
+use crate::services::signal_catalogue::CatalogueFormat;
+use crate::state::AppState;
+use axum::body::Bytes;
+use axum::extract::{Path, Query};
+use axum::http::HeaderMap;
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::IntoResponse;
+use axum::{Json, extract::State};
+use tracing::info;
+
+pub async fn create_signal(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateSignalRequest>,
+) -> Result<Json<SignalResponse>, AppError> {
+    info!("Creating signal: {}", payload.signal_name);
+
+    let signal = state
+        .services()
+        .signal_service()
+        .create_signal(payload)
+        .await?;
+
+    Ok(Json(signal))
+}
+
+pub async fn get_signal(
+    State(state): State<AppState>,
+    Path(signal_name): Path<String>,
+) -> Result<Json<SignalResponse>, AppError> {
+    info!("Getting signal: {}", signal_name);
+
+    let signal = state
+        .services()
+        .signal_service()
+        .get_signal(&signal_name)
+        .await?;
+
+    Ok(Json(signal))
+}
+
+pub async fn list_signals(
+    State(state): State<AppState>,
+    Query(query): Query<SignalQuery>,
+) -> Result<Json<Vec<SignalResponse>>, AppError> {
+    info!("Listing signals: {:?}", query);
+
+    let signals = state
+        .services()
+        .signal_service()
+        .list_signals(query)
+        .await?;
+
+    Ok(Json(signals))
+}
+
+pub async fn update_signal(
+    State(state): State<AppState>,
+    Path(signal_name): Path<String>,
+    Json(payload): Json<SignalDefinition>,
+) -> Result<Json<SignalResponse>, AppError> {
+    info!("Updating signal: {}", signal_name);
+
+    let signal = state
+        .services()
+        .signal_service()
+        .update_signal(&signal_name, payload)
+        .await?;
+
+    Ok(Json(signal))
+}
+
+pub async fn retire_signal(
+    State(state): State<AppState>,
+    Path(signal_name): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    info!("Retiring signal: {}", signal_name);
+
+    state
+        .services()
+        .signal_service()
+        .retire_signal(&signal_name)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Signal retired successfully"
+    })))
+}
+
+/// Takes a YAML (`application/yaml`) or CSV (`text/csv`) catalogue and returns what importing it
+/// changes; it is only written with `?apply=true`
+pub async fn import_signal_catalogue(
+    State(state): State<AppState>,
+    Query(query): Query<SignalImportQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<SignalCatalogueDiff>, AppError> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|_| AppError::Validation("Invalid Content-Type header".to_string()))
+        })
+        .transpose()?;
+    let format = CatalogueFormat::from_content_type(content_type)?;
+    info!(
+        "Importing {} signal catalogue (apply: {})",
+        format.name(),
+        query.apply
+    );
+
+    let entries = format.parse(&body)?;
+    let diff = state
+        .services()
+        .signal_service()
+        .import_catalogue(entries, query.apply)
+        .await?;
+
+    Ok(Json(diff))
+}
+
+pub async fn export_signal_catalogue(
+    State(state): State<AppState>,
+    Query(query): Query<SignalExportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let format = match query.format.as_deref() {
+        Some(name) => CatalogueFormat::from_name(name)?,
+        None => CatalogueFormat::Yaml,
+    };
+    info!("Exporting signal catalogue as {}", format.name());
+
+    let signals = state.services().signal_service().export_catalogue().await?;
+    let body = format.render(signals)?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"signals.{}\"", format.name()),
+            ),
+        ],
+        body,
+    ))
+}
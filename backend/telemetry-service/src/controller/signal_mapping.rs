@@ -0,0 +1,61 @@
+use crate::error::AppError;
+use crate::models::signal_mapping::{
+    SignalMappingQuery, SignalMappingResponse, UpsertSignalMappingRequest,
+};
+use crate::state::AppState;
+use axum::extract::{Path, Query};
+use axum::{Json, extract::State};
+use tracing::info;
+
+pub async fn list_signal_mappings(
+    State(state): State<AppState>,
+    Query(query): Query<SignalMappingQuery>,
+) -> Result<Json<Vec<SignalMappingResponse>>, AppError> {
+    info!("Listing signal mappings");
+
+    let mappings = state
+        .services()
+        .signal_mapping_service()
+        .list_mappings(query.source)
+        .await?;
+
+    Ok(Json(mappings))
+}
+
+pub async fn upsert_signal_mapping(
+    State(state): State<AppState>,
+    Json(payload): Json<UpsertSignalMappingRequest>,
+) -> Result<Json<SignalMappingResponse>, AppError> {
+    info!(
+        "Saving signal mapping {} {}.{} -> {}",
+        payload.source, payload.message_type, payload.field_name, payload.signal_name
+    );
+
+    let mapping = state
+        .services()
+        .signal_mapping_service()
+        .upsert_mapping(payload)
+        .await?;
+
+    Ok(Json(mapping))
+}
+
+pub async fn delete_signal_mapping(
+    State(state): State<AppState>,
+    Path((source, message_type, field_name)): Path<(String, String, String)>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    info!(
+        "Deleting signal mapping {} {}.{}",
+        source, message_type, field_name
+    );
+
+    state
+        .services()
+        .signal_mapping_service()
+        .delete_mapping(&source, &message_type, &field_name)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Signal mapping deleted successfully"
+    })))
+}
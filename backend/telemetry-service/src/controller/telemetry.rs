@@ -1,26 +1,274 @@
+use crate::controller::payload::Negotiated;
 use crate::error::AppError;
-use crate::models::telemetry::{TelemetryRequest, TelemetryResponse};
+use crate::models::telemetry::{
+    ClockDriftQuery, ClockDriftResponse, IngestionStatusResponse, TelemetryBatchResponse,
+    TelemetryRequest, TelemetryResponse, TelemetryStreamSummary,
+};
+use crate::proto::telemetry::TelemetryFrame;
+use crate::proto::{TELEMETRY_PROTO, TELEMETRY_SERVICE_PROTO};
+use crate::services::ingest_queue::Enqueued;
+use crate::services::telemetry::{TelemetryService, TelemetryStream};
 use crate::state::AppState;
+use axum::body::{Body, Bytes};
+use axum::extract::Path;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json, extract::State};
-use tracing::info;
+use futures_util::StreamExt;
+use prost::Message;
+use tracing::{info, warn};
+use uuid::Uuid;
 
+/// Longest single NDJSON line accepted by the streaming route
+const MAX_STREAM_LINE_BYTES: usize = 1024 * 1024;
+
+/// Ingests one frame; answers `202 Accepted` once it is queued when async ingestion is enabled
 pub async fn ingest_telemetry(
     State(state): State<AppState>,
     Extension(authenticated_vessel_id): Extension<String>,
-    Json(payload): Json<TelemetryRequest>,
-) -> Result<Json<TelemetryResponse>, AppError> {
+    headers: HeaderMap,
+    Negotiated(mut payload): Negotiated<TelemetryRequest>,
+) -> Result<Response, AppError> {
     info!("Received telemetry for vessel: {}", payload.vessel_id);
+    if let Some(header_frame_id) = headers.get("x-frame-id").and_then(|h| h.to_str().ok()) {
+        match &payload.frame_id {
+            Some(frame_id) if frame_id != header_frame_id => {
+                return Err(AppError::Validation(format!(
+                    "x-frame-id '{}' does not match frameId '{}' in payload",
+                    header_frame_id, frame_id
+                )));
+            }
+            _ => payload.frame_id = Some(header_frame_id.to_string()),
+        }
+    }
     if payload.vessel_id != authenticated_vessel_id {
         return Err(AppError::Forbidden(format!(
             "Vessel ID mismatch: authenticated as '{}' but payload contains '{}'",
             authenticated_vessel_id, payload.vessel_id
         )));
     }
+
+    if let Some(ingest_queue) = state.services().ingest_queue() {
+        return Ok(match ingest_queue.enqueue(payload).await? {
+            Enqueued::Accepted(response) => (StatusCode::ACCEPTED, Json(response)).into_response(),
+            Enqueued::Replayed(response) => Json(response).into_response(),
+        });
+    }
+
     let response = state
         .services()
         .telemetry_service()
         .ingest_telemetry(payload)
         .await?;
 
+    Ok(Json(response).into_response())
+}
+
+/// Final status of a frame accepted with `202`, for the authenticated vessel only
+pub async fn get_ingestion_status(
+    State(state): State<AppState>,
+    Extension(authenticated_vessel_id): Extension<String>,
+    Path(correlation_id): Path<Uuid>,
+) -> Result<Json<IngestionStatusResponse>, AppError> {
+    let ingest_queue = state
+        .services()
+        .ingest_queue()
+        .ok_or_else(|| AppError::NotFound("Async ingestion is not enabled".to_string()))?;
+
+    let status = ingest_queue
+        .get_status(&authenticated_vessel_id, correlation_id)
+        .await?;
+
+    Ok(Json(status))
+}
+
+pub async fn ingest_telemetry_batch(
+    State(state): State<AppState>,
+    Extension(authenticated_vessel_id): Extension<String>,
+    Negotiated(payload): Negotiated<Vec<TelemetryRequest>>,
+) -> Result<Json<TelemetryBatchResponse>, AppError> {
+    info!(
+        "Received telemetry batch of {} frames for vessel: {}",
+        payload.len(),
+        authenticated_vessel_id
+    );
+
+    let response = state
+        .services()
+        .telemetry_service()
+        .ingest_telemetry_batch(&authenticated_vessel_id, payload)
+        .await?;
+
+    Ok(Json(response))
+}
+
+pub async fn ingest_telemetry_proto(
+    State(state): State<AppState>,
+    Extension(authenticated_vessel_id): Extension<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<TelemetryResponse>, AppError> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+    if !matches!(
+        content_type.split(';').next().unwrap_or_default().trim(),
+        "application/x-protobuf" | "application/protobuf" | "application/vnd.google.protobuf"
+    ) {
+        return Err(AppError::UnsupportedMediaType(format!(
+            "Content-Type '{}' is not supported, use application/x-protobuf",
+            content_type
+        )));
+    }
+
+    let frame = TelemetryFrame::decode(body)
+        .map_err(|e| AppError::Validation(format!("Failed to decode protobuf body: {}", e)))?;
+
+    info!(
+        "Received protobuf telemetry for vessel: {}",
+        frame.vessel_id
+    );
+    if frame.vessel_id != authenticated_vessel_id {
+        return Err(AppError::Forbidden(format!(
+            "Vessel ID mismatch: authenticated as '{}' but payload contains '{}'",
+            authenticated_vessel_id, frame.vessel_id
+        )));
+    }
+
+    let response = state
+        .services()
+        .telemetry_service()
+        .ingest_telemetry_proto(frame)
+        .await?;
+
     Ok(Json(response))
 }
+
+pub async fn telemetry_proto_schema() -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/plain; charset=utf-8")],
+        TELEMETRY_PROTO,
+    )
+}
+
+pub async fn telemetry_service_proto_schema() -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/plain; charset=utf-8")],
+        TELEMETRY_SERVICE_PROTO,
+    )
+}
+
+// Estimate per-vessel clock drift from ingestion delays
+pub async fn get_clock_drift(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<ClockDriftQuery>,
+) -> Result<Json<Vec<ClockDriftResponse>>, AppError> {
+    info!("Fetching clock drift for vessel: {:?}", query.vessel_id);
+
+    let hours = query.hours.unwrap_or(24.0);
+    let threshold_secs = query.threshold_secs.unwrap_or(60.0);
+    let drift = state
+        .services()
+        .telemetry_service()
+        .get_clock_drift(query.vessel_id, hours, threshold_secs)
+        .await?;
+
+    Ok(Json(drift))
+}
+
+pub async fn ingest_telemetry_stream(
+    State(state): State<AppState>,
+    Extension(authenticated_vessel_id): Extension<String>,
+    body: Body,
+) -> Result<(StatusCode, Json<TelemetryStreamSummary>), AppError> {
+    info!(
+        "Received telemetry stream for vessel: {}",
+        authenticated_vessel_id
+    );
+
+    let service = state.services().telemetry_service();
+    let mut stream = service.open_stream(&authenticated_vessel_id).await?;
+
+    let mut chunks = body.into_data_stream();
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    let mut oversized = false;
+    let mut error = None;
+
+    'read: while let Some(chunk) = chunks.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                warn!(error = %e, "Failed to read telemetry stream body");
+                error = Some(format!("Failed to read request body: {}", e));
+                break;
+            }
+        };
+
+        let mut rest = &chunk[..];
+        while let Some(pos) = rest.iter().position(|b| *b == b'\n') {
+            line_number += 1;
+            oversized |= line.len() + pos > MAX_STREAM_LINE_BYTES;
+            if !oversized {
+                line.extend_from_slice(&rest[..pos]);
+            }
+            if let Err(e) =
+                process_stream_line(&service, &mut stream, line_number, &line, oversized).await
+            {
+                error = Some(e.to_string());
+                break 'read;
+            }
+            line.clear();
+            oversized = false;
+            rest = &rest[pos + 1..];
+        }
+
+        if !oversized {
+            line.extend_from_slice(rest);
+            if line.len() > MAX_STREAM_LINE_BYTES {
+                oversized = true;
+                line.clear();
+            }
+        }
+    }
+
+    // Final line without a trailing newline
+    if error.is_none() && (oversized || !line.is_empty()) {
+        line_number += 1;
+        if let Err(e) =
+            process_stream_line(&service, &mut stream, line_number, &line, oversized).await
+        {
+            error = Some(e.to_string());
+        }
+    }
+
+    let summary = service.finish_stream(stream, error).await;
+    let status = if summary.error.is_some() {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::OK
+    };
+
+    Ok((status, Json(summary)))
+}
+
+async fn process_stream_line(
+    service: &TelemetryService,
+    stream: &mut TelemetryStream,
+    line_number: usize,
+    line: &[u8],
+    oversized: bool,
+) -> Result<(), AppError> {
+    if oversized {
+        TelemetryService::reject_stream_line(
+            stream,
+            line_number,
+            format!("Line exceeds {} bytes", MAX_STREAM_LINE_BYTES),
+        );
+        return Ok(());
+    }
+
+    service.push_stream_line(stream, line_number, line).await
+}
@@ -0,0 +1,154 @@
+use crate::error::AppError;
+use crate::models::vessel::VesselResponse;
+use crate::models::vessel_class::{
+    AssignVesselClassRequest, CreateVesselClassRequest, SignalRangeRequest, SignalRangeResponse,
+    VesselClassDefinition, VesselClassResponse, VesselSignalsResponse,
+};
+use crate::state::AppState;
+use axum::extract::Path;
+use axum::{Json, extract::State};
+use tracing::info;
+
+pub async fn create_vessel_class(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateVesselClassRequest>,
+) -> Result<Json<VesselClassResponse>, AppError> {
+    info!("Creating vessel class: {}", payload.class_name);
+
+    let class = state
+        .services()
+        .vessel_class_service()
+        .create_class(payload)
+        .await?;
+
+    Ok(Json(class))
+}
+
+pub async fn get_vessel_class(
+    State(state): State<AppState>,
+    Path(class_name): Path<String>,
+) -> Result<Json<VesselClassResponse>, AppError> {
+    info!("Getting vessel class: {}", class_name);
+
+    let class = state
+        .services()
+        .vessel_class_service()
+        .get_class(&class_name)
+        .await?;
+
+    Ok(Json(class))
+}
+
+pub async fn list_vessel_classes(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<VesselClassResponse>>, AppError> {
+    info!("Listing vessel classes");
+
+    let classes = state
+        .services()
+        .vessel_class_service()
+        .list_classes()
+        .await?;
+
+    Ok(Json(classes))
+}
+
+pub async fn update_vessel_class(
+    State(state): State<AppState>,
+    Path(class_name): Path<String>,
+    Json(payload): Json<VesselClassDefinition>,
+) -> Result<Json<VesselClassResponse>, AppError> {
+    info!("Updating vessel class: {}", class_name);
+
+    let class = state
+        .services()
+        .vessel_class_service()
+        .update_class(&class_name, payload)
+        .await?;
+
+    Ok(Json(class))
+}
+
+pub async fn delete_vessel_class(
+    State(state): State<AppState>,
+    Path(class_name): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    info!("Deleting vessel class: {}", class_name);
+
+    state
+        .services()
+        .vessel_class_service()
+        .delete_class(&class_name)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Vessel class deleted successfully"
+    })))
+}
+
+pub async fn assign_vessel_class(
+    State(state): State<AppState>,
+    Path(vessel_id): Path<String>,
+    Json(payload): Json<AssignVesselClassRequest>,
+) -> Result<Json<VesselResponse>, AppError> {
+    info!(
+        "Assigning vessel {} class: {:?}",
+        vessel_id, payload.class_name
+    );
+
+    let vessel = state
+        .services()
+        .vessel_class_service()
+        .assign_class(&vessel_id, payload.class_name)
+        .await?;
+
+    Ok(Json(vessel))
+}
+
+pub async fn get_vessel_signals(
+    State(state): State<AppState>,
+    Path(vessel_id): Path<String>,
+) -> Result<Json<VesselSignalsResponse>, AppError> {
+    info!("Getting signals of vessel: {}", vessel_id);
+
+    let signals = state
+        .services()
+        .vessel_class_service()
+        .vessel_signals(&vessel_id)
+        .await?;
+
+    Ok(Json(signals))
+}
+
+pub async fn set_signal_range(
+    State(state): State<AppState>,
+    Path((vessel_id, signal_name)): Path<(String, String)>,
+    Json(payload): Json<SignalRangeRequest>,
+) -> Result<Json<SignalRangeResponse>, AppError> {
+    info!("Setting range of {} for vessel {}", signal_name, vessel_id);
+
+    let range = state
+        .services()
+        .vessel_class_service()
+        .set_signal_range(&vessel_id, &signal_name, payload)
+        .await?;
+
+    Ok(Json(range))
+}
+
+pub async fn clear_signal_range(
+    State(state): State<AppState>,
+    Path((vessel_id, signal_name)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    info!("Clearing range of {} for vessel {}", signal_name, vessel_id);
+
+    state
+        .services()
+        .vessel_class_service()
+        .clear_signal_range(&vessel_id, &signal_name)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Signal range override removed successfully"
+    })))
+}
@@ -0,0 +1,20 @@
+use crate::error::AppError;
+use crate::models::telemetry::WalBacklogResponse;
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::State;
+use tracing::info;
+
+/// Frames buffered on disk during a database outage and the state of their replay
+pub async fn get_wal_backlog(
+    State(state): State<AppState>,
+) -> Result<Json<WalBacklogResponse>, AppError> {
+    info!("Fetching write-ahead log backlog");
+
+    let wal_service = state
+        .services()
+        .wal_service()
+        .ok_or_else(|| AppError::NotFound("Write-ahead log is not enabled".to_string()))?;
+
+    Ok(Json(wal_service.backlog().await))
+}
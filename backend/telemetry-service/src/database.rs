@@ -1,9 +1,17 @@
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Error, Pool, Postgres};
+use std::time::Duration;
 
-pub async fn get_pool(database_url: String, connections: u32) -> Result<Pool<Postgres>, Error> {
-    PgPoolOptions::new()
-        .max_connections(connections)
-        .connect(&database_url)
-        .await
+/// Connects a pool; `acquire_timeout` overrides how long a query waits for a free connection
+pub async fn get_pool(
+    database_url: String,
+    connections: u32,
+    acquire_timeout: Option<Duration>,
+) -> Result<Pool<Postgres>, Error> {
+    let mut options = PgPoolOptions::new().max_connections(connections);
+    if let Some(acquire_timeout) = acquire_timeout {
+        options = options.acquire_timeout(acquire_timeout);
+    }
+
+    options.connect(&database_url).await
 }
@@ -0,0 +1,425 @@
+//! AIS AIVDM/AIVDO decoding for message types 1, 2, 3, 5, 18 and 24
+
+use crate::decoders::nmea0183::{self, SentenceError};
+use std::collections::HashMap;
+
+/// Message types this decoder understands
+pub const SUPPORTED_TYPES: [u8; 6] = [1, 2, 3, 5, 18, 24];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AisError {
+    BadChecksum(String),
+    Malformed(String),
+    UnsupportedMessage(u8),
+    IncompleteMessage,
+}
+
+impl AisError {
+    /// Reason code stored in telemetry_filtered
+    pub fn reason(&self) -> &'static str {
+        match self {
+            AisError::BadChecksum(_) => "ais_bad_checksum",
+            AisError::Malformed(_) => "ais_malformed_sentence",
+            AisError::UnsupportedMessage(_) => "ais_unsupported_message",
+            AisError::IncompleteMessage => "ais_incomplete_message",
+        }
+    }
+}
+
+impl std::fmt::Display for AisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AisError::BadChecksum(msg) => write!(f, "{}", msg),
+            AisError::Malformed(msg) => write!(f, "malformed AIS sentence: {}", msg),
+            AisError::UnsupportedMessage(kind) => {
+                write!(f, "unsupported AIS message type {}", kind)
+            }
+            AisError::IncompleteMessage => write!(f, "multi-sentence AIS message is incomplete"),
+        }
+    }
+}
+
+impl From<SentenceError> for AisError {
+    fn from(e: SentenceError) -> Self {
+        match e {
+            SentenceError::BadChecksum { .. } | SentenceError::MissingChecksum => {
+                AisError::BadChecksum(e.to_string())
+            }
+            other => AisError::Malformed(other.to_string()),
+        }
+    }
+}
+
+/// ETA as broadcast: AIS carries no year
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eta {
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+/// Static and voyage data from types 5 and 24; absent values are `None`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StaticData {
+    pub callsign: Option<String>,
+    pub ship_name: Option<String>,
+    pub ship_type: Option<u8>,
+    pub destination: Option<String>,
+    pub eta: Option<Eta>,
+    pub draught_m: Option<f64>,
+}
+
+/// Types 1, 2, 3 and 18
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionReport {
+    /// `latitude`, `longitude`, `sog_knots`, `cog_true`, `heading_true` and, for class A,
+    /// `nav_status`
+    pub fields: Vec<(String, f64)>,
+    /// Second of the UTC minute the report was generated; `None` when not available
+    pub utc_second: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AisBody {
+    Position(PositionReport),
+    Static(StaticData),
+    /// Any other type; only the header is decoded
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AisMessage {
+    /// `true` for AIVDO, the transponder reporting its own ship
+    pub own_ship: bool,
+    pub message_type: u8,
+    pub mmsi: u32,
+    pub body: AisBody,
+}
+
+struct PartialMessage {
+    total: u8,
+    received: u8,
+    own_ship: bool,
+    payload: String,
+}
+
+/// Decodes sentences one at a time, holding multi-sentence messages until complete
+#[derive(Default)]
+pub struct Decoder {
+    pending: HashMap<(String, String), PartialMessage>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `Ok(None)` for a fragment that does not complete a message yet
+    pub fn push_line(&mut self, line: &str) -> Result<Option<AisMessage>, AisError> {
+        let payload = nmea0183::verify_checksum(line)?;
+        let fields: Vec<&str> = payload.split(',').collect();
+        if fields.len() != 7 {
+            return Err(AisError::Malformed(format!(
+                "expected 7 fields, got {}",
+                fields.len()
+            )));
+        }
+
+        let own_ship = match fields[0].get(2..) {
+            Some("VDO") => true,
+            Some("VDM") => false,
+            _ => {
+                return Err(AisError::Malformed(format!(
+                    "'{}' is not a VDM or VDO sentence",
+                    fields[0]
+                )));
+            }
+        };
+        let total = parse_count(fields[1], "fragment count")?;
+        let number = parse_count(fields[2], "fragment number")?;
+        if number > total {
+            return Err(AisError::Malformed(format!(
+                "fragment {} of {}",
+                number, total
+            )));
+        }
+
+        if total == 1 {
+            return decode_payload(fields[5], own_ship).map(Some);
+        }
+
+        let key = (fields[3].to_string(), fields[4].to_string());
+        if number == 1 {
+            let restarted = self.pending.insert(
+                key,
+                PartialMessage {
+                    total,
+                    received: 1,
+                    own_ship,
+                    payload: fields[5].to_string(),
+                },
+            );
+            return match restarted {
+                Some(_) => Err(AisError::IncompleteMessage),
+                None => Ok(None),
+            };
+        }
+
+        let Some(partial) = self.pending.get_mut(&key) else {
+            return Err(AisError::IncompleteMessage);
+        };
+        if partial.total != total || partial.received + 1 != number {
+            self.pending.remove(&key);
+            return Err(AisError::IncompleteMessage);
+        }
+        partial.received = number;
+        partial.payload.push_str(fields[5]);
+
+        if number < total {
+            return Ok(None);
+        }
+        let partial = self.pending.remove(&key).expect("entry checked above");
+        decode_payload(&partial.payload, partial.own_ship).map(Some)
+    }
+
+    /// Multi-sentence messages still missing fragments when the input ended
+    pub fn finish(self) -> Vec<AisError> {
+        self.pending
+            .into_values()
+            .map(|_| AisError::IncompleteMessage)
+            .collect()
+    }
+}
+
+fn parse_count(value: &str, name: &str) -> Result<u8, AisError> {
+    match value.parse::<u8>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(AisError::Malformed(format!(
+            "{} '{}' is invalid",
+            name, value
+        ))),
+    }
+}
+
+fn decode_payload(payload: &str, own_ship: bool) -> Result<AisMessage, AisError> {
+    let bits = Bits::unarmor(payload)?;
+    if bits.len() < 38 {
+        return Err(AisError::Malformed(
+            "payload is shorter than the header".to_string(),
+        ));
+    }
+
+    let message_type = bits.uint(0, 6) as u8;
+    let mmsi = bits.uint(8, 30) as u32;
+
+    let body = match message_type {
+        1..=3 => {
+            bits.require(message_type, 168)?;
+            AisBody::Position(class_a_position(&bits))
+        }
+        18 => {
+            bits.require(message_type, 168)?;
+            AisBody::Position(class_b_position(&bits))
+        }
+        5 => {
+            bits.require(message_type, 420)?;
+            AisBody::Static(static_voyage(&bits))
+        }
+        24 => {
+            bits.require(message_type, 160)?;
+            AisBody::Static(static_class_b(&bits))
+        }
+        _ => AisBody::Other,
+    };
+
+    Ok(AisMessage {
+        own_ship,
+        message_type,
+        mmsi,
+        body,
+    })
+}
+
+fn class_a_position(bits: &Bits) -> PositionReport {
+    let status = bits.uint(38, 4);
+    position_report(
+        bits,
+        PositionLayout {
+            sog: 50,
+            lon: 61,
+            lat: 89,
+            cog: 116,
+            heading: 128,
+            second: 137,
+        },
+        (status != 15).then_some(status as f64),
+    )
+}
+
+fn class_b_position(bits: &Bits) -> PositionReport {
+    position_report(
+        bits,
+        PositionLayout {
+            sog: 46,
+            lon: 57,
+            lat: 85,
+            cog: 112,
+            heading: 124,
+            second: 133,
+        },
+        None,
+    )
+}
+
+/// Bit offsets of the fields shared by class A and class B position reports
+struct PositionLayout {
+    sog: usize,
+    lon: usize,
+    lat: usize,
+    cog: usize,
+    heading: usize,
+    second: usize,
+}
+
+fn position_report(bits: &Bits, layout: PositionLayout, nav_status: Option<f64>) -> PositionReport {
+    // Values equal to the "not available" code are left out
+    let longitude = bits.int(layout.lon, 28);
+    let latitude = bits.int(layout.lat, 27);
+    let sog = bits.uint(layout.sog, 10);
+    let cog = bits.uint(layout.cog, 12);
+    let heading = bits.uint(layout.heading, 9);
+
+    let candidates = [
+        (
+            "latitude",
+            (latitude != 91 * 600_000).then_some(latitude as f64 / 600_000.0),
+        ),
+        (
+            "longitude",
+            (longitude != 181 * 600_000).then_some(longitude as f64 / 600_000.0),
+        ),
+        ("sog_knots", (sog != 1023).then_some(sog as f64 / 10.0)),
+        ("cog_true", (cog != 3600).then_some(cog as f64 / 10.0)),
+        ("heading_true", (heading != 511).then_some(heading as f64)),
+        ("nav_status", nav_status),
+    ];
+
+    // 60 and above mean the time stamp is not available or the position is not from a fix
+    let second = bits.uint(layout.second, 6) as u32;
+
+    PositionReport {
+        fields: candidates
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| (name.to_string(), v)))
+            .collect(),
+        utc_second: (second < 60).then_some(second),
+    }
+}
+
+fn static_voyage(bits: &Bits) -> StaticData {
+    let month = bits.uint(274, 4) as u32;
+    let day = bits.uint(278, 5) as u32;
+    let hour = bits.uint(283, 5) as u32;
+    let minute = bits.uint(288, 6) as u32;
+    let draught = bits.uint(294, 8);
+
+    StaticData {
+        callsign: bits.text(70, 7),
+        ship_name: bits.text(112, 20),
+        ship_type: non_zero(bits.uint(232, 8)),
+        destination: bits.text(302, 20),
+        eta: (month != 0 && day != 0 && hour < 24 && minute < 60).then_some(Eta {
+            month,
+            day,
+            hour,
+            minute,
+        }),
+        draught_m: (draught != 0).then_some(draught as f64 / 10.0),
+    }
+}
+
+fn static_class_b(bits: &Bits) -> StaticData {
+    match bits.uint(38, 2) {
+        0 => StaticData {
+            ship_name: bits.text(40, 20),
+            ..Default::default()
+        },
+        _ => StaticData {
+            ship_type: non_zero(bits.uint(40, 8)),
+            callsign: bits.text(90, 7),
+            ..Default::default()
+        },
+    }
+}
+
+fn non_zero(value: u64) -> Option<u8> {
+    (value != 0).then_some(value as u8)
+}
+
+/// The de-armored payload, one bool per bit
+struct Bits(Vec<bool>);
+
+impl Bits {
+    fn unarmor(payload: &str) -> Result<Self, AisError> {
+        let mut bits = Vec::with_capacity(payload.len() * 6);
+        for c in payload.bytes() {
+            if !(48..=119).contains(&c) || (88..96).contains(&c) {
+                return Err(AisError::Malformed(format!(
+                    "'{}' is not a valid payload character",
+                    c as char
+                )));
+            }
+            let mut value = c - 48;
+            if value > 40 {
+                value -= 8;
+            }
+            bits.extend((0..6).rev().map(|shift| (value >> shift) & 1 == 1));
+        }
+        Ok(Self(bits))
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn require(&self, message_type: u8, length: usize) -> Result<(), AisError> {
+        if self.len() < length {
+            return Err(AisError::Malformed(format!(
+                "type {} needs {} bits, got {}",
+                message_type,
+                length,
+                self.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Unsigned field; bits past the end read as zero (senders may drop trailing fill)
+    fn uint(&self, start: usize, width: usize) -> u64 {
+        (start..start + width).fold(0, |acc, i| {
+            (acc << 1) | u64::from(self.0.get(i).copied().unwrap_or(false))
+        })
+    }
+
+    fn int(&self, start: usize, width: usize) -> i64 {
+        let raw = self.uint(start, width) as i64;
+        if raw & (1 << (width - 1)) != 0 {
+            raw - (1 << width)
+        } else {
+            raw
+        }
+    }
+
+    /// Six-bit text, trimmed of `@` padding and spaces; `None` when blank
+    fn text(&self, start: usize, chars: usize) -> Option<String> {
+        let text: String = (0..chars)
+            .map(|i| {
+                let value = self.uint(start + i * 6, 6) as u8;
+                (if value < 32 { value + 64 } else { value }) as char
+            })
+            .collect();
+        let text = text.trim_end_matches(['@', ' ']).trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
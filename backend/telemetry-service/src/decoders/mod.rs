@@ -0,0 +1,8 @@
+//! Decoders that turn onboard wire protocols into named numeric fields.
+//!
+//! Decoders are pure: they know nothing about vessels or the signal registry. Decoded fields are
+//! mapped to registered signals through `signal_source_mappings` by the service layer.
+
+pub mod ais;
+pub mod nmea0183;
+pub mod nmea2000;
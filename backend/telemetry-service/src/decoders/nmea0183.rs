@@ -0,0 +1,370 @@
+//! NMEA 0183 sentence parsing for GGA, RMC, ZDA, VTG, HDT, MWV, DPT and XDR
+
+use chrono::{NaiveDate, NaiveTime};
+
+/// Sentence types this decoder understands
+pub const SUPPORTED_SENTENCES: [&str; 8] = ["GGA", "RMC", "ZDA", "VTG", "HDT", "MWV", "DPT", "XDR"];
+
+const KMH_TO_KNOTS: f64 = 1.0 / 1.852;
+const MS_TO_KNOTS: f64 = 3600.0 / 1852.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SentenceError {
+    BadChecksum { expected: u8, actual: u8 },
+    MissingChecksum,
+    Malformed(String),
+    UnknownSentence(String),
+}
+
+impl SentenceError {
+    /// Reason code stored in telemetry_filtered
+    pub fn reason(&self) -> &'static str {
+        match self {
+            SentenceError::BadChecksum { .. } | SentenceError::MissingChecksum => {
+                "nmea_bad_checksum"
+            }
+            SentenceError::Malformed(_) => "nmea_malformed_sentence",
+            SentenceError::UnknownSentence(_) => "nmea_unknown_sentence",
+        }
+    }
+}
+
+impl std::fmt::Display for SentenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SentenceError::BadChecksum { expected, actual } => write!(
+                f,
+                "checksum mismatch: sentence says {:02X}, computed {:02X}",
+                expected, actual
+            ),
+            SentenceError::MissingChecksum => write!(f, "sentence has no checksum"),
+            SentenceError::Malformed(msg) => write!(f, "malformed sentence: {}", msg),
+            SentenceError::UnknownSentence(kind) => write!(f, "unsupported sentence type {}", kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedSentence {
+    pub talker: String,
+    pub sentence_type: String,
+    /// Decoded numeric fields; empty NMEA fields are omitted
+    pub fields: Vec<(String, f64)>,
+    /// UTC time of day reported by GGA, RMC and ZDA
+    pub time: Option<NaiveTime>,
+    /// UTC date reported by RMC and ZDA
+    pub date: Option<NaiveDate>,
+}
+
+/// Best-effort label for a sentence that failed to decode, e.g. `GPGGA`
+pub fn sentence_label(line: &str) -> String {
+    line.trim()
+        .trim_start_matches(['$', '!'])
+        .split([',', '*'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .take(10)
+        .collect()
+}
+
+/// Verifies the `*hh` checksum and returns the payload between the start delimiter and `*`
+pub fn verify_checksum(line: &str) -> Result<&str, SentenceError> {
+    let line = line.trim();
+    let body = line
+        .strip_prefix('$')
+        .or_else(|| line.strip_prefix('!'))
+        .ok_or_else(|| {
+            SentenceError::Malformed("missing '$' or '!' start delimiter".to_string())
+        })?;
+
+    let (payload, checksum) = body
+        .rsplit_once('*')
+        .ok_or(SentenceError::MissingChecksum)?;
+    let expected = u8::from_str_radix(checksum.trim(), 16)
+        .map_err(|_| SentenceError::Malformed(format!("invalid checksum '{}'", checksum)))?;
+    let actual = payload.bytes().fold(0u8, |acc, b| acc ^ b);
+
+    if expected != actual {
+        return Err(SentenceError::BadChecksum { expected, actual });
+    }
+
+    Ok(payload)
+}
+
+/// Checks and decodes one sentence such as `$GPHDT,274.07,T*03`
+pub fn decode_sentence(line: &str) -> Result<DecodedSentence, SentenceError> {
+    let payload = verify_checksum(line)?;
+    let mut parts = payload.split(',');
+    let address = parts.next().unwrap_or_default();
+    let fields: Vec<&str> = parts.collect();
+
+    if address.len() != 5 || !address.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(SentenceError::UnknownSentence(address.to_string()));
+    }
+    let (talker, sentence_type) = address.split_at(2);
+
+    let (time, date) = match sentence_type {
+        "GGA" => (time_of_day(&fields, 0)?, None),
+        "RMC" => (time_of_day(&fields, 0)?, rmc_date(&fields, 8)?),
+        "ZDA" => (time_of_day(&fields, 0)?, zda_date(&fields)?),
+        _ => (None, None),
+    };
+
+    let decoded = match sentence_type {
+        "GGA" => decode_gga(&fields)?,
+        "RMC" => decode_rmc(&fields)?,
+        "ZDA" => Vec::new(),
+        "VTG" => decode_vtg(&fields)?,
+        "HDT" => decode_hdt(&fields)?,
+        "MWV" => decode_mwv(&fields)?,
+        "DPT" => decode_dpt(&fields)?,
+        "XDR" => decode_xdr(&fields)?,
+        _ => return Err(SentenceError::UnknownSentence(sentence_type.to_string())),
+    };
+
+    Ok(DecodedSentence {
+        talker: talker.to_string(),
+        sentence_type: sentence_type.to_string(),
+        fields: decoded,
+        time,
+        date,
+    })
+}
+
+fn field<'a>(fields: &[&'a str], index: usize) -> &'a str {
+    fields.get(index).copied().unwrap_or_default().trim()
+}
+
+/// Parses a numeric field; empty fields decode to `None`
+fn number(fields: &[&str], index: usize, name: &str) -> Result<Option<f64>, SentenceError> {
+    let value = field(fields, index);
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse::<f64>()
+        .map(Some)
+        .map_err(|_| SentenceError::Malformed(format!("{} '{}' is not a number", name, value)))
+}
+
+/// Converts `ddmm.mmmm` / `dddmm.mmmm` plus hemisphere into signed decimal degrees
+fn coordinate(
+    fields: &[&str],
+    index: usize,
+    name: &str,
+    negative: char,
+) -> Result<Option<f64>, SentenceError> {
+    let Some(raw) = number(fields, index, name)? else {
+        return Ok(None);
+    };
+    let degrees = (raw / 100.0).trunc();
+    let minutes = raw - degrees * 100.0;
+    let value = degrees + minutes / 60.0;
+
+    let hemisphere = field(fields, index + 1);
+    match hemisphere.chars().next() {
+        Some(c) if c == negative => Ok(Some(-value)),
+        Some(_) => Ok(Some(value)),
+        None => Err(SentenceError::Malformed(format!(
+            "{} has no hemisphere",
+            name
+        ))),
+    }
+}
+
+/// Parses `hhmmss` or `hhmmss.ss`; an empty field decodes to `None`
+fn time_of_day(fields: &[&str], index: usize) -> Result<Option<NaiveTime>, SentenceError> {
+    let value = field(fields, index);
+    if value.is_empty() {
+        return Ok(None);
+    }
+    NaiveTime::parse_from_str(value, "%H%M%S%.f")
+        .map(Some)
+        .map_err(|_| SentenceError::Malformed(format!("time '{}' is not hhmmss.ss", value)))
+}
+
+/// Parses the RMC `ddmmyy` date; two-digit years from 80 on are taken as 19xx
+fn rmc_date(fields: &[&str], index: usize) -> Result<Option<NaiveDate>, SentenceError> {
+    let value = field(fields, index);
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || SentenceError::Malformed(format!("date '{}' is not ddmmyy", value));
+    if value.len() != 6 || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let pair = |at: usize| value[at..at + 2].parse::<u32>().unwrap_or_default();
+    let year = if pair(4) >= 80 { 1900 } else { 2000 } + pair(4) as i32;
+
+    NaiveDate::from_ymd_opt(year, pair(2), pair(0))
+        .map(Some)
+        .ok_or_else(invalid)
+}
+
+/// Parses the ZDA `dd,mm,yyyy` fields that follow the time
+fn zda_date(fields: &[&str]) -> Result<Option<NaiveDate>, SentenceError> {
+    let (Some(day), Some(month), Some(year)) = (
+        number(fields, 1, "day")?,
+        number(fields, 2, "month")?,
+        number(fields, 3, "year")?,
+    ) else {
+        return Ok(None);
+    };
+
+    NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+        .map(Some)
+        .ok_or_else(|| {
+            SentenceError::Malformed(format!("date {}-{}-{} does not exist", year, month, day))
+        })
+}
+
+fn push(out: &mut Vec<(String, f64)>, name: &str, value: Option<f64>) {
+    if let Some(value) = value {
+        out.push((name.to_string(), value));
+    }
+}
+
+fn decode_gga(fields: &[&str]) -> Result<Vec<(String, f64)>, SentenceError> {
+    let mut out = Vec::new();
+    push(
+        &mut out,
+        "latitude",
+        coordinate(fields, 1, "latitude", 'S')?,
+    );
+    push(
+        &mut out,
+        "longitude",
+        coordinate(fields, 3, "longitude", 'W')?,
+    );
+    push(&mut out, "fix_quality", number(fields, 5, "fix quality")?);
+    push(&mut out, "satellites", number(fields, 6, "satellites")?);
+    push(&mut out, "hdop", number(fields, 7, "hdop")?);
+    push(&mut out, "altitude", number(fields, 8, "altitude")?);
+    Ok(out)
+}
+
+fn decode_rmc(fields: &[&str]) -> Result<Vec<(String, f64)>, SentenceError> {
+    let mut out = Vec::new();
+    let status = match field(fields, 1) {
+        "A" => 1.0,
+        "V" => 0.0,
+        other => {
+            return Err(SentenceError::Malformed(format!(
+                "RMC status '{}' is not A or V",
+                other
+            )));
+        }
+    };
+    out.push(("status_valid".to_string(), status));
+    push(
+        &mut out,
+        "latitude",
+        coordinate(fields, 2, "latitude", 'S')?,
+    );
+    push(
+        &mut out,
+        "longitude",
+        coordinate(fields, 4, "longitude", 'W')?,
+    );
+    push(
+        &mut out,
+        "sog_knots",
+        number(fields, 6, "speed over ground")?,
+    );
+    push(
+        &mut out,
+        "cog_true",
+        number(fields, 7, "course over ground")?,
+    );
+    let variation = number(fields, 9, "magnetic variation")?
+        .map(|v| if field(fields, 10) == "W" { -v } else { v });
+    push(&mut out, "magnetic_variation", variation);
+    Ok(out)
+}
+
+fn decode_vtg(fields: &[&str]) -> Result<Vec<(String, f64)>, SentenceError> {
+    let mut out = Vec::new();
+    push(&mut out, "cog_true", number(fields, 0, "true course")?);
+    push(
+        &mut out,
+        "cog_magnetic",
+        number(fields, 2, "magnetic course")?,
+    );
+    push(&mut out, "sog_knots", number(fields, 4, "speed in knots")?);
+    push(&mut out, "sog_kmh", number(fields, 6, "speed in km/h")?);
+    Ok(out)
+}
+
+fn decode_hdt(fields: &[&str]) -> Result<Vec<(String, f64)>, SentenceError> {
+    let mut out = Vec::new();
+    push(&mut out, "heading_true", number(fields, 0, "heading")?);
+    Ok(out)
+}
+
+fn decode_mwv(fields: &[&str]) -> Result<Vec<(String, f64)>, SentenceError> {
+    let reference = match field(fields, 1) {
+        "R" => "relative",
+        "T" => "true",
+        other => {
+            return Err(SentenceError::Malformed(format!(
+                "MWV reference '{}' is not R or T",
+                other
+            )));
+        }
+    };
+    if field(fields, 4) != "A" {
+        return Err(SentenceError::Malformed(
+            "MWV data is flagged invalid".to_string(),
+        ));
+    }
+
+    let speed = number(fields, 2, "wind speed")?
+        .map(|speed| match field(fields, 3) {
+            "K" => Ok(speed * KMH_TO_KNOTS),
+            "M" => Ok(speed * MS_TO_KNOTS),
+            "N" => Ok(speed),
+            other => Err(SentenceError::Malformed(format!(
+                "MWV speed unit '{}' is not K, M or N",
+                other
+            ))),
+        })
+        .transpose()?;
+
+    let mut out = Vec::new();
+    push(
+        &mut out,
+        &format!("wind_angle_{}", reference),
+        number(fields, 0, "wind angle")?,
+    );
+    push(&mut out, &format!("wind_speed_{}", reference), speed);
+    Ok(out)
+}
+
+fn decode_dpt(fields: &[&str]) -> Result<Vec<(String, f64)>, SentenceError> {
+    let mut out = Vec::new();
+    push(&mut out, "depth", number(fields, 0, "depth")?);
+    push(&mut out, "offset", number(fields, 1, "transducer offset")?);
+    Ok(out)
+}
+
+/// XDR carries repeated `type,value,unit,name` quadruplets; each is keyed by its transducer name
+fn decode_xdr(fields: &[&str]) -> Result<Vec<(String, f64)>, SentenceError> {
+    if fields.is_empty() || !fields.len().is_multiple_of(4) {
+        return Err(SentenceError::Malformed(format!(
+            "XDR has {} fields, expected groups of 4",
+            fields.len()
+        )));
+    }
+
+    let mut out = Vec::new();
+    for (index, group) in fields.chunks(4).enumerate() {
+        let name = match group[3].trim() {
+            "" => format!("{}{}", group[0].trim(), index),
+            name => name.to_string(),
+        };
+        push(&mut out, &name, number(group, 1, "transducer value")?);
+    }
+    Ok(out)
+}
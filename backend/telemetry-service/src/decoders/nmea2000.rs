@@ -0,0 +1,634 @@
+//! NMEA 2000 / J1939 PGN decoding from candump and Actisense (canboat) text captures
+//!
+//! Supported captures, one CAN frame or message per line:
+//! - candump log: `(1697530000.123456) can0 09F20083#00E02E0000FFFF7F`
+//! - candump console: `can0  09F20083   [8]  00 E0 2E 00 00 FF FF 7F`
+//! - Actisense / canboat: `2026-10-17T10:00:00.000Z,2,127488,131,255,8,00,e0,2e,00,00,ff,ff,7f`
+//!
+//! Fast-packet PGNs arrive from candump as several CAN frames and are reassembled here; the
+//! Actisense format already carries the complete message. Capture timestamps are kept when the
+//! line has one.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::collections::HashMap;
+
+/// PGNs this decoder understands
+pub const SUPPORTED_PGNS: [u32; 10] = [
+    61444, 65262, 65263, 65266, 127488, 127489, 127505, 127508, 128267, 130312,
+];
+
+/// Supported PGNs sent as NMEA 2000 fast packets
+const FAST_PACKET_PGNS: [u32; 1] = [127489];
+
+const KELVIN_OFFSET: f64 = 273.15;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageError {
+    Malformed(String),
+    UnsupportedPgn(u32),
+    IncompleteFastPacket(u32),
+}
+
+impl MessageError {
+    /// Reason code stored in telemetry_filtered
+    pub fn reason(&self) -> &'static str {
+        match self {
+            MessageError::Malformed(_) => "n2k_malformed_frame",
+            MessageError::UnsupportedPgn(_) => "n2k_unsupported_pgn",
+            MessageError::IncompleteFastPacket(_) => "n2k_incomplete_fast_packet",
+        }
+    }
+
+    /// PGN the error refers to, when it got that far
+    pub fn pgn(&self) -> Option<u32> {
+        match self {
+            MessageError::Malformed(_) => None,
+            MessageError::UnsupportedPgn(pgn) | MessageError::IncompleteFastPacket(pgn) => {
+                Some(*pgn)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for MessageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageError::Malformed(msg) => write!(f, "malformed frame: {}", msg),
+            MessageError::UnsupportedPgn(pgn) => write!(f, "unsupported PGN {}", pgn),
+            MessageError::IncompleteFastPacket(pgn) => {
+                write!(f, "fast packet for PGN {} is incomplete", pgn)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedMessage {
+    pub pgn: u32,
+    pub source: u8,
+    /// Decoded fields named `<device><instance>.<quantity>`; unavailable values are omitted
+    pub fields: Vec<(String, f64)>,
+    /// Capture time of the line that completed the message
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// One capture line split into its parts
+struct CaptureLine {
+    pgn: u32,
+    source: u8,
+    data: Vec<u8>,
+    /// Actisense lines carry the whole message, fast packet or not
+    reassembled: bool,
+    timestamp: Option<DateTime<Utc>>,
+}
+
+struct PartialPacket {
+    sequence: u8,
+    next_frame: u8,
+    length: usize,
+    data: Vec<u8>,
+}
+
+/// Decodes a capture line by line, holding fast packets until their last frame arrives
+#[derive(Default)]
+pub struct Decoder {
+    pending: HashMap<(u8, u32), PartialPacket>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `Ok(None)` for a fast-packet frame that does not complete a message yet.
+    ///
+    /// A restarted fast packet reports the abandoned one as incomplete.
+    pub fn push_line(&mut self, line: &str) -> Result<Option<DecodedMessage>, MessageError> {
+        let CaptureLine {
+            pgn,
+            source,
+            data,
+            reassembled,
+            timestamp,
+        } = parse_line(line)?;
+
+        if !SUPPORTED_PGNS.contains(&pgn) {
+            return Err(MessageError::UnsupportedPgn(pgn));
+        }
+
+        let message = if reassembled || !FAST_PACKET_PGNS.contains(&pgn) {
+            data
+        } else {
+            match self.push_fast_packet(pgn, source, &data)? {
+                Some(message) => message,
+                None => return Ok(None),
+            }
+        };
+
+        Ok(Some(DecodedMessage {
+            timestamp,
+            ..decode_pgn(pgn, source, &message)?
+        }))
+    }
+
+    /// Fast packets still waiting for frames when the capture ended
+    pub fn finish(self) -> Vec<MessageError> {
+        self.pending
+            .into_keys()
+            .map(|(_, pgn)| MessageError::IncompleteFastPacket(pgn))
+            .collect()
+    }
+
+    fn push_fast_packet(
+        &mut self,
+        pgn: u32,
+        source: u8,
+        frame: &[u8],
+    ) -> Result<Option<Vec<u8>>, MessageError> {
+        let Some(&header) = frame.first() else {
+            return Err(MessageError::Malformed(
+                "empty fast-packet frame".to_string(),
+            ));
+        };
+        let sequence = header >> 5;
+        let index = header & 0x1F;
+        let key = (source, pgn);
+
+        if index == 0 {
+            let length = *frame.get(1).ok_or_else(|| {
+                MessageError::Malformed("fast-packet first frame has no length".to_string())
+            })? as usize;
+            let restarted = self.pending.insert(
+                key,
+                PartialPacket {
+                    sequence,
+                    next_frame: 1,
+                    length,
+                    data: frame[2..].to_vec(),
+                },
+            );
+            if restarted.is_some() {
+                return Err(MessageError::IncompleteFastPacket(pgn));
+            }
+        } else {
+            let Some(partial) = self.pending.get_mut(&key) else {
+                return Err(MessageError::IncompleteFastPacket(pgn));
+            };
+            if partial.sequence != sequence || partial.next_frame != index {
+                self.pending.remove(&key);
+                return Err(MessageError::IncompleteFastPacket(pgn));
+            }
+            partial.next_frame += 1;
+            partial.data.extend_from_slice(&frame[1..]);
+        }
+
+        match self.pending.get(&key) {
+            Some(partial) if partial.data.len() >= partial.length => {
+                let mut partial = self.pending.remove(&key).expect("entry checked above");
+                partial.data.truncate(partial.length);
+                Ok(Some(partial.data))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Best-effort label for a line that failed to decode, e.g. `127489`
+pub fn error_label(error: &MessageError) -> String {
+    error
+        .pgn()
+        .map(|pgn| pgn.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn parse_line(line: &str) -> Result<CaptureLine, MessageError> {
+    let line = line.trim();
+
+    // candump prefixes lines with `(seconds.micros)` when logging or run with -t
+    let (timestamp, line) = match line.strip_prefix('(').and_then(|rest| rest.split_once(')')) {
+        Some((time, rest)) => (Some(candump_time(time)?), rest.trim_start()),
+        None => (None, line),
+    };
+
+    if let Some((_, frame)) = line.rsplit_once(char::is_whitespace)
+        && let Some((id, data)) = frame.split_once('#')
+    {
+        // candump log format
+        let (pgn, source) = split_can_id(id)?;
+        let data = hex_bytes(
+            data.as_bytes()
+                .chunks(2)
+                .map(|pair| std::str::from_utf8(pair).unwrap_or_default()),
+        )?;
+        return Ok(CaptureLine {
+            pgn,
+            source,
+            data,
+            reassembled: false,
+            timestamp,
+        });
+    }
+
+    if let Some((head, bytes)) = line.split_once(']') {
+        // candump console format
+        let mut parts = head.split_whitespace();
+        let id = parts
+            .nth(1)
+            .ok_or_else(|| MessageError::Malformed("candump line has no CAN ID".to_string()))?;
+        let (pgn, source) = split_can_id(id)?;
+        let data = hex_bytes(bytes.split_whitespace())?;
+        return Ok(CaptureLine {
+            pgn,
+            source,
+            data,
+            reassembled: false,
+            timestamp,
+        });
+    }
+
+    let parts: Vec<&str> = line.split(',').collect();
+    if parts.len() >= 6 {
+        // Actisense / canboat format: timestamp,prio,pgn,src,dst,len,data...
+        let pgn = parts[2]
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| MessageError::Malformed(format!("PGN '{}' is not a number", parts[2])))?;
+        let source = parts[3].trim().parse::<u8>().map_err(|_| {
+            MessageError::Malformed(format!("source '{}' is not a number", parts[3]))
+        })?;
+        let length = parts[5].trim().parse::<usize>().map_err(|_| {
+            MessageError::Malformed(format!("length '{}' is not a number", parts[5]))
+        })?;
+        let data = hex_bytes(parts[6..].iter().copied())?;
+        if data.len() != length {
+            return Err(MessageError::Malformed(format!(
+                "length says {} bytes but {} were given",
+                length,
+                data.len()
+            )));
+        }
+        return Ok(CaptureLine {
+            pgn,
+            source,
+            data,
+            reassembled: true,
+            timestamp: actisense_time(parts[0]),
+        });
+    }
+
+    Err(MessageError::Malformed(
+        "not a candump or Actisense line".to_string(),
+    ))
+}
+
+/// Parses the candump `seconds.micros` Unix time
+fn candump_time(value: &str) -> Result<DateTime<Utc>, MessageError> {
+    let invalid =
+        || MessageError::Malformed(format!("timestamp '{}' is not seconds.micros", value));
+    let (secs, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let secs = secs.parse::<i64>().map_err(|_| invalid())?;
+    let nanos = format!("{:0<9}", fraction.get(..9).unwrap_or(fraction))
+        .parse::<u32>()
+        .map_err(|_| invalid())?;
+    DateTime::from_timestamp(secs, nanos).ok_or_else(invalid)
+}
+
+/// Actisense / canboat times are RFC 3339 or `yyyy-mm-dd-hh:mm:ss.sss` in UTC; anything else,
+/// such as a relative counter, is left out
+fn actisense_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d-%H:%M:%S%.f").map(|time| time.and_utc())
+        })
+        .ok()
+}
+
+/// Extracts PGN and source address from a 29-bit CAN identifier
+fn split_can_id(id: &str) -> Result<(u32, u8), MessageError> {
+    if id.len() != 8 {
+        return Err(MessageError::Malformed(format!(
+            "CAN ID '{}' is not a 29-bit extended identifier",
+            id
+        )));
+    }
+    let id = u32::from_str_radix(id, 16)
+        .map_err(|_| MessageError::Malformed(format!("CAN ID '{}' is not hex", id)))?;
+
+    let source = (id & 0xFF) as u8;
+    let pdu_format = (id >> 16) & 0xFF;
+    let data_page = (id >> 24) & 0x3;
+    let pgn = if pdu_format < 240 {
+        // PDU1: the low byte is a destination address, not part of the PGN
+        (data_page << 16) | (pdu_format << 8)
+    } else {
+        (data_page << 16) | (pdu_format << 8) | ((id >> 8) & 0xFF)
+    };
+
+    Ok((pgn, source))
+}
+
+fn hex_bytes<'a>(parts: impl Iterator<Item = &'a str>) -> Result<Vec<u8>, MessageError> {
+    parts
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            u8::from_str_radix(part, 16)
+                .map_err(|_| MessageError::Malformed(format!("'{}' is not a hex byte", part)))
+        })
+        .collect()
+}
+
+/// Decodes the payload of a supported PGN
+pub fn decode_pgn(pgn: u32, source: u8, data: &[u8]) -> Result<DecodedMessage, MessageError> {
+    let reader = Payload { pgn, data };
+    let fields = match pgn {
+        61444 => decode_eec1(&reader, source)?,
+        65262 => decode_et1(&reader, source)?,
+        65263 => decode_eflp1(&reader, source)?,
+        65266 => decode_lfe(&reader, source)?,
+        127488 => decode_engine_rapid(&reader)?,
+        127489 => decode_engine_dynamic(&reader)?,
+        127505 => decode_fluid_level(&reader)?,
+        127508 => decode_battery_status(&reader)?,
+        128267 => decode_water_depth(&reader)?,
+        130312 => decode_temperature(&reader)?,
+        _ => return Err(MessageError::UnsupportedPgn(pgn)),
+    };
+
+    Ok(DecodedMessage {
+        pgn,
+        source,
+        fields: fields
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| (name, v)))
+            .collect(),
+        timestamp: None,
+    })
+}
+
+type Fields = Vec<(String, Option<f64>)>;
+
+/// Little-endian field reader. NMEA 2000 marks unavailable values with the top three codes of
+/// each type; J1939 reserves everything above 0xFA / 0xFAFF.
+struct Payload<'a> {
+    pgn: u32,
+    data: &'a [u8],
+}
+
+impl Payload<'_> {
+    fn require(&self, length: usize) -> Result<(), MessageError> {
+        if self.data.len() < length {
+            return Err(MessageError::Malformed(format!(
+                "PGN {} needs {} bytes, got {}",
+                self.pgn,
+                length,
+                self.data.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn u8(&self, at: usize) -> u8 {
+        self.data[at]
+    }
+
+    fn u16(&self, at: usize) -> u16 {
+        u16::from_le_bytes([self.data[at], self.data[at + 1]])
+    }
+
+    fn n2k_u8(&self, at: usize) -> Option<f64> {
+        let v = self.u8(at);
+        (v < 0xFD).then_some(v as f64)
+    }
+
+    fn n2k_i8(&self, at: usize) -> Option<f64> {
+        let v = self.u8(at) as i8;
+        (v < 0x7D).then_some(v as f64)
+    }
+
+    fn n2k_u16(&self, at: usize) -> Option<f64> {
+        let v = self.u16(at);
+        (v < 0xFFFD).then_some(v as f64)
+    }
+
+    fn n2k_i16(&self, at: usize) -> Option<f64> {
+        let v = self.u16(at) as i16;
+        (v < 0x7FFD).then_some(v as f64)
+    }
+
+    fn n2k_u32(&self, at: usize) -> Option<f64> {
+        let v = u32::from_le_bytes([
+            self.data[at],
+            self.data[at + 1],
+            self.data[at + 2],
+            self.data[at + 3],
+        ]);
+        (v < 0xFFFF_FFFD).then_some(v as f64)
+    }
+
+    fn j1939_u8(&self, at: usize) -> Option<f64> {
+        let v = self.u8(at);
+        (v <= 0xFA).then_some(v as f64)
+    }
+
+    fn j1939_u16(&self, at: usize) -> Option<f64> {
+        let v = self.u16(at);
+        (v <= 0xFAFF).then_some(v as f64)
+    }
+}
+
+fn scaled(value: Option<f64>, factor: f64, offset: f64) -> Option<f64> {
+    value.map(|v| v * factor + offset)
+}
+
+fn field(prefix: &str, name: &str, value: Option<f64>) -> (String, Option<f64>) {
+    (format!("{}.{}", prefix, name), value)
+}
+
+/// PGN 127488 Engine Parameters, Rapid Update
+fn decode_engine_rapid(p: &Payload) -> Result<Fields, MessageError> {
+    p.require(6)?;
+    let engine = format!("engine{}", p.u8(0));
+    Ok(vec![
+        field(&engine, "speed_rpm", scaled(p.n2k_u16(1), 0.25, 0.0)),
+        field(
+            &engine,
+            "boost_pressure_kpa",
+            scaled(p.n2k_u16(3), 0.1, 0.0),
+        ),
+        field(&engine, "tilt_trim_pct", p.n2k_i8(5)),
+    ])
+}
+
+/// PGN 127489 Engine Parameters, Dynamic
+fn decode_engine_dynamic(p: &Payload) -> Result<Fields, MessageError> {
+    p.require(26)?;
+    let engine = format!("engine{}", p.u8(0));
+    Ok(vec![
+        field(&engine, "oil_pressure_kpa", scaled(p.n2k_u16(1), 0.1, 0.0)),
+        field(
+            &engine,
+            "oil_temp_c",
+            scaled(p.n2k_u16(3), 0.1, -KELVIN_OFFSET),
+        ),
+        field(
+            &engine,
+            "coolant_temp_c",
+            scaled(p.n2k_u16(5), 0.01, -KELVIN_OFFSET),
+        ),
+        field(&engine, "alternator_v", scaled(p.n2k_i16(7), 0.01, 0.0)),
+        field(&engine, "fuel_rate_lph", scaled(p.n2k_i16(9), 0.1, 0.0)),
+        field(&engine, "hours", scaled(p.n2k_u32(11), 1.0 / 3600.0, 0.0)),
+        field(
+            &engine,
+            "coolant_pressure_kpa",
+            scaled(p.n2k_u16(15), 0.1, 0.0),
+        ),
+        field(&engine, "fuel_pressure_kpa", p.n2k_u16(17)),
+        field(&engine, "load_pct", p.n2k_i8(24)),
+        field(&engine, "torque_pct", p.n2k_i8(25)),
+    ])
+}
+
+/// PGN 127505 Fluid Level
+fn decode_fluid_level(p: &Payload) -> Result<Fields, MessageError> {
+    p.require(7)?;
+    let fluid = match p.u8(0) >> 4 {
+        0 => "fuel",
+        1 => "fresh_water",
+        2 => "waste_water",
+        3 => "live_well",
+        4 => "oil",
+        5 => "black_water",
+        _ => "fluid",
+    };
+    let tank = format!("{}{}", fluid, p.u8(0) & 0x0F);
+    Ok(vec![
+        field(&tank, "level_pct", scaled(p.n2k_i16(1), 0.004, 0.0)),
+        field(&tank, "capacity_l", scaled(p.n2k_u32(3), 0.1, 0.0)),
+    ])
+}
+
+/// PGN 127508 Battery Status
+fn decode_battery_status(p: &Payload) -> Result<Fields, MessageError> {
+    p.require(7)?;
+    let battery = format!("battery{}", p.u8(0));
+    Ok(vec![
+        field(&battery, "voltage_v", scaled(p.n2k_i16(1), 0.01, 0.0)),
+        field(&battery, "current_a", scaled(p.n2k_i16(3), 0.1, 0.0)),
+        field(
+            &battery,
+            "temperature_c",
+            scaled(p.n2k_u16(5), 0.01, -KELVIN_OFFSET),
+        ),
+    ])
+}
+
+/// PGN 128267 Water Depth
+fn decode_water_depth(p: &Payload) -> Result<Fields, MessageError> {
+    p.require(8)?;
+    Ok(vec![
+        ("depth_m".to_string(), scaled(p.n2k_u32(1), 0.01, 0.0)),
+        ("offset_m".to_string(), scaled(p.n2k_i16(5), 0.001, 0.0)),
+        ("range_m".to_string(), scaled(p.n2k_u8(7), 10.0, 0.0)),
+    ])
+}
+
+/// PGN 130312 Temperature
+fn decode_temperature(p: &Payload) -> Result<Fields, MessageError> {
+    p.require(7)?;
+    let source = match p.u8(2) {
+        0 => "sea",
+        1 => "outside",
+        2 => "inside",
+        3 => "engine_room",
+        4 => "main_cabin",
+        5 => "live_well",
+        6 => "bait_well",
+        7 => "refrigeration",
+        8 => "heating_system",
+        13 => "freezer",
+        14 => "exhaust_gas",
+        _ => "temperature",
+    };
+    let sensor = format!("{}{}", source, p.u8(1));
+    Ok(vec![
+        field(
+            &sensor,
+            "temperature_c",
+            scaled(p.n2k_u16(3), 0.01, -KELVIN_OFFSET),
+        ),
+        field(
+            &sensor,
+            "set_temperature_c",
+            scaled(p.n2k_u16(5), 0.01, -KELVIN_OFFSET),
+        ),
+    ])
+}
+
+/// J1939 PGN 61444 Electronic Engine Controller 1; the engine is identified by source address
+fn decode_eec1(p: &Payload, source: u8) -> Result<Fields, MessageError> {
+    p.require(5)?;
+    let engine = format!("engine{}", source);
+    Ok(vec![
+        field(&engine, "torque_pct", scaled(p.j1939_u8(2), 1.0, -125.0)),
+        field(&engine, "speed_rpm", scaled(p.j1939_u16(3), 0.125, 0.0)),
+    ])
+}
+
+/// J1939 PGN 65262 Engine Temperature 1
+fn decode_et1(p: &Payload, source: u8) -> Result<Fields, MessageError> {
+    p.require(4)?;
+    let engine = format!("engine{}", source);
+    Ok(vec![
+        field(&engine, "coolant_temp_c", scaled(p.j1939_u8(0), 1.0, -40.0)),
+        field(&engine, "fuel_temp_c", scaled(p.j1939_u8(1), 1.0, -40.0)),
+        field(
+            &engine,
+            "oil_temp_c",
+            scaled(p.j1939_u16(2), 0.03125, -273.0),
+        ),
+    ])
+}
+
+/// J1939 PGN 65263 Engine Fluid Level/Pressure 1
+fn decode_eflp1(p: &Payload, source: u8) -> Result<Fields, MessageError> {
+    p.require(8)?;
+    let engine = format!("engine{}", source);
+    Ok(vec![
+        field(
+            &engine,
+            "fuel_pressure_kpa",
+            scaled(p.j1939_u8(0), 4.0, 0.0),
+        ),
+        field(&engine, "oil_level_pct", scaled(p.j1939_u8(2), 0.4, 0.0)),
+        field(&engine, "oil_pressure_kpa", scaled(p.j1939_u8(3), 4.0, 0.0)),
+        field(
+            &engine,
+            "coolant_pressure_kpa",
+            scaled(p.j1939_u8(6), 2.0, 0.0),
+        ),
+        field(
+            &engine,
+            "coolant_level_pct",
+            scaled(p.j1939_u8(7), 0.4, 0.0),
+        ),
+    ])
+}
+
+/// J1939 PGN 65266 Fuel Economy (Liquid)
+fn decode_lfe(p: &Payload, source: u8) -> Result<Fields, MessageError> {
+    p.require(2)?;
+    let engine = format!("engine{}", source);
+    Ok(vec![field(
+        &engine,
+        "fuel_rate_lph",
+        scaled(p.j1939_u16(0), 0.05, 0.0),
+    )])
+}
@@ -1,9 +1,17 @@
+use crate::models::dead_letter::{
+    DEAD_LETTER_CONSTRAINT_VIOLATION, DEAD_LETTER_DATA_EXCEPTION, DEAD_LETTER_MISSING_VESSEL,
+    DEAD_LETTER_NUMERIC_OVERFLOW, DEAD_LETTER_VALUE_TOO_LONG,
+};
 use axum::Json;
-use axum::http::StatusCode;
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 use serde::Serialize;
 use thiserror::Error;
 
+/// Seconds a client is asked to wait after a 503
+const RETRY_AFTER_SECS: &str = "1";
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("Database error: {0}")]
@@ -27,15 +35,90 @@ pub enum AppError {
     TooManyRequests(String),
     #[error("Forbidden: {0}")]
     Forbidden(String),
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+    #[error("Rejected ({reason}): {message}")]
+    Rejected { reason: String, message: String },
+    #[error("Overloaded ({reason}): {message}")]
+    Overloaded {
+        reason: String,
+        message: String,
+        retry_after_secs: u64,
+    },
+}
+
+impl AppError {
+    /// Machine-readable reason code for rejections, if any
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            AppError::Rejected { reason, .. } | AppError::Overloaded { reason, .. } => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Whether the database could not be reached, as opposed to rejecting the statement
+    pub fn is_database_outage(&self) -> bool {
+        let AppError::Database(e) = self else {
+            return false;
+        };
+
+        match e {
+            sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::Protocol(_) => true,
+            // Connection exceptions (08), operator intervention (57P) and too_many_connections
+            sqlx::Error::Database(e) => e.code().is_some_and(|code| {
+                code.starts_with("08") || code.starts_with("57P") || code == "53300"
+            }),
+            _ => false,
+        }
+    }
+
+    /// SQLSTATE of an error returned by PostgreSQL
+    pub fn sqlstate(&self) -> Option<String> {
+        match self {
+            AppError::Database(sqlx::Error::Database(e)) => e.code().map(|code| code.into_owned()),
+            _ => None,
+        }
+    }
+
+    /// Dead-letter class when PostgreSQL refused to store a frame because of its contents.
+    ///
+    /// Every foreign key on the telemetry tables points at vessel_register_table, so a
+    /// foreign key violation means the vessel was deleted.
+    pub fn dead_letter_class(&self) -> Option<&'static str> {
+        let code = self.sqlstate()?;
+        Some(match code.as_str() {
+            "22003" => DEAD_LETTER_NUMERIC_OVERFLOW,
+            "22001" => DEAD_LETTER_VALUE_TOO_LONG,
+            "23503" => DEAD_LETTER_MISSING_VESSEL,
+            code if code.starts_with("23") => DEAD_LETTER_CONSTRAINT_VIOLATION,
+            code if code.starts_with("22") => DEAD_LETTER_DATA_EXCEPTION,
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Serialize)]
 struct ErrorResponse {
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let reason = self.reason().map(str::to_string);
+        let retry_after = match &self {
+            AppError::Overloaded {
+                retry_after_secs, ..
+            } => HeaderValue::from(*retry_after_secs),
+            _ => HeaderValue::from_static(RETRY_AFTER_SECS),
+        };
         let (status, message) = match self {
             AppError::Database(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -48,8 +131,45 @@ impl IntoResponse for AppError {
             AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             AppError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
             AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            AppError::UnsupportedMediaType(msg) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, msg),
+            AppError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            AppError::Rejected { message, .. } => (StatusCode::UNPROCESSABLE_ENTITY, message),
+            AppError::Overloaded { message, .. } => (StatusCode::SERVICE_UNAVAILABLE, message),
         };
 
-        (status, Json(ErrorResponse { message })).into_response()
+        let mut response = (status, Json(ErrorResponse { message, reason })).into_response();
+        if status == StatusCode::SERVICE_UNAVAILABLE {
+            response.headers_mut().insert(RETRY_AFTER, retry_after);
+        }
+        response
+    }
+}
+
+/// gRPC equivalent of the HTTP mapping above; rejection reasons travel in `x-reason` metadata
+impl From<AppError> for tonic::Status {
+    fn from(error: AppError) -> Self {
+        let reason = error.reason().map(str::to_string);
+        let mut status = match error {
+            AppError::Database(e) => tonic::Status::internal(format!("Database error: {}", e)),
+            AppError::Validation(msg) | AppError::UnsupportedMediaType(msg) => {
+                tonic::Status::invalid_argument(msg)
+            }
+            AppError::NotFound(msg) => tonic::Status::not_found(msg),
+            AppError::Conflict(msg) => tonic::Status::already_exists(msg),
+            AppError::Unauthorized(msg) => tonic::Status::unauthenticated(msg),
+            AppError::Internal(msg) => tonic::Status::internal(msg),
+            AppError::TooManyRequests(msg) => tonic::Status::resource_exhausted(msg),
+            AppError::Forbidden(msg) => tonic::Status::permission_denied(msg),
+            AppError::ServiceUnavailable(msg) | AppError::Overloaded { message: msg, .. } => {
+                tonic::Status::unavailable(msg)
+            }
+            AppError::Rejected { message, .. } => tonic::Status::failed_precondition(message),
+        };
+
+        if let Some(value) = reason.and_then(|r| r.parse().ok()) {
+            status.metadata_mut().insert("x-reason", value);
+        }
+
+        status
     }
 }
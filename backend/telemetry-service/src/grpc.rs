@@ -0,0 +1,217 @@
+//! Optional gRPC server exposing `proto/telemetry_service.proto`
+//!
+//! Runs on its own port next to the Axum router and calls the same `Services`, so validation,
+//! dedup and storage match the REST routes. Callers authenticate with `x-api-key` metadata;
+//! `QueryTelemetry` also accepts `x-admin-key` for reading any vessel.
+
+use crate::config::GrpcConfig;
+use crate::error::AppError;
+use crate::middleware::is_admin_key;
+use crate::models::telemetry::{StoredTelemetryQuery, TelemetryResponse};
+use crate::proto::telemetry::telemetry_service_server::{
+    TelemetryService as TelemetryServiceApi, TelemetryServiceServer,
+};
+use crate::proto::telemetry::{
+    FrameRejection, IngestResponse, IngestStreamSummary, QueryTelemetryRequest, StoredSample,
+    TelemetryFrame,
+};
+use crate::state::AppState;
+use chrono::DateTime;
+use futures_util::StreamExt;
+use rust_decimal::prelude::ToPrimitive;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::metadata::MetadataMap;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{error, info};
+
+/// Samples buffered ahead of a slow query client
+const QUERY_BUFFER: usize = 256;
+
+/// Binds the port and starts the server in the background
+pub async fn spawn_server(config: GrpcConfig, state: AppState) -> std::io::Result<JoinHandle<()>> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
+    let listener = TcpListener::bind(addr).await?;
+
+    info!("gRPC server listening on {}", addr);
+
+    let service = TelemetryServiceServer::new(GrpcTelemetryService { state });
+    Ok(tokio::spawn(async move {
+        if let Err(e) = Server::builder()
+            .add_service(service)
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+        {
+            error!(error = %e, "gRPC server stopped");
+        }
+    }))
+}
+
+pub struct GrpcTelemetryService {
+    state: AppState,
+}
+
+impl GrpcTelemetryService {
+    /// Resolves the vessel behind the `x-api-key` metadata entry
+    async fn authenticate(&self, metadata: &MetadataMap) -> Result<String, AppError> {
+        let api_key = metadata
+            .get("x-api-key")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| AppError::Unauthorized("Missing API key".to_string()))?;
+
+        self.state
+            .services()
+            .auth_service()
+            .validate_api_key(api_key)
+            .await
+    }
+
+    async fn ingest_frame(
+        &self,
+        authenticated_vessel_id: &str,
+        frame: TelemetryFrame,
+    ) -> Result<TelemetryResponse, AppError> {
+        if frame.vessel_id != authenticated_vessel_id {
+            return Err(AppError::Forbidden(format!(
+                "Vessel ID mismatch: authenticated as '{}' but payload contains '{}'",
+                authenticated_vessel_id, frame.vessel_id
+            )));
+        }
+
+        self.state
+            .services()
+            .telemetry_service()
+            .ingest_telemetry_proto(frame)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl TelemetryServiceApi for GrpcTelemetryService {
+    async fn ingest(
+        &self,
+        request: Request<TelemetryFrame>,
+    ) -> Result<Response<IngestResponse>, Status> {
+        let vessel_id = self.authenticate(request.metadata()).await?;
+        info!("Received gRPC telemetry for vessel: {}", vessel_id);
+
+        let response = self.ingest_frame(&vessel_id, request.into_inner()).await?;
+
+        Ok(Response::new(IngestResponse {
+            message: response.message,
+            correlation_id: response.correlation_id.to_string(),
+            valid_signals: response.valid_signals as u32,
+            invalid_signals: response.invalid_signals as u32,
+        }))
+    }
+
+    async fn ingest_stream(
+        &self,
+        request: Request<Streaming<TelemetryFrame>>,
+    ) -> Result<Response<IngestStreamSummary>, Status> {
+        let vessel_id = self.authenticate(request.metadata()).await?;
+        info!("Opened gRPC telemetry stream for vessel: {}", vessel_id);
+
+        let mut frames = request.into_inner();
+        let mut summary = IngestStreamSummary::default();
+
+        while let Some(frame) = frames.message().await? {
+            let index = summary.frames_received;
+            let frame_id = frame.frame_id.clone();
+            summary.frames_received += 1;
+
+            match self.ingest_frame(&vessel_id, frame).await {
+                Ok(_) => summary.frames_accepted += 1,
+                Err(e) => {
+                    summary.frames_rejected += 1;
+                    summary.rejections.push(FrameRejection {
+                        index,
+                        frame_id,
+                        reason: e.reason().unwrap_or_default().to_string(),
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        info!(
+            vessel_id = %vessel_id,
+            frames_received = summary.frames_received,
+            frames_rejected = summary.frames_rejected,
+            "gRPC telemetry stream finished"
+        );
+
+        Ok(Response::new(summary))
+    }
+
+    type QueryTelemetryStream = ReceiverStream<Result<StoredSample, Status>>;
+
+    async fn query_telemetry(
+        &self,
+        request: Request<QueryTelemetryRequest>,
+    ) -> Result<Response<Self::QueryTelemetryStream>, Status> {
+        let is_admin = request
+            .metadata()
+            .get("x-admin-key")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(is_admin_key);
+        if !is_admin {
+            let vessel_id = self.authenticate(request.metadata()).await?;
+            if vessel_id != request.get_ref().vessel_id {
+                return Err(AppError::Forbidden(format!(
+                    "Authenticated as '{}' but queried '{}'",
+                    vessel_id,
+                    request.get_ref().vessel_id
+                ))
+                .into());
+            }
+        }
+
+        let request = request.into_inner();
+        let out_of_range = |field: &str, value: i64| {
+            AppError::Validation(format!("{} {} is out of range", field, value))
+        };
+        let query = StoredTelemetryQuery {
+            from: DateTime::from_timestamp_millis(request.from_utc_ms)
+                .ok_or_else(|| out_of_range("from_utc_ms", request.from_utc_ms))?,
+            to: DateTime::from_timestamp_millis(request.to_utc_ms)
+                .ok_or_else(|| out_of_range("to_utc_ms", request.to_utc_ms))?,
+            vessel_id: request.vessel_id,
+            signal_names: request.signal_names,
+        };
+
+        let telemetry_service = self.state.services().telemetry_service();
+        let (sender, receiver) = mpsc::channel(QUERY_BUFFER);
+
+        tokio::spawn(async move {
+            let mut rows = match telemetry_service.stream_stored_telemetry(&query).await {
+                Ok(rows) => rows,
+                Err(e) => {
+                    let _ = sender.send(Err(e.into())).await;
+                    return;
+                }
+            };
+
+            while let Some(row) = rows.next().await {
+                let sample = row.map(|r| StoredSample {
+                    timestamp_utc_ms: r.timestamp_utc.timestamp_millis(),
+                    signal_name: r.signal_name,
+                    value: r.signal_value.to_f64().unwrap_or_default(),
+                    correlation_id: r.correlation_id.to_string(),
+                });
+                let failed = sample.is_err();
+
+                // A closed channel means the client went away
+                if sender.send(sample.map_err(Status::from)).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
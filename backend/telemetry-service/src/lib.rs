@@ -1,9 +1,17 @@
+pub mod cli;
+pub mod config;
 pub mod controller;
 pub mod database;
+pub mod decoders;
+pub mod grpc;
 pub mod models;
+pub mod mqtt;
+pub mod proto;
 pub mod repositories;
 pub mod services;
 pub mod state;
+pub mod udp;
+pub mod wal;
 
 pub mod error;
 pub mod middleware;
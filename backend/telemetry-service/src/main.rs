@@ -1,16 +1,37 @@
 use axum::{Router, routing::get};
 use sqlx::PgPool;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use telemetry_service::tracing::init_logging;
 use telemetry_service::{
+    cli,
+    config::{
+        AdmissionConfig, AsyncIngestionConfig, CacheConfig, DatabaseConfig, GrpcConfig,
+        IngestionConfig, MqttConfig, UdpConfig, WalConfig,
+    },
     database::get_pool,
+    grpc::spawn_server,
+    mqtt::spawn_bridge,
     routes::{api_routes, root},
     state::AppState,
+    udp::spawn_listener,
+    wal::WriteAheadLog,
 };
-use tracing::info;
+use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Subcommands print to stdout, so they run before JSON logging is set up
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(e) = cli::run(&args).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     init_logging()?;
 
     dotenv::dotenv().ok();
@@ -18,17 +39,79 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Starting Telemetry Service");
 
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let pool: PgPool = get_pool(database_url, 5).await?;
+    let wal_config = WalConfig::from_env();
+    // Fail over to the write-ahead log quickly instead of holding requests for the default 30s
+    let acquire_timeout = wal_config
+        .as_ref()
+        .map(|config| Duration::from_secs(config.acquire_timeout_secs));
+    let database_config = DatabaseConfig::from_env();
+    let pool: PgPool = get_pool(
+        database_url,
+        database_config.max_connections,
+        acquire_timeout,
+    )
+    .await?;
 
     sqlx::migrate!("./migrations").run(&pool).await?;
     info!("Migrations done");
 
-    let state = AppState::builder().db(pool.clone()).build();
+    let wal = match wal_config {
+        Some(config) => Some(Arc::new(WriteAheadLog::open(config).await?)),
+        None => None,
+    };
+
+    let state = AppState::builder()
+        .db(pool.clone())
+        .ingestion_config(IngestionConfig::from_env())
+        .async_ingestion(AsyncIngestionConfig::from_env())
+        .write_ahead_log(wal)
+        .admission(AdmissionConfig::from_env())
+        .cache(CacheConfig::from_env())
+        .build();
+
+    let signal_registry = state.services().signal_registry();
+    signal_registry.load().await?;
+    signal_registry.spawn_refresh();
+    state.services().auth_service().spawn_last_used_flush();
+
+    state
+        .services()
+        .session_service()
+        .close_stale_sessions()
+        .await?;
+    state
+        .services()
+        .backfill_service()
+        .fail_interrupted_jobs()
+        .await?;
+
+    if let Some(ingest_queue) = state.services().ingest_queue() {
+        ingest_queue.spawn_workers();
+    }
+
+    state.services().admission_service().spawn_metrics_flush();
+    state.services().telemetry_service().spawn_claim_purge();
+
+    if let Some(wal_service) = state.services().wal_service() {
+        wal_service.spawn_replay();
+    }
+
+    if let Some(mqtt_config) = MqttConfig::from_env() {
+        spawn_bridge(mqtt_config, state.clone());
+    }
+
+    if let Some(udp_config) = UdpConfig::from_env() {
+        spawn_listener(udp_config, state.clone()).await?;
+    }
+
+    if let Some(grpc_config) = GrpcConfig::from_env() {
+        spawn_server(grpc_config, state.clone()).await?;
+    }
 
     let app = Router::new()
         .route("/", get(root))
         .nest("/api/v1", api_routes(state.clone()))
-        .with_state(state);
+        .with_state(state.clone());
 
     let port = std::env::var("APP_PORT")
         .unwrap_or_else(|_| "3000".to_string())
@@ -40,7 +123,47 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Server listening on {}", addr);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    // Accepted frames were promised a write; drain them before exiting
+    if let Some(ingest_queue) = state.services().ingest_queue() {
+        ingest_queue.shutdown().await;
+    }
+    state.services().auth_service().flush_last_used().await;
 
     Ok(())
 }
+
+/// Resolves on Ctrl+C or SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!(error = %e, "Failed to listen for Ctrl+C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    info!("Shutdown signal received");
+}
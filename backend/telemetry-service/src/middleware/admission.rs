@@ -0,0 +1,33 @@
+use crate::error::AppError;
+use crate::services::admission::{PRIORITY_ALARM, PRIORITY_HEADER};
+use crate::state::AppState;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+/// Marks a request admitted as an alarm, so authentication can check the key may claim it
+#[derive(Clone, Copy)]
+pub struct AlarmClaim;
+
+/// Sheds ingestion requests before authentication, so an overloaded service spends no
+/// database round trips on requests it will refuse
+pub async fn admission_middleware(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let alarm = req
+        .headers()
+        .get(PRIORITY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|priority| priority.trim().eq_ignore_ascii_case(PRIORITY_ALARM));
+
+    let _permit = state.services().admission_service().admit(alarm)?;
+    if alarm {
+        req.extensions_mut().insert(AlarmClaim);
+    }
+
+    Ok(next.run(req).await)
+}
@@ -1,4 +1,5 @@
 use crate::error::AppError;
+use crate::middleware::admission::AlarmClaim;
 use crate::state::AppState;
 use axum::{
     extract::{Request, State},
@@ -17,14 +18,19 @@ pub async fn auth_middleware(
         .and_then(|h| h.to_str().ok())
         .ok_or_else(|| AppError::Unauthorized("Missing API key".to_string()))?;
 
-    let vessel_id = state
+    let key = state
         .services()
         .auth_service()
-        .validate_api_key(auth_header)
+        .authenticate(auth_header)
         .await?;
 
+    // An alarm claim only stands if the key was created with alarm priority
+    if req.extensions().get::<AlarmClaim>().is_some() && !key.alarm_priority {
+        state.services().admission_service().demote_alarm()?;
+    }
+
     // Store vessel_id in request extensions for later use
-    req.extensions_mut().insert(vessel_id);
+    req.extensions_mut().insert(key.vessel_id);
 
     Ok(next.run(req).await)
 }
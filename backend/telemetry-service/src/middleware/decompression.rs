@@ -0,0 +1,105 @@
+use crate::error::AppError;
+use crate::state::AppState;
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::header::{CONTENT_ENCODING, CONTENT_LENGTH};
+use axum::middleware::Next;
+use axum::response::Response;
+use futures_util::TryStreamExt;
+use http_body_util::Limited;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::AsyncRead;
+use tokio_util::io::{ReaderStream, StreamReader};
+use tracing::warn;
+
+/// Default ceiling on a decoded request body, guarding against decompression bombs
+const DEFAULT_MAX_DECOMPRESSED_BYTES: usize = 512 * 1024 * 1024;
+
+/// Transparently decodes `Content-Encoding: gzip` and `zstd` request bodies.
+///
+/// Must run after `auth_middleware` so the authenticated vessel is known; the compressed and
+/// decompressed byte counts are recorded against it once the handler has consumed the body.
+pub async fn decompression_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let encoding = match req.headers().get(CONTENT_ENCODING) {
+        Some(value) => value
+            .to_str()
+            .map_err(|_| AppError::Validation("Invalid Content-Encoding header".to_string()))?
+            .trim()
+            .to_ascii_lowercase(),
+        None => return Ok(next.run(req).await),
+    };
+
+    if encoding == "identity" {
+        return Ok(next.run(req).await);
+    }
+    if encoding != "gzip" && encoding != "zstd" {
+        return Err(AppError::UnsupportedMediaType(format!(
+            "Content-Encoding '{}' is not supported, use gzip or zstd",
+            encoding
+        )));
+    }
+
+    let max_decompressed = std::env::var("TELEMETRY_MAX_DECOMPRESSED_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_DECOMPRESSED_BYTES);
+
+    let vessel_id = req.extensions().get::<String>().cloned();
+    let (mut parts, body) = req.into_parts();
+    parts.headers.remove(CONTENT_ENCODING);
+    parts.headers.remove(CONTENT_LENGTH);
+
+    let compressed_bytes = Arc::new(AtomicU64::new(0));
+    let decompressed_bytes = Arc::new(AtomicU64::new(0));
+
+    let counter = compressed_bytes.clone();
+    let compressed = StreamReader::new(
+        body.into_data_stream()
+            .map_ok(move |chunk| {
+                counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                chunk
+            })
+            .map_err(std::io::Error::other),
+    );
+
+    let decoder: Pin<Box<dyn AsyncRead + Send>> = if encoding == "gzip" {
+        let mut decoder = GzipDecoder::new(compressed);
+        decoder.multiple_members(true);
+        Box::pin(decoder)
+    } else {
+        Box::pin(ZstdDecoder::new(compressed))
+    };
+
+    let counter = decompressed_bytes.clone();
+    let decoded = ReaderStream::new(decoder).map_ok(move |chunk| {
+        counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        chunk
+    });
+
+    // Limited surfaces as a length-limit rejection (413) in axum's body extractors
+    let body = Body::new(Limited::new(Body::from_stream(decoded), max_decompressed));
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    if let Some(vessel_id) = vessel_id {
+        let metrics_service = state.services().metrics_service();
+        let compressed = compressed_bytes.load(Ordering::Relaxed);
+        let decompressed = decompressed_bytes.load(Ordering::Relaxed);
+        tokio::spawn(async move {
+            if let Err(e) = metrics_service
+                .record_compression(&vessel_id, &encoding, compressed, decompressed)
+                .await
+            {
+                warn!(error = %e, "Failed to record compression metric");
+            }
+        });
+    }
+
+    Ok(response)
+}
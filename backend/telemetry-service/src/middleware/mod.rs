@@ -1,19 +1,26 @@
+pub mod admission;
 pub mod auth;
+pub mod decompression;
 
 use crate::error::AppError;
 use axum::{extract::Request, middleware::Next, response::Response};
 
-pub async fn admin_middleware(req: Request, next: Next) -> Result<Response, AppError> {
+/// Whether `key` is the admin key (`ADMIN_API_KEY`)
+pub fn is_admin_key(key: &str) -> bool {
     let admin_key =
         std::env::var("ADMIN_API_KEY").unwrap_or_else(|_| "admin_secret_key_change_me".to_string());
 
+    key == admin_key
+}
+
+pub async fn admin_middleware(req: Request, next: Next) -> Result<Response, AppError> {
     let auth_header = req
         .headers()
         .get("x-admin-key")
         .and_then(|h| h.to_str().ok())
         .ok_or_else(|| AppError::Unauthorized("Missing admin key".to_string()))?;
 
-    if auth_header != admin_key {
+    if !is_admin_key(auth_header) {
         return Err(AppError::Unauthorized("Invalid admin key".to_string()));
     }
 
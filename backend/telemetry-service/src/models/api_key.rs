@@ -7,6 +7,9 @@ pub struct CreateApiKeyRequest {
     pub vessel_id: String,
     #[serde(default)]
     pub expires_at: Option<DateTime<Utc>>,
+    /// Lets frames sent with the key claim alarm priority during load shedding
+    #[serde(default)]
+    pub alarm_priority: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -19,4 +22,23 @@ pub struct ApiKeyResponse {
     pub created_at: DateTime<Utc>,
     pub expires_at: Option<DateTime<Utc>>,
     pub last_used_at: Option<DateTime<Utc>>,
+    pub alarm_priority: bool,
+}
+
+/// A valid API key together with the state of its vessel
+#[derive(Debug)]
+pub struct ApiKeyLookup {
+    pub vessel_id: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// `false` when the vessel was deactivated; the key still authenticates
+    pub vessel_active: bool,
+    pub alarm_priority: bool,
+}
+
+/// What a valid API key grants
+#[derive(Debug, Clone)]
+pub struct AuthenticatedKey {
+    pub vessel_id: String,
+    /// Whether the key's frames may claim alarm priority
+    pub alarm_priority: bool,
 }
@@ -0,0 +1,169 @@
+use crate::error::AppError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const JOB_QUEUED: &str = "queued";
+pub const JOB_RUNNING: &str = "running";
+pub const JOB_COMPLETED: &str = "completed";
+pub const JOB_FAILED: &str = "failed";
+
+/// How a CSV export maps onto telemetry; sent as the `mapping` part of the upload
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillMapping {
+    /// Header of the column holding the sample time
+    pub timestamp_column: String,
+    /// `epoch_s`, `epoch_ms`, or a chrono format string read as UTC; RFC 3339 or
+    /// `YYYY-MM-DD HH:MM:SS` when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_format: Option<String>,
+    /// Field separator; `,` when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<char>,
+    /// Read `12,5` as 12.5, as spreadsheets in many locales export it
+    #[serde(default)]
+    pub decimal_comma: bool,
+    /// Column header to signal name; when omitted every other column is read under its header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BackfillJobQuery {
+    pub vessel_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BackfillUploadQuery {
+    /// Import a file even if the same content was already imported for the vessel
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug)]
+pub struct NewBackfillJob {
+    pub job_id: Uuid,
+    pub vessel_id: String,
+    pub file_name: Option<String>,
+    pub file_sha256: String,
+    pub column_mapping: JsonValue,
+    pub status: String,
+    pub rows_total: i64,
+    pub correlation_id: Uuid,
+    pub trace_id: String,
+}
+
+#[derive(Debug)]
+pub struct BackfillJob {
+    pub job_id: Uuid,
+    pub vessel_id: String,
+    pub file_name: Option<String>,
+    pub status: String,
+    pub rows_total: i64,
+    pub rows_processed: i64,
+    pub rows_accepted: i64,
+    pub rows_rejected: i64,
+    pub valid_signals: i64,
+    pub invalid_signals: i64,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub correlation_id: Uuid,
+}
+
+/// Progress of one import; counts only ever grow while the job runs
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BackfillProgress {
+    pub rows_processed: i64,
+    pub rows_accepted: i64,
+    pub rows_rejected: i64,
+    pub valid_signals: i64,
+    pub invalid_signals: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillRowError {
+    pub row_number: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl BackfillRowError {
+    pub fn from_error(row_number: i64, error: &AppError) -> Self {
+        Self {
+            row_number,
+            message: error.to_string(),
+            reason: error.reason().map(str::to_string),
+        }
+    }
+}
+
+/// Outcome of one chunk of rows passed to `TelemetryService::ingest_backfill_chunk`
+#[derive(Debug, Default)]
+pub struct BackfillChunkResult {
+    pub rows_accepted: usize,
+    pub valid_signals: usize,
+    pub invalid_signals: usize,
+    /// Position within the chunk and the reason the row was not stored
+    pub rejected: Vec<(usize, AppError)>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillJobResponse {
+    pub job_id: Uuid,
+    pub vessel_id: String,
+    pub file_name: Option<String>,
+    pub status: String,
+    pub rows_total: i64,
+    pub rows_processed: i64,
+    pub rows_accepted: i64,
+    pub rows_rejected: i64,
+    pub valid_signals: i64,
+    pub invalid_signals: i64,
+    /// Share of rows processed, 0 to 100
+    pub progress_percent: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub correlation_id: Uuid,
+    /// Only filled in when a single job is fetched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_errors: Option<Vec<BackfillRowError>>,
+}
+
+impl From<BackfillJob> for BackfillJobResponse {
+    fn from(job: BackfillJob) -> Self {
+        let progress_percent = if job.rows_total == 0 {
+            100.0
+        } else {
+            (job.rows_processed as f64 * 1000.0 / job.rows_total as f64).round() / 10.0
+        };
+
+        Self {
+            job_id: job.job_id,
+            vessel_id: job.vessel_id,
+            file_name: job.file_name,
+            status: job.status,
+            rows_total: job.rows_total,
+            rows_processed: job.rows_processed,
+            rows_accepted: job.rows_accepted,
+            rows_rejected: job.rows_rejected,
+            valid_signals: job.valid_signals,
+            invalid_signals: job.invalid_signals,
+            progress_percent,
+            error: job.error,
+            created_at: job.created_at,
+            finished_at: job.finished_at,
+            correlation_id: job.correlation_id,
+            row_errors: None,
+        }
+    }
+}
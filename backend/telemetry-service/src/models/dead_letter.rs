@@ -0,0 +1,142 @@
+use crate::error::AppError;
+use crate::models::telemetry::{DecodeRejection, TelemetryRequest};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// Error classes stored in `dead_letter_frames.error_class` for frames the database refused
+pub const DEAD_LETTER_NUMERIC_OVERFLOW: &str = "numeric_overflow";
+pub const DEAD_LETTER_VALUE_TOO_LONG: &str = "value_too_long";
+pub const DEAD_LETTER_MISSING_VESSEL: &str = "missing_vessel";
+pub const DEAD_LETTER_CONSTRAINT_VIOLATION: &str = "constraint_violation";
+pub const DEAD_LETTER_DATA_EXCEPTION: &str = "data_exception";
+/// A replayed or retried frame that failed validation without a more specific reason
+pub const DEAD_LETTER_REJECTED: &str = "rejected";
+
+#[derive(Debug)]
+pub struct NewDeadLetter {
+    pub id: Uuid,
+    pub vessel_id: String,
+    pub frame_id: Option<String>,
+    /// The `TelemetryRequest` as received
+    pub payload: JsonValue,
+    pub decode_rejections: JsonValue,
+    pub error_class: String,
+    pub error_message: String,
+    pub sqlstate: Option<String>,
+    pub correlation_id: Uuid,
+    pub trace_id: String,
+}
+
+impl NewDeadLetter {
+    pub fn for_frame(
+        request: &TelemetryRequest,
+        rejected: &[DecodeRejection],
+        correlation_id: Uuid,
+        trace_id: &str,
+        error: &AppError,
+        error_class: &str,
+    ) -> Result<Self, AppError> {
+        let to_json = |value: serde_json::Result<JsonValue>| {
+            value.map_err(|e| AppError::Internal(format!("Failed to serialize dead letter: {}", e)))
+        };
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            vessel_id: request.vessel_id.clone(),
+            frame_id: request.frame_id.clone(),
+            payload: to_json(serde_json::to_value(request))?,
+            decode_rejections: to_json(serde_json::to_value(rejected))?,
+            error_class: error_class.to_string(),
+            error_message: error.to_string(),
+            sqlstate: error.sqlstate(),
+            correlation_id,
+            trace_id: trace_id.to_string(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct DeadLetter {
+    pub id: Uuid,
+    pub vessel_id: String,
+    pub frame_id: Option<String>,
+    pub payload: JsonValue,
+    pub decode_rejections: JsonValue,
+    pub error_class: String,
+    pub error_message: String,
+    pub sqlstate: Option<String>,
+    pub retry_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub last_retried_at: Option<DateTime<Utc>>,
+    pub correlation_id: Uuid,
+    pub trace_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterQuery {
+    pub vessel_id: Option<String>,
+    pub error_class: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Filters for a purge; without any, every dead letter is removed
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterPurgeQuery {
+    pub vessel_id: Option<String>,
+    pub error_class: Option<String>,
+    pub older_than_hours: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterResponse {
+    pub id: Uuid,
+    pub vessel_id: String,
+    pub frame_id: Option<String>,
+    pub payload: JsonValue,
+    pub decode_rejections: JsonValue,
+    pub error_class: String,
+    pub error_message: String,
+    pub sqlstate: Option<String>,
+    pub retry_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub last_retried_at: Option<DateTime<Utc>>,
+    pub correlation_id: Uuid,
+}
+
+impl From<DeadLetter> for DeadLetterResponse {
+    fn from(dead_letter: DeadLetter) -> Self {
+        Self {
+            id: dead_letter.id,
+            vessel_id: dead_letter.vessel_id,
+            frame_id: dead_letter.frame_id,
+            payload: dead_letter.payload,
+            decode_rejections: dead_letter.decode_rejections,
+            error_class: dead_letter.error_class,
+            error_message: dead_letter.error_message,
+            sqlstate: dead_letter.sqlstate,
+            retry_count: dead_letter.retry_count,
+            created_at: dead_letter.created_at,
+            last_retried_at: dead_letter.last_retried_at,
+            correlation_id: dead_letter.correlation_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeadLetterPurgeResponse {
+    pub purged: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterRetryResponse {
+    pub id: Uuid,
+    /// `stored`, or `duplicate` when the frame ID had been ingested in the meantime
+    pub status: String,
+    pub correlation_id: Uuid,
+    pub valid_signals: usize,
+    pub invalid_signals: usize,
+}
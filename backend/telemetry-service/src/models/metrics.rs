@@ -16,6 +16,16 @@ pub struct ServerMetric {
     pub trace_id: Option<String>,
 }
 
+/// A server_metrics row written as part of a batch
+#[derive(Debug)]
+pub struct MetricRecord {
+    pub vessel_id: Option<String>,
+    pub metric_type: String,
+    pub metric_value: Decimal,
+    pub correlation_id: Uuid,
+    pub trace_id: String,
+}
+
 #[derive(Debug)]
 pub struct MetricType {
     pub request_volume: String,
@@ -57,3 +67,86 @@ pub struct MetricsQuery {
     pub vessel_id: Option<String>,
     pub hours: Option<f64>,
 }
+
+#[derive(Debug)]
+pub struct CompressionStats {
+    pub vessel_id: String,
+    pub compressed_requests: i64,
+    pub compressed_bytes: Decimal,
+    pub decompressed_bytes: Decimal,
+    pub avg_compression_ratio: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressionSummary {
+    pub vessel_id: String,
+    pub time_range: String,
+    pub compressed_requests: i64,
+    pub compressed_bytes: u64,
+    pub decompressed_bytes: u64,
+    pub bytes_saved: u64,
+    pub avg_compression_ratio: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UdpRejectionSummary {
+    /// `None` for datagrams that could not be attributed to a vessel
+    pub vessel_id: Option<String>,
+    pub reason: String,
+    pub datagrams: i64,
+}
+
+/// Requests refused by the admission controller for one priority and reason
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionShedSummary {
+    pub priority: String,
+    pub reason: String,
+    pub requests: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionMetrics {
+    pub in_flight: usize,
+    pub max_in_flight: usize,
+    pub saturated_in_flight: usize,
+    pub alarm_max_in_flight: usize,
+    pub pool_size: u32,
+    pub pool_idle: usize,
+    pub pool_max_connections: u32,
+    /// Every pool connection is in use
+    pub pool_saturated: bool,
+    /// Counts since startup
+    pub admitted_alarm: u64,
+    pub admitted_routine: u64,
+    /// Alarm claims from keys without alarm priority, decided again as routine
+    pub demoted_alarm: u64,
+    pub shed_since_startup: Vec<AdmissionShedSummary>,
+    /// Shed counts written to server_metrics over `time_range`
+    pub time_range: String,
+    pub shed: Vec<AdmissionShedSummary>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalCacheMetrics {
+    /// Signals in the snapshot, retired ones included
+    pub signals: usize,
+    /// Vessels with a class or range overrides; every other vessel may send every signal
+    pub vessel_signal_sets: usize,
+    pub loaded_at: Option<DateTime<Utc>>,
+    pub age_secs: Option<f64>,
+    /// Whether change notifications are being received
+    pub listening: bool,
+    pub refresh_interval_secs: u64,
+    /// Lookups answered from the snapshot
+    pub hits: u64,
+    /// Lookups that queried the database because nothing was loaded yet
+    pub misses: u64,
+    pub reloads: u64,
+    pub reload_failures: u64,
+    pub notifications: u64,
+}
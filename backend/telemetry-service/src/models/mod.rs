@@ -1,5 +1,10 @@
 pub mod api_key;
+pub mod backfill;
+pub mod dead_letter;
 pub mod metrics;
+pub mod session;
 pub mod signal;
+pub mod signal_mapping;
 pub mod telemetry;
 pub mod vessel;
+pub mod vessel_class;
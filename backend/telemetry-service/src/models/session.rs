@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const TRANSPORT_WEBSOCKET: &str = "websocket";
+
+/// Why a session ended, stored in `ingestion_sessions.end_reason`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEndReason {
+    /// The vessel sent a close frame or shut the connection down cleanly
+    ClientClosed,
+    /// Nothing arrived within the idle timeout
+    IdleTimeout,
+    /// The connection broke without a close frame
+    ConnectionError,
+    /// The session was still open when the service stopped
+    ServerRestart,
+}
+
+impl SessionEndReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionEndReason::ClientClosed => "client_closed",
+            SessionEndReason::IdleTimeout => "idle_timeout",
+            SessionEndReason::ConnectionError => "connection_error",
+            SessionEndReason::ServerRestart => "server_restart",
+        }
+    }
+}
+
+/// Frame counts kept in memory for the life of a session and stored when it closes
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SessionCounters {
+    pub frames_received: i64,
+    pub frames_accepted: i64,
+    pub frames_rejected: i64,
+}
+
+#[derive(Debug)]
+pub struct IngestionSession {
+    pub session_id: Uuid,
+    pub vessel_id: String,
+    pub transport: String,
+    pub connected_at: DateTime<Utc>,
+    pub disconnected_at: Option<DateTime<Utc>>,
+    pub end_reason: Option<String>,
+    pub frames_received: i64,
+    pub frames_accepted: i64,
+    pub frames_rejected: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionResponse {
+    pub session_id: Uuid,
+    pub vessel_id: String,
+    pub transport: String,
+    pub connected_at: DateTime<Utc>,
+    pub disconnected_at: Option<DateTime<Utc>>,
+    pub end_reason: Option<String>,
+    pub frames_received: i64,
+    pub frames_accepted: i64,
+    pub frames_rejected: i64,
+    pub active: bool,
+}
+
+impl From<IngestionSession> for SessionResponse {
+    fn from(s: IngestionSession) -> Self {
+        Self {
+            active: s.disconnected_at.is_none(),
+            session_id: s.session_id,
+            vessel_id: s.vessel_id,
+            transport: s.transport,
+            connected_at: s.connected_at,
+            disconnected_at: s.disconnected_at,
+            end_reason: s.end_reason,
+            frames_received: s.frames_received,
+            frames_accepted: s.frames_accepted,
+            frames_rejected: s.frames_rejected,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionQuery {
+    pub vessel_id: Option<String>,
+    /// Only sessions that are still connected
+    pub active: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+/// Reply to one frame on a WebSocket session; `seq` counts frames from 1 within the session
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionReply {
+    Ack {
+        seq: i64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        frame_id: Option<String>,
+        correlation_id: Uuid,
+        valid_signals: usize,
+        invalid_signals: usize,
+    },
+    Error {
+        seq: i64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        frame_id: Option<String>,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+}
@@ -1,7 +1,12 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+pub const SIGNAL_TYPE_DIGITAL: &str = "digital";
+pub const SIGNAL_TYPE_ANALOG: &str = "analog";
+
 #[derive(Debug, Clone)]
 pub struct Signal {
     pub signal_id: i32,
@@ -14,4 +19,156 @@ pub struct Signal {
     pub updated_at: DateTime<Utc>,
     pub correlation_id: Option<Uuid>,
     pub trace_id: Option<String>,
+    pub unit: Option<String>,
+    /// Frames naming a retired signal have it filtered as `retired_signal`
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+/// Everything about a signal except its name; the body of `PUT /signals/{signal_name}`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalDefinition {
+    pub signal_type: String,
+    #[serde(default)]
+    pub min_value: Option<Decimal>,
+    #[serde(default)]
+    pub max_value: Option<Decimal>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSignalRequest {
+    pub signal_name: String,
+    #[serde(flatten)]
+    pub definition: SignalDefinition,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignalQuery {
+    pub signal_type: Option<String>,
+    /// Name pattern where `*` matches any run of characters, e.g. `Signal_5*`
+    pub name: Option<String>,
+    #[serde(default)]
+    pub include_retired: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalResponse {
+    pub signal_id: i32,
+    pub signal_name: String,
+    pub signal_type: String,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub description: Option<String>,
+    pub unit: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+impl From<Signal> for SignalResponse {
+    fn from(s: Signal) -> Self {
+        Self {
+            signal_id: s.signal_id,
+            signal_name: s.signal_name,
+            signal_type: s.signal_type,
+            min_value: s.min_value.and_then(|v| v.to_f64()),
+            max_value: s.max_value.and_then(|v| v.to_f64()),
+            description: s.description,
+            unit: s.unit,
+            created_at: s.created_at,
+            updated_at: s.updated_at,
+            retired_at: s.retired_at,
+        }
+    }
+}
+
+/// One entry of a signal catalogue: a YAML list item under `signals:`, or a CSV row whose
+/// header uses the same names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogueSignal {
+    pub signal_name: String,
+    pub signal_type: String,
+    #[serde(default, serialize_with = "bound_as_number")]
+    pub min_value: Option<Decimal>,
+    #[serde(default, serialize_with = "bound_as_number")]
+    pub max_value: Option<Decimal>,
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl From<Signal> for CatalogueSignal {
+    fn from(s: Signal) -> Self {
+        Self {
+            signal_name: s.signal_name,
+            signal_type: s.signal_type,
+            min_value: s.min_value,
+            max_value: s.max_value,
+            unit: s.unit,
+            description: s.description,
+        }
+    }
+}
+
+/// Layout of a YAML catalogue
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignalCatalogue {
+    pub signals: Vec<CatalogueSignal>,
+}
+
+/// Writes bounds as plain numbers rather than rust_decimal's default strings
+fn bound_as_number<S: serde::Serializer>(
+    value: &Option<Decimal>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value.map(|v| v.normalize()) {
+        Some(v) if v.scale() == 0 => serializer.serialize_some(&v.to_i64()),
+        Some(v) => serializer.serialize_some(&v.to_f64()),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignalImportQuery {
+    /// Without it the import is only a preview
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignalExportQuery {
+    /// `yaml` (default) or `csv`
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalChange {
+    pub signal_name: String,
+    /// Fields that differ; `retired` when a retired signal is brought back
+    pub fields: Vec<String>,
+    pub before: CatalogueSignal,
+    pub after: CatalogueSignal,
+}
+
+/// What importing a catalogue changes, or changed when `applied`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalCatalogueDiff {
+    pub applied: bool,
+    /// Shared by every row the import wrote
+    pub correlation_id: Option<Uuid>,
+    pub added: Vec<CatalogueSignal>,
+    pub changed: Vec<SignalChange>,
+    /// Active signals missing from the catalogue, retired when applied
+    pub removed: Vec<CatalogueSignal>,
+    pub unchanged: usize,
 }
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Protocols a decoded field can come from
+pub const SOURCE_NMEA0183: &str = "nmea0183";
+pub const SOURCE_NMEA2000: &str = "nmea2000";
+pub const SOURCE_AIS: &str = "ais";
+
+#[derive(Debug)]
+pub struct SignalMapping {
+    pub source: String,
+    pub message_type: String,
+    pub field_name: String,
+    pub signal_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub correlation_id: Option<Uuid>,
+    pub trace_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertSignalMappingRequest {
+    pub source: String,
+    pub message_type: String,
+    pub field_name: String,
+    pub signal_name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalMappingResponse {
+    pub source: String,
+    pub message_type: String,
+    pub field_name: String,
+    pub signal_name: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SignalMapping> for SignalMappingResponse {
+    fn from(m: SignalMapping) -> Self {
+        Self {
+            source: m.source,
+            message_type: m.message_type,
+            field_name: m.field_name,
+            signal_name: m.signal_name,
+            updated_at: m.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignalMappingQuery {
+    pub source: Option<String>,
+}
@@ -1,10 +1,12 @@
+use crate::error::AppError;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Instant;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelemetryRequest {
     #[serde(rename = "vesselId")]
     pub vessel_id: String,
@@ -13,9 +15,21 @@ pub struct TelemetryRequest {
     #[serde(rename = "epochUTC")]
     pub epoch_utc: String,
     pub signals: HashMap<String, serde_json::Value>,
+    /// Client-assigned ID; a retry with the same ID returns the original response
+    #[serde(rename = "frameId", default, skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+/// MQTT payload: a telemetry frame plus the publisher's API key, since MQTT 3.1.1 has no headers
+#[derive(Debug, Deserialize)]
+pub struct MqttTelemetryMessage {
+    #[serde(rename = "apiKey")]
+    pub api_key: String,
+    #[serde(flatten)]
+    pub telemetry: TelemetryRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelemetryResponse {
     pub message: String,
     pub correlation_id: Uuid,
@@ -23,6 +37,355 @@ pub struct TelemetryResponse {
     pub invalid_signals: usize,
 }
 
+#[derive(Debug, Serialize)]
+pub struct TelemetryBatchResponse {
+    pub message: String,
+    pub accepted_frames: usize,
+    pub rejected_frames: usize,
+    pub results: Vec<TelemetryFrameResult>,
+}
+
+/// Outcome of a single frame within a batch, keyed by its position in the request array
+#[derive(Debug, Serialize)]
+pub struct TelemetryFrameResult {
+    pub index: usize,
+    pub accepted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_signals: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_signals: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl TelemetryFrameResult {
+    pub fn accepted(index: usize, response: &TelemetryResponse) -> Self {
+        Self {
+            index,
+            accepted: true,
+            correlation_id: Some(response.correlation_id),
+            valid_signals: Some(response.valid_signals),
+            invalid_signals: Some(response.invalid_signals),
+            error: None,
+            reason: None,
+        }
+    }
+
+    pub fn rejected(index: usize, error: String) -> Self {
+        Self {
+            index,
+            accepted: false,
+            correlation_id: None,
+            valid_signals: None,
+            invalid_signals: None,
+            error: Some(error),
+            reason: None,
+        }
+    }
+
+    pub fn failed(index: usize, error: &AppError) -> Self {
+        Self {
+            reason: error.reason().map(str::to_string),
+            ..Self::rejected(index, error.to_string())
+        }
+    }
+}
+
+/// A protocol message that could not be decoded; stored in telemetry_filtered under `label`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodeRejection {
+    pub label: String,
+    pub reason: String,
+}
+
+/// Result of ingesting the frames decoded from a wire protocol such as NMEA 0183, NMEA 2000 or AIS
+#[derive(Debug, Serialize)]
+pub struct DecodedIngestResponse {
+    pub message: String,
+    /// First stored frame, which also holds the rejected messages
+    pub correlation_id: Uuid,
+    /// Every stored frame in time order, one per sample time in the upload
+    pub correlation_ids: Vec<Uuid>,
+    /// Frames refused by validation, e.g. for a timestamp outside the accepted range
+    pub frames_rejected: usize,
+    /// Non-empty input lines; fast-packet frames are combined into one message
+    pub messages_received: usize,
+    pub messages_decoded: usize,
+    pub messages_rejected: usize,
+    /// Well-formed messages about other vessels (AIS traffic)
+    pub messages_ignored: usize,
+    pub valid_signals: usize,
+    pub invalid_signals: usize,
+    /// Decoded `<messageType>.<field>` names with no entry in signal_source_mappings
+    pub unmapped_fields: Vec<String>,
+}
+
+/// Query for decoded-protocol routes; the frame time defaults to the server receive time
+#[derive(Debug, Deserialize)]
+pub struct DecodedIngestQuery {
+    #[serde(rename = "timestampUTC")]
+    pub timestamp_utc: Option<DateTime<Utc>>,
+}
+
+/// Final record returned once an NDJSON upload has been consumed
+#[derive(Debug, Default, Serialize)]
+pub struct TelemetryStreamSummary {
+    pub message: String,
+    pub correlation_id: Uuid,
+    pub frames_received: usize,
+    pub frames_committed: usize,
+    /// Frames whose `frameId` was already ingested inside the dedup window, so were not written
+    pub duplicate_frames: usize,
+    pub last_committed_line: usize,
+    pub valid_signals: usize,
+    pub invalid_signals: usize,
+    pub malformed_frames: usize,
+    pub malformed_lines: Vec<MalformedLine>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MalformedLine {
+    pub line: usize,
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Per-vessel distribution of `ingested_at - timestamp_utc`, one sample per frame
+#[derive(Debug)]
+pub struct IngestionDelayStats {
+    pub vessel_id: String,
+    pub frames: i64,
+    pub min_delay_secs: f64,
+    pub p05_delay_secs: f64,
+    pub median_delay_secs: f64,
+    pub max_delay_secs: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockDriftResponse {
+    pub vessel_id: String,
+    pub time_range: String,
+    pub frames: i64,
+    /// 5th percentile of the ingestion delay. Store-and-forward backlogs inflate the upper
+    /// percentiles but not the low tail, which tracks network latency plus clock offset;
+    /// a negative value means the vessel clock runs ahead of the server.
+    pub estimated_drift_secs: f64,
+    pub min_delay_secs: f64,
+    pub median_delay_secs: f64,
+    pub max_delay_secs: f64,
+    pub suspect_clock: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClockDriftQuery {
+    pub vessel_id: Option<String>,
+    pub hours: Option<f64>,
+    pub threshold_secs: Option<f64>,
+}
+
+/// Range of stored telemetry for one vessel; `to` is exclusive
+#[derive(Debug)]
+pub struct StoredTelemetryQuery {
+    pub vessel_id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Empty means every signal
+    pub signal_names: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct StoredTelemetry {
+    pub id: i64,
+    pub timestamp_utc: DateTime<Utc>,
+    pub signal_name: String,
+    pub signal_value: Decimal,
+    pub correlation_id: Uuid,
+}
+
+/// Sort key of the last stored sample returned, so the next page starts after it
+#[derive(Debug)]
+pub struct StoredCursor {
+    pub timestamp_utc: DateTime<Utc>,
+    pub signal_name: String,
+    pub id: i64,
+}
+
+impl From<&StoredTelemetry> for StoredCursor {
+    fn from(row: &StoredTelemetry) -> Self {
+        Self {
+            timestamp_utc: row.timestamp_utc,
+            signal_name: row.signal_name.clone(),
+            id: row.id,
+        }
+    }
+}
+
+/// Client frame ID recorded alongside a frame's rows so retries can be answered from storage
+#[derive(Debug)]
+pub struct FrameClaim {
+    pub vessel_id: String,
+    pub frame_id: String,
+    pub correlation_id: Uuid,
+    pub response: serde_json::Value,
+    pub window_secs: f64,
+}
+
+pub const INGESTION_QUEUED: &str = "queued";
+pub const INGESTION_COMMITTED: &str = "committed";
+pub const INGESTION_DUPLICATE: &str = "duplicate";
+pub const INGESTION_FAILED: &str = "failed";
+/// Handed to the write-ahead log while the database was unreachable; stored when the log is
+/// replayed
+pub const INGESTION_BUFFERED: &str = "buffered";
+
+/// A validated frame waiting in the async ingestion queue
+#[derive(Debug)]
+pub struct QueuedFrame {
+    pub vessel_id: String,
+    pub frame_id: Option<String>,
+    pub correlation_id: Uuid,
+    pub trace_id: String,
+    pub raw: Vec<RawRecord>,
+    pub filtered: Vec<FilteredRecord>,
+    pub claim: Option<FrameClaim>,
+    pub accepted_at: DateTime<Utc>,
+    /// When the request started, for the total latency metric
+    pub received: Instant,
+    pub validation_ms: u128,
+    /// The frame as received, kept in dead letters if the database refuses it
+    pub request: TelemetryRequest,
+}
+
+/// Final outcome of an async frame, as stored in async_ingestion_status
+#[derive(Debug, Clone)]
+pub struct IngestionStatusRecord {
+    pub correlation_id: Uuid,
+    pub vessel_id: String,
+    pub frame_id: Option<String>,
+    pub status: String,
+    pub valid_signals: i32,
+    pub invalid_signals: i32,
+    pub error: Option<String>,
+    pub accepted_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl IngestionStatusRecord {
+    pub fn for_frame(frame: &QueuedFrame, status: &str, error: Option<String>) -> Self {
+        Self {
+            correlation_id: frame.correlation_id,
+            vessel_id: frame.vessel_id.clone(),
+            frame_id: frame.frame_id.clone(),
+            status: status.to_string(),
+            valid_signals: frame.raw.len() as i32,
+            invalid_signals: frame.filtered.len() as i32,
+            error,
+            accepted_at: frame.accepted_at,
+            completed_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestionStatusResponse {
+    pub correlation_id: Uuid,
+    pub vessel_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<String>,
+    /// `queued`, `committed`, `duplicate` (the frame ID was committed by another request), `failed`
+    /// or `buffered` (waiting in the write-ahead log)
+    pub status: String,
+    pub valid_signals: i32,
+    pub invalid_signals: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub accepted_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<IngestionStatusRecord> for IngestionStatusResponse {
+    fn from(record: IngestionStatusRecord) -> Self {
+        Self {
+            correlation_id: record.correlation_id,
+            vessel_id: record.vessel_id,
+            frame_id: record.frame_id,
+            status: record.status,
+            valid_signals: record.valid_signals,
+            invalid_signals: record.invalid_signals,
+            error: record.error,
+            accepted_at: record.accepted_at,
+            completed_at: record.completed_at,
+        }
+    }
+}
+
+/// A frame accepted while the database was unreachable, as stored in the write-ahead log.
+///
+/// The request is kept as received and validated against the registry when it is replayed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalRecord {
+    pub correlation_id: Uuid,
+    pub trace_id: String,
+    pub accepted_at: DateTime<Utc>,
+    pub request: TelemetryRequest,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<DecodeRejection>,
+}
+
+/// Result of replaying one write-ahead log segment
+#[derive(Debug, Default)]
+pub struct WalReplayOutcome {
+    pub replayed: usize,
+    /// Already written by an earlier replay or a client retry
+    pub duplicates: usize,
+    /// Could not be stored, e.g. the vessel was deleted in the meantime, and kept as dead letters
+    pub dead_lettered: usize,
+    /// Could not be stored and could not be kept as dead letters either
+    pub dropped: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WalBacklogResponse {
+    /// Frames on disk waiting to be replayed
+    pub frames: usize,
+    pub segments: usize,
+    pub bytes: u64,
+    pub max_bytes: u64,
+    pub oldest_accepted_at: Option<DateTime<Utc>>,
+    pub last_replay_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_replay_error: Option<String>,
+    /// Totals since startup
+    pub frames_replayed: u64,
+    pub frames_dead_lettered: u64,
+    pub frames_dropped: u64,
+}
+
+/// Row destined for telemetry_raw:
+/// (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id)
+pub type RawRecord = (String, DateTime<Utc>, i64, String, Decimal, Uuid, String);
+
+/// Row destined for telemetry_filtered:
+/// (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, reason, correlation_id, trace_id)
+pub type FilteredRecord = (
+    String,
+    DateTime<Utc>,
+    i64,
+    String,
+    Decimal,
+    String,
+    Uuid,
+    String,
+);
+
 #[derive(Debug)]
 pub struct TelemetryRaw {
     pub id: i64,
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -11,6 +12,14 @@ pub struct Vessel {
     pub updated_at: DateTime<Utc>,
     pub correlation_id: Option<Uuid>,
     pub trace_id: Option<String>,
+    pub mmsi: Option<i32>,
+    pub ais_callsign: Option<String>,
+    pub ais_ship_type: Option<i16>,
+    pub ais_destination: Option<String>,
+    pub ais_eta: Option<DateTime<Utc>>,
+    pub ais_draught: Option<Decimal>,
+    pub ais_static_updated_at: Option<DateTime<Utc>>,
+    pub vessel_class: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -18,6 +27,19 @@ pub struct Vessel {
 pub struct CreateVesselRequest {
     pub vessel_id: String,
     pub vessel_name: String,
+    /// Own-ship MMSI; learned from AIVDO sentences when not given
+    #[serde(default)]
+    pub mmsi: Option<i32>,
+}
+
+/// Static and voyage data from AIS types 5 and 24; `None` leaves the stored value unchanged
+#[derive(Debug, Default)]
+pub struct AisStaticUpdate {
+    pub callsign: Option<String>,
+    pub ship_type: Option<i16>,
+    pub destination: Option<String>,
+    pub eta: Option<DateTime<Utc>>,
+    pub draught: Option<Decimal>,
 }
 
 #[derive(Debug, Serialize)]
@@ -28,4 +50,40 @@ pub struct VesselResponse {
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub mmsi: Option<i32>,
+    pub vessel_class: Option<String>,
+    pub ais: Option<AisVoyageResponse>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AisVoyageResponse {
+    pub callsign: Option<String>,
+    pub ship_type: Option<i16>,
+    pub destination: Option<String>,
+    pub eta: Option<DateTime<Utc>>,
+    pub draught: Option<Decimal>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Vessel> for VesselResponse {
+    fn from(v: Vessel) -> Self {
+        Self {
+            ais: v.ais_static_updated_at.map(|updated_at| AisVoyageResponse {
+                callsign: v.ais_callsign,
+                ship_type: v.ais_ship_type,
+                destination: v.ais_destination,
+                eta: v.ais_eta,
+                draught: v.ais_draught,
+                updated_at,
+            }),
+            vessel_id: v.vessel_id,
+            vessel_name: v.vessel_name,
+            is_active: v.is_active,
+            created_at: v.created_at,
+            updated_at: v.updated_at,
+            mmsi: v.mmsi,
+            vessel_class: v.vessel_class,
+        }
+    }
 }
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct VesselClass {
+    pub class_name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub correlation_id: Option<Uuid>,
+    pub trace_id: Option<String>,
+}
+
+/// Everything about a class except its name; the body of `PUT /vessel-classes/{class_name}`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VesselClassDefinition {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Registered signals a vessel of the class may send; the rest are filtered as
+    /// `unowned_signal`
+    pub signals: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVesselClassRequest {
+    pub class_name: String,
+    #[serde(flatten)]
+    pub definition: VesselClassDefinition,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VesselClassResponse {
+    pub class_name: String,
+    pub description: Option<String>,
+    pub signals: Vec<String>,
+    /// Vessels assigned the class, inactive ones included
+    pub vessel_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body of `PUT /vessels/{vessel_id}/class`; `null` lets the vessel send every signal again
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignVesselClassRequest {
+    pub class_name: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignalRangeOverride {
+    pub vessel_id: String,
+    pub signal_name: String,
+    pub min_value: Decimal,
+    pub max_value: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub correlation_id: Option<Uuid>,
+    pub trace_id: Option<String>,
+}
+
+/// Body of `PUT /vessels/{vessel_id}/signals/{signal_name}/range`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalRangeRequest {
+    pub min_value: Decimal,
+    pub max_value: Decimal,
+}
+
+/// A signal as one vessel's frames are validated against it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VesselSignalResponse {
+    pub signal_name: String,
+    pub signal_type: String,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub unit: Option<String>,
+    /// Whether the range is the vessel's own rather than the registered one
+    pub overridden: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VesselSignalsResponse {
+    pub vessel_id: String,
+    pub vessel_class: Option<String>,
+    pub signals: Vec<VesselSignalResponse>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalRangeResponse {
+    pub vessel_id: String,
+    pub signal_name: String,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SignalRangeOverride> for SignalRangeResponse {
+    fn from(o: SignalRangeOverride) -> Self {
+        Self {
+            vessel_id: o.vessel_id,
+            signal_name: o.signal_name,
+            min_value: o.min_value.to_f64(),
+            max_value: o.max_value.to_f64(),
+            updated_at: o.updated_at,
+        }
+    }
+}
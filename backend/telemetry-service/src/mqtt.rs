@@ -0,0 +1,209 @@
+//! Optional MQTT subscriber feeding broker messages into `TelemetryService::ingest_telemetry`
+//!
+//! Publishers send the usual JSON frame plus an `apiKey` field to `{prefix}/{vessel_id}/telemetry`.
+//! QoS 1 messages are acknowledged only once the outcome is final: after the frame is committed,
+//! or after it is rejected for a reason a redelivery cannot fix. Database and internal failures
+//! retry the same message with backoff before the next one is taken, so an outage never strands
+//! unacknowledged messages in the broker's inflight window. Only an unreachable database is
+//! retried until it recovers; any other failure gives up after `MAX_RETRIES` so one bad message
+//! cannot hold up the rest of the bridge.
+
+use crate::config::MqttConfig;
+use crate::error::AppError;
+use crate::models::telemetry::{MqttTelemetryMessage, TelemetryResponse};
+use crate::state::AppState;
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, Publish, QoS};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Messages buffered between the network loop and the ingestion worker
+const WORKER_QUEUE: usize = 100;
+
+/// Pause before polling again after a connection error; rumqttc reconnects on the next poll
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// First and longest pause before retrying a message that failed on a database or internal error
+const RETRY_BACKOFF_MIN: Duration = Duration::from_secs(1);
+const RETRY_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Retries of a message that failed with the database reachable before it is dropped
+const MAX_RETRIES: u32 = 5;
+
+/// Starts the bridge in the background
+pub fn spawn_bridge(config: MqttConfig, state: AppState) -> JoinHandle<()> {
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    options
+        .set_keep_alive(Duration::from_secs(config.keep_alive_secs))
+        // A persistent session makes the broker keep unacknowledged messages across reconnects
+        .set_clean_session(false)
+        .set_manual_acks(true);
+    if let Some((username, password)) = &config.credentials {
+        options.set_credentials(username, password);
+    }
+
+    let (client, event_loop) = AsyncClient::new(options, WORKER_QUEUE);
+    let (sender, receiver) = mpsc::channel(WORKER_QUEUE);
+
+    info!(
+        host = %config.host,
+        port = config.port,
+        topic = %config.topic_filter(),
+        "Starting MQTT bridge"
+    );
+
+    tokio::spawn(run_worker(
+        client.clone(),
+        receiver,
+        state,
+        config.topic_prefix.clone(),
+    ));
+    tokio::spawn(run_event_loop(client, event_loop, sender, config))
+}
+
+/// Drives the connection and hands incoming publishes to the worker in arrival order
+async fn run_event_loop(
+    client: AsyncClient,
+    mut event_loop: EventLoop,
+    sender: mpsc::Sender<Publish>,
+    config: MqttConfig,
+) {
+    loop {
+        match event_loop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(ack))) => {
+                info!(
+                    session_present = ack.session_present,
+                    "Connected to MQTT broker"
+                );
+                // The broker remembers subscriptions of a persistent session
+                if !ack.session_present
+                    && let Err(e) = client.try_subscribe(config.topic_filter(), QoS::AtLeastOnce)
+                {
+                    error!(error = %e, "Failed to subscribe to telemetry topics");
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                if sender.send(publish).await.is_err() {
+                    error!("MQTT worker stopped, shutting down bridge");
+                    return;
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!(error = %e, "MQTT connection error, reconnecting");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Ingests messages one at a time so acknowledgements go out in the order messages arrived
+async fn run_worker(
+    client: AsyncClient,
+    mut receiver: mpsc::Receiver<Publish>,
+    state: AppState,
+    topic_prefix: String,
+) {
+    while let Some(publish) = receiver.recv().await {
+        let mut backoff = RETRY_BACKOFF_MIN;
+        let mut retries = 0;
+        loop {
+            match handle_publish(&state, &topic_prefix, &publish).await {
+                Ok(response) => {
+                    info!(
+                        topic = %publish.topic,
+                        correlation_id = %response.correlation_id,
+                        "MQTT telemetry ingested"
+                    );
+                }
+                // The broker only redelivers on reconnect, so skipping ahead would hold an inflight
+                // slot per failure until delivery stops altogether
+                Err(e @ (AppError::Database(_) | AppError::Internal(_)))
+                    if e.is_database_outage() || retries < MAX_RETRIES =>
+                {
+                    if !e.is_database_outage() {
+                        retries += 1;
+                    }
+                    error!(
+                        topic = %publish.topic,
+                        pkid = publish.pkid,
+                        error = %e,
+                        retry_in_secs = backoff.as_secs(),
+                        "MQTT telemetry not stored, retrying"
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(RETRY_BACKOFF_MAX);
+                    continue;
+                }
+                Err(e @ (AppError::Database(_) | AppError::Internal(_))) => {
+                    error!(
+                        topic = %publish.topic,
+                        pkid = publish.pkid,
+                        error = %e,
+                        retries,
+                        "MQTT telemetry not stored, dropping message"
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        topic = %publish.topic,
+                        error = %e,
+                        reason = ?e.reason(),
+                        "MQTT telemetry rejected"
+                    );
+                }
+            }
+            break;
+        }
+
+        if let Err(e) = client.ack(&publish).await {
+            error!(error = %e, "Failed to acknowledge MQTT message");
+        }
+    }
+}
+
+async fn handle_publish(
+    state: &AppState,
+    topic_prefix: &str,
+    publish: &Publish,
+) -> Result<TelemetryResponse, AppError> {
+    let topic_vessel_id = topic_vessel_id(topic_prefix, &publish.topic).ok_or_else(|| {
+        AppError::Validation(format!(
+            "Topic '{}' is not {}/{{vessel_id}}/telemetry",
+            publish.topic, topic_prefix
+        ))
+    })?;
+
+    let message: MqttTelemetryMessage = serde_json::from_slice(&publish.payload)
+        .map_err(|e| AppError::Validation(format!("Invalid telemetry payload: {}", e)))?;
+
+    let authenticated_vessel_id = state
+        .services()
+        .auth_service()
+        .validate_api_key(&message.api_key)
+        .await?;
+
+    if authenticated_vessel_id != topic_vessel_id
+        || message.telemetry.vessel_id != authenticated_vessel_id
+    {
+        return Err(AppError::Forbidden(format!(
+            "Vessel ID mismatch: authenticated as '{}' but topic is for '{}' and payload contains '{}'",
+            authenticated_vessel_id, topic_vessel_id, message.telemetry.vessel_id
+        )));
+    }
+
+    state
+        .services()
+        .telemetry_service()
+        .ingest_telemetry(message.telemetry)
+        .await
+}
+
+fn topic_vessel_id<'a>(topic_prefix: &str, topic: &'a str) -> Option<&'a str> {
+    topic
+        .strip_prefix(topic_prefix)?
+        .strip_prefix('/')?
+        .strip_suffix("/telemetry")
+        .filter(|vessel_id| !vessel_id.is_empty() && !vessel_id.contains('/'))
+}
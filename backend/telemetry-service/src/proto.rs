@@ -0,0 +1,11 @@
+//! Protobuf types and gRPC service generated from `proto/telemetry.proto` and `proto/telemetry_service.proto`
+
+/// Published telemetry schema, served so onboard agents can generate their own encoders
+pub const TELEMETRY_PROTO: &str = include_str!("../proto/telemetry.proto");
+
+/// Published gRPC service definition; imports `telemetry.proto`
+pub const TELEMETRY_SERVICE_PROTO: &str = include_str!("../proto/telemetry_service.proto");
+
+pub mod telemetry {
+    include!(concat!(env!("OUT_DIR"), "/nautilus.telemetry.v1.rs"));
+}
@@ -1,3 +1,4 @@
+use crate::models::api_key::{ApiKeyLookup, ApiKeyResponse};
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
@@ -10,32 +11,63 @@ impl AuthRepository {
         Self { pool }
     }
 
-    pub async fn validate_api_key(&self, api_key: &str) -> Result<Option<String>, sqlx::Error> {
-        let result = sqlx::query!(
+    /// The key's vessel if the key is active and unexpired, with the vessel looked up in the
+    /// same query
+    pub async fn validate_api_key(
+        &self,
+        api_key: &str,
+    ) -> Result<Option<ApiKeyLookup>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiKeyLookup,
+            r#"
+            SELECT k.vessel_id, k.expires_at, COALESCE(v.is_active, FALSE) AS "vessel_active!",
+                   k.alarm_priority
+            FROM api_keys k
+            LEFT JOIN vessel_register_table v ON v.vessel_id = k.vessel_id
+            WHERE k.api_key = $1
+              AND k.is_active = TRUE
+              AND (k.expires_at IS NULL OR k.expires_at > NOW())
+            "#,
+            api_key
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Active, unexpired keys of a vessel
+    pub async fn find_active_keys(&self, vessel_id: &str) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query!(
             r#"
-            SELECT vessel_id
+            SELECT api_key
             FROM api_keys
-            WHERE api_key = $1
+            WHERE vessel_id = $1
               AND is_active = TRUE
               AND (expires_at IS NULL OR expires_at > NOW())
             "#,
-            api_key
+            vessel_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(result.map(|r| r.vessel_id))
+        Ok(rows.into_iter().map(|r| r.api_key).collect())
     }
 
-    pub async fn update_last_used(&self, api_key: &str) -> Result<(), sqlx::Error> {
+    /// Sets `last_used_at` for many keys in one statement, never moving it backwards
+    pub async fn update_last_used(
+        &self,
+        api_keys: &[String],
+        used_at: &[DateTime<Utc>],
+    ) -> Result<(), sqlx::Error> {
         sqlx::query!(
             r#"
-            UPDATE api_keys
-            SET last_used_at = $1
-            WHERE api_key = $2
+            UPDATE api_keys AS k
+            SET last_used_at = u.used_at
+            FROM UNNEST($1::text[], $2::timestamptz[]) AS u(api_key, used_at)
+            WHERE k.api_key = u.api_key
+              AND (k.last_used_at IS NULL OR k.last_used_at < u.used_at)
             "#,
-            Utc::now(),
-            api_key
+            api_keys,
+            used_at
         )
         .execute(&self.pool)
         .await?;
@@ -48,16 +80,18 @@ impl AuthRepository {
         vessel_id: &str,
         api_key: &str,
         expires_at: Option<DateTime<Utc>>,
+        alarm_priority: bool,
     ) -> Result<i32, sqlx::Error> {
         let result = sqlx::query!(
             r#"
-            INSERT INTO api_keys (vessel_id, api_key, expires_at)
-            VALUES ($1, $2, $3)
+            INSERT INTO api_keys (vessel_id, api_key, expires_at, alarm_priority)
+            VALUES ($1, $2, $3, $4)
             RETURNING id
             "#,
             vessel_id,
             api_key,
-            expires_at
+            expires_at,
+            alarm_priority
         )
         .fetch_one(&self.pool)
         .await?;
@@ -65,24 +99,11 @@ impl AuthRepository {
         Ok(result.id)
     }
 
-    pub async fn list_api_keys(
-        &self,
-        vessel_id: &str,
-    ) -> Result<
-        Vec<(
-            i32,
-            String,
-            String,
-            bool,
-            DateTime<Utc>,
-            Option<DateTime<Utc>>,
-            Option<DateTime<Utc>>,
-        )>,
-        sqlx::Error,
-    > {
-        let keys = sqlx::query!(
+    pub async fn list_api_keys(&self, vessel_id: &str) -> Result<Vec<ApiKeyResponse>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiKeyResponse,
             r#"
-            SELECT id, vessel_id, api_key, is_active, created_at, expires_at, last_used_at
+            SELECT id, vessel_id, api_key, is_active, created_at, expires_at, last_used_at, alarm_priority
             FROM api_keys
             WHERE vessel_id = $1
             ORDER BY created_at DESC
@@ -90,22 +111,7 @@ impl AuthRepository {
             vessel_id
         )
         .fetch_all(&self.pool)
-        .await?;
-
-        Ok(keys
-            .into_iter()
-            .map(|k| {
-                (
-                    k.id,
-                    k.vessel_id,
-                    k.api_key,
-                    k.is_active,
-                    k.created_at,
-                    k.expires_at,
-                    k.last_used_at,
-                )
-            })
-            .collect())
+        .await
     }
 
     pub async fn revoke_api_key(&self, api_key: &str) -> Result<(), sqlx::Error> {
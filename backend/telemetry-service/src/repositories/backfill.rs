@@ -0,0 +1,219 @@
+use crate::models::backfill::{BackfillJob, BackfillProgress, BackfillRowError, NewBackfillJob};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct BackfillRepository {
+    pool: PgPool,
+}
+
+impl BackfillRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, job: &NewBackfillJob) -> Result<BackfillJob, sqlx::Error> {
+        sqlx::query_as!(
+            BackfillJob,
+            r#"
+            INSERT INTO backfill_jobs
+                (job_id, vessel_id, file_name, file_sha256, column_mapping, status, rows_total, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING job_id, vessel_id, file_name, status, rows_total, rows_processed, rows_accepted,
+                      rows_rejected, valid_signals, invalid_signals, error, created_at, finished_at,
+                      correlation_id
+            "#,
+            job.job_id,
+            job.vessel_id,
+            job.file_name,
+            job.file_sha256,
+            job.column_mapping,
+            job.status,
+            job.rows_total,
+            job.correlation_id,
+            job.trace_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// A job for the same file contents that did not fail, if any
+    pub async fn find_by_file(
+        &self,
+        vessel_id: &str,
+        file_sha256: &str,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            SELECT job_id
+            FROM backfill_jobs
+            WHERE vessel_id = $1 AND file_sha256 = $2 AND status <> 'failed'
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            vessel_id,
+            file_sha256
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.map(|r| r.job_id))
+    }
+
+    pub async fn find_by_id(&self, job_id: Uuid) -> Result<Option<BackfillJob>, sqlx::Error> {
+        sqlx::query_as!(
+            BackfillJob,
+            r#"
+            SELECT job_id, vessel_id, file_name, status, rows_total, rows_processed, rows_accepted,
+                   rows_rejected, valid_signals, invalid_signals, error, created_at, finished_at,
+                   correlation_id
+            FROM backfill_jobs
+            WHERE job_id = $1
+            "#,
+            job_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn find_all(
+        &self,
+        vessel_id: Option<String>,
+        limit: i64,
+    ) -> Result<Vec<BackfillJob>, sqlx::Error> {
+        sqlx::query_as!(
+            BackfillJob,
+            r#"
+            SELECT job_id, vessel_id, file_name, status, rows_total, rows_processed, rows_accepted,
+                   rows_rejected, valid_signals, invalid_signals, error, created_at, finished_at,
+                   correlation_id
+            FROM backfill_jobs
+            WHERE ($1::text IS NULL OR vessel_id = $1)
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            vessel_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn set_status(&self, job_id: Uuid, status: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE backfill_jobs SET status = $2, updated_at = NOW() WHERE job_id = $1
+            "#,
+            job_id,
+            status
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stores the job's counts together with the row errors of the chunk just processed
+    pub async fn record_progress(
+        &self,
+        job_id: Uuid,
+        progress: &BackfillProgress,
+        row_errors: &[BackfillRowError],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE backfill_jobs
+            SET rows_processed = $2, rows_accepted = $3, rows_rejected = $4,
+                valid_signals = $5, invalid_signals = $6, updated_at = NOW()
+            WHERE job_id = $1
+            "#,
+            job_id,
+            progress.rows_processed,
+            progress.rows_accepted,
+            progress.rows_rejected,
+            progress.valid_signals,
+            progress.invalid_signals
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for row_error in row_errors {
+            sqlx::query!(
+                r#"
+                INSERT INTO backfill_row_errors (job_id, row_number, message, reason)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (job_id, row_number) DO NOTHING
+                "#,
+                job_id,
+                row_error.row_number,
+                row_error.message,
+                row_error.reason
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn finish(
+        &self,
+        job_id: Uuid,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE backfill_jobs
+            SET status = $2, error = $3, finished_at = NOW(), updated_at = NOW()
+            WHERE job_id = $1
+            "#,
+            job_id,
+            status,
+            error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Fails jobs that were queued or running when the service stopped
+    pub async fn fail_unfinished(&self, error: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE backfill_jobs
+            SET status = 'failed', error = $1, finished_at = NOW(), updated_at = NOW()
+            WHERE status IN ('queued', 'running')
+            "#,
+            error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_row_errors(
+        &self,
+        job_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<BackfillRowError>, sqlx::Error> {
+        sqlx::query_as!(
+            BackfillRowError,
+            r#"
+            SELECT row_number, message, reason
+            FROM backfill_row_errors
+            WHERE job_id = $1
+            ORDER BY row_number
+            LIMIT $2
+            "#,
+            job_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,144 @@
+use crate::models::dead_letter::{DeadLetter, NewDeadLetter};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct DeadLetterRepository {
+    pool: PgPool,
+}
+
+impl DeadLetterRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn insert(&self, dead_letter: &NewDeadLetter) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO dead_letter_frames
+                (id, vessel_id, frame_id, payload, decode_rejections, error_class, error_message,
+                 sqlstate, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            dead_letter.id,
+            dead_letter.vessel_id,
+            dead_letter.frame_id,
+            dead_letter.payload,
+            dead_letter.decode_rejections,
+            dead_letter.error_class,
+            dead_letter.error_message,
+            dead_letter.sqlstate,
+            dead_letter.correlation_id,
+            dead_letter.trace_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<DeadLetter>, sqlx::Error> {
+        sqlx::query_as!(
+            DeadLetter,
+            r#"
+            SELECT id, vessel_id, frame_id, payload, decode_rejections, error_class, error_message,
+                   sqlstate, retry_count, created_at, last_retried_at, correlation_id, trace_id
+            FROM dead_letter_frames
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn find_all(
+        &self,
+        vessel_id: Option<String>,
+        error_class: Option<String>,
+        limit: i64,
+    ) -> Result<Vec<DeadLetter>, sqlx::Error> {
+        sqlx::query_as!(
+            DeadLetter,
+            r#"
+            SELECT id, vessel_id, frame_id, payload, decode_rejections, error_class, error_message,
+                   sqlstate, retry_count, created_at, last_retried_at, correlation_id, trace_id
+            FROM dead_letter_frames
+            WHERE ($1::text IS NULL OR vessel_id = $1)
+              AND ($2::text IS NULL OR error_class = $2)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+            vessel_id,
+            error_class,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Keeps the payload that was tried and the error it failed with
+    pub async fn record_retry_failure(
+        &self,
+        id: Uuid,
+        payload: &JsonValue,
+        error_class: &str,
+        error_message: &str,
+        sqlstate: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE dead_letter_frames
+            SET payload = $2, error_class = $3, error_message = $4, sqlstate = $5,
+                retry_count = retry_count + 1, last_retried_at = NOW()
+            WHERE id = $1
+            "#,
+            id,
+            payload,
+            error_class,
+            error_message,
+            sqlstate
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// `false` when no dead letter had this ID
+    pub async fn delete(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM dead_letter_frames WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn purge(
+        &self,
+        vessel_id: Option<String>,
+        error_class: Option<String>,
+        older_than_hours: Option<f64>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM dead_letter_frames
+            WHERE ($1::text IS NULL OR vessel_id = $1)
+              AND ($2::text IS NULL OR error_class = $2)
+              AND ($3::float8 IS NULL OR created_at < NOW() - INTERVAL '1 hour' * $3)
+            "#,
+            vessel_id,
+            error_class,
+            older_than_hours
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -1,5 +1,9 @@
+use crate::models::metrics::{
+    AdmissionShedSummary, CompressionStats, MetricRecord, UdpRejectionSummary,
+};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
+use serde_json::Value as JsonValue;
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -37,6 +41,62 @@ impl MetricsRepository {
         Ok(())
     }
 
+    /// Writes many metric rows in one statement
+    pub async fn insert_metrics(&self, metrics: &[MetricRecord]) -> Result<(), sqlx::Error> {
+        if metrics.is_empty() {
+            return Ok(());
+        }
+
+        let vessel_ids: Vec<Option<String>> = metrics.iter().map(|m| m.vessel_id.clone()).collect();
+        let metric_types: Vec<String> = metrics.iter().map(|m| m.metric_type.clone()).collect();
+        let metric_values: Vec<Decimal> = metrics.iter().map(|m| m.metric_value).collect();
+        let correlation_ids: Vec<Uuid> = metrics.iter().map(|m| m.correlation_id).collect();
+        let trace_ids: Vec<String> = metrics.iter().map(|m| m.trace_id.clone()).collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO server_metrics (vessel_id, metric_type, metric_value, correlation_id, trace_id)
+            SELECT * FROM UNNEST($1::VARCHAR[], $2::VARCHAR[], $3::DECIMAL[], $4::UUID[], $5::VARCHAR[])
+            "#,
+            &vessel_ids as &[Option<String>],
+            &metric_types,
+            &metric_values,
+            &correlation_ids,
+            &trace_ids
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn insert_metric_with_metadata(
+        &self,
+        vessel_id: Option<String>,
+        metric_type: String,
+        metric_value: Decimal,
+        additional_metadata: JsonValue,
+        correlation_id: Uuid,
+        trace_id: String,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO server_metrics (vessel_id, metric_type, metric_value, additional_metadata, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            vessel_id,
+            metric_type,
+            metric_value,
+            additional_metadata,
+            correlation_id,
+            trace_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn get_request_count_last_n_minutes(&self, minutes: f64) -> Result<i64, sqlx::Error> {
         let result = sqlx::query!(
             r#"
@@ -183,4 +243,76 @@ impl MetricsRepository {
             })
             .collect())
     }
+
+    pub async fn get_compression_summary(
+        &self,
+        hours: f64,
+    ) -> Result<Vec<CompressionStats>, sqlx::Error> {
+        sqlx::query_as!(
+            CompressionStats,
+            r#"
+            SELECT
+                vessel_id as "vessel_id!",
+                COUNT(*) as "compressed_requests!",
+                COALESCE(SUM((additional_metadata->>'compressed_bytes')::BIGINT), 0) as "compressed_bytes!",
+                COALESCE(SUM((additional_metadata->>'decompressed_bytes')::BIGINT), 0) as "decompressed_bytes!",
+                COALESCE(AVG(metric_value), 0) as "avg_compression_ratio!"
+            FROM server_metrics
+            WHERE metric_type = 'compression_ratio'
+              AND timestamp > NOW() - INTERVAL '1 hour' * $1
+              AND vessel_id IS NOT NULL
+            GROUP BY vessel_id
+            ORDER BY vessel_id
+            "#,
+            hours
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_udp_rejection_summary(
+        &self,
+        hours: f64,
+    ) -> Result<Vec<UdpRejectionSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            UdpRejectionSummary,
+            r#"
+            SELECT
+                vessel_id,
+                COALESCE(additional_metadata->>'reason', 'unknown') as "reason!",
+                COALESCE(SUM(metric_value), 0)::BIGINT as "datagrams!"
+            FROM server_metrics
+            WHERE metric_type = 'udp_datagram_rejected'
+              AND timestamp > NOW() - INTERVAL '1 hour' * $1
+            GROUP BY vessel_id, additional_metadata->>'reason'
+            ORDER BY vessel_id NULLS FIRST, 2
+            "#,
+            hours
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_admission_shed_summary(
+        &self,
+        hours: f64,
+    ) -> Result<Vec<AdmissionShedSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            AdmissionShedSummary,
+            r#"
+            SELECT
+                COALESCE(additional_metadata->>'priority', 'unknown') as "priority!",
+                COALESCE(additional_metadata->>'reason', 'unknown') as "reason!",
+                COALESCE(SUM(metric_value), 0)::BIGINT as "requests!"
+            FROM server_metrics
+            WHERE metric_type = 'admission_shed'
+              AND timestamp > NOW() - INTERVAL '1 hour' * $1
+            GROUP BY 1, 2
+            ORDER BY 1, 2
+            "#,
+            hours
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
 }
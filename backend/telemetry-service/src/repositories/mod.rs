@@ -1,5 +1,44 @@
 pub mod auth;
+pub mod backfill;
+pub mod dead_letter;
 pub mod metrics;
+pub mod session;
 pub mod signal;
+pub mod signal_mapping;
 pub mod telemetry;
 pub mod vessel;
+pub mod vessel_class;
+
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Every repository, sharing one pool; handed to `Services::new`
+pub struct Repositories {
+    pub vessel: Arc<vessel::VesselRepository>,
+    pub signal: Arc<signal::SignalRepository>,
+    pub telemetry: Arc<telemetry::TelemetryRepository>,
+    pub metrics: Arc<metrics::MetricsRepository>,
+    pub auth: Arc<auth::AuthRepository>,
+    pub signal_mapping: Arc<signal_mapping::SignalMappingRepository>,
+    pub session: Arc<session::SessionRepository>,
+    pub backfill: Arc<backfill::BackfillRepository>,
+    pub dead_letter: Arc<dead_letter::DeadLetterRepository>,
+    pub vessel_class: Arc<vessel_class::VesselClassRepository>,
+}
+
+impl Repositories {
+    pub fn new(db: PgPool) -> Self {
+        Self {
+            vessel: Arc::new(vessel::VesselRepository::new(db.clone())),
+            signal: Arc::new(signal::SignalRepository::new(db.clone())),
+            telemetry: Arc::new(telemetry::TelemetryRepository::new(db.clone())),
+            metrics: Arc::new(metrics::MetricsRepository::new(db.clone())),
+            auth: Arc::new(auth::AuthRepository::new(db.clone())),
+            signal_mapping: Arc::new(signal_mapping::SignalMappingRepository::new(db.clone())),
+            session: Arc::new(session::SessionRepository::new(db.clone())),
+            backfill: Arc::new(backfill::BackfillRepository::new(db.clone())),
+            dead_letter: Arc::new(dead_letter::DeadLetterRepository::new(db.clone())),
+            vessel_class: Arc::new(vessel_class::VesselClassRepository::new(db)),
+        }
+    }
+}
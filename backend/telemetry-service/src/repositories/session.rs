@@ -0,0 +1,107 @@
+use crate::models::session::{IngestionSession, SessionCounters};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct SessionRepository {
+    pool: PgPool,
+}
+
+impl SessionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn open(
+        &self,
+        session_id: Uuid,
+        vessel_id: &str,
+        transport: &str,
+        correlation_id: Uuid,
+        trace_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO ingestion_sessions (session_id, vessel_id, transport, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            session_id,
+            vessel_id,
+            transport,
+            correlation_id,
+            trace_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn close(
+        &self,
+        session_id: Uuid,
+        end_reason: &str,
+        counters: &SessionCounters,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE ingestion_sessions
+            SET disconnected_at = NOW(),
+                end_reason = $2,
+                frames_received = $3,
+                frames_accepted = $4,
+                frames_rejected = $5
+            WHERE session_id = $1 AND disconnected_at IS NULL
+            "#,
+            session_id,
+            end_reason,
+            counters.frames_received,
+            counters.frames_accepted,
+            counters.frames_rejected
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Closes every open session; only valid before any connection is accepted
+    pub async fn close_all_open(&self, end_reason: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE ingestion_sessions
+            SET disconnected_at = NOW(), end_reason = $1
+            WHERE disconnected_at IS NULL
+            "#,
+            end_reason
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find(
+        &self,
+        vessel_id: Option<String>,
+        active_only: bool,
+        limit: i64,
+    ) -> Result<Vec<IngestionSession>, sqlx::Error> {
+        sqlx::query_as!(
+            IngestionSession,
+            r#"
+            SELECT session_id, vessel_id, transport, connected_at, disconnected_at, end_reason,
+                   frames_received, frames_accepted, frames_rejected
+            FROM ingestion_sessions
+            WHERE ($1::text IS NULL OR vessel_id = $1)
+              AND (NOT $2 OR disconnected_at IS NULL)
+            ORDER BY connected_at DESC
+            LIMIT $3
+            "#,
+            vessel_id,
+            active_only,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
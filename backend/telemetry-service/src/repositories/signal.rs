@@ -1,6 +1,7 @@
-use crate::models::signal::Signal;
+use crate::models::signal::{CatalogueSignal, Signal, SignalDefinition};
 use sqlx::PgPool;
 use std::collections::HashMap;
+use uuid::Uuid;
 
 pub struct SignalRepository {
     pool: PgPool,
@@ -11,11 +12,12 @@ impl SignalRepository {
         Self { pool }
     }
 
+    /// Every signal keyed by name, retired ones included
     pub async fn find_all(&self) -> Result<HashMap<String, Signal>, sqlx::Error> {
         let signals = sqlx::query_as!(
             Signal,
             r#"
-            SELECT signal_id, signal_name, signal_type, min_value, max_value, description, created_at, updated_at, correlation_id, trace_id
+            SELECT signal_id, signal_name, signal_type, min_value, max_value, description, created_at, updated_at, correlation_id, trace_id, unit, retired_at
             FROM signal_register_table
             "#
         )
@@ -29,4 +31,170 @@ impl SignalRepository {
 
         Ok(map)
     }
+
+    pub async fn find_by_name(&self, signal_name: &str) -> Result<Option<Signal>, sqlx::Error> {
+        sqlx::query_as!(
+            Signal,
+            r#"
+            SELECT signal_id, signal_name, signal_type, min_value, max_value, description, created_at, updated_at, correlation_id, trace_id, unit, retired_at
+            FROM signal_register_table
+            WHERE signal_name = $1
+            "#,
+            signal_name
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// `name_like` is a `LIKE` pattern
+    pub async fn list(
+        &self,
+        signal_type: Option<String>,
+        name_like: Option<String>,
+        include_retired: bool,
+    ) -> Result<Vec<Signal>, sqlx::Error> {
+        sqlx::query_as!(
+            Signal,
+            r#"
+            SELECT signal_id, signal_name, signal_type, min_value, max_value, description, created_at, updated_at, correlation_id, trace_id, unit, retired_at
+            FROM signal_register_table
+            WHERE ($1::text IS NULL OR signal_type = $1)
+              AND ($2::text IS NULL OR signal_name LIKE $2)
+              AND ($3 OR retired_at IS NULL)
+            ORDER BY signal_id
+            "#,
+            signal_type,
+            name_like,
+            include_retired
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn create(
+        &self,
+        signal_name: &str,
+        definition: &SignalDefinition,
+        correlation_id: Uuid,
+        trace_id: Option<String>,
+    ) -> Result<Signal, sqlx::Error> {
+        sqlx::query_as!(
+            Signal,
+            r#"
+            INSERT INTO signal_register_table
+                (signal_name, signal_type, min_value, max_value, description, unit, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING signal_id, signal_name, signal_type, min_value, max_value, description, created_at, updated_at, correlation_id, trace_id, unit, retired_at
+            "#,
+            signal_name,
+            definition.signal_type,
+            definition.min_value,
+            definition.max_value,
+            definition.description,
+            definition.unit,
+            correlation_id,
+            trace_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Replaces a signal's definition; a retired signal is brought back
+    pub async fn update(
+        &self,
+        signal_name: &str,
+        definition: &SignalDefinition,
+        correlation_id: Uuid,
+        trace_id: Option<String>,
+    ) -> Result<Option<Signal>, sqlx::Error> {
+        sqlx::query_as!(
+            Signal,
+            r#"
+            UPDATE signal_register_table
+            SET signal_type = $2, min_value = $3, max_value = $4, description = $5, unit = $6,
+                correlation_id = $7, trace_id = $8, retired_at = NULL, updated_at = NOW()
+            WHERE signal_name = $1
+            RETURNING signal_id, signal_name, signal_type, min_value, max_value, description, created_at, updated_at, correlation_id, trace_id, unit, retired_at
+            "#,
+            signal_name,
+            definition.signal_type,
+            definition.min_value,
+            definition.max_value,
+            definition.description,
+            definition.unit,
+            correlation_id,
+            trace_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// `false` when no active signal has this name
+    pub async fn retire(&self, signal_name: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE signal_register_table
+            SET retired_at = NOW(), updated_at = NOW()
+            WHERE signal_name = $1 AND retired_at IS NULL
+            "#,
+            signal_name
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Writes an imported catalogue in one transaction: `upserts` are created or replaced (and
+    /// brought back if retired), `retire` are retired
+    pub async fn apply_catalogue(
+        &self,
+        upserts: &[CatalogueSignal],
+        retire: &[String],
+        correlation_id: Uuid,
+        trace_id: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for signal in upserts {
+            sqlx::query!(
+                r#"
+                INSERT INTO signal_register_table
+                    (signal_name, signal_type, min_value, max_value, description, unit, correlation_id, trace_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (signal_name) DO UPDATE
+                SET signal_type = EXCLUDED.signal_type, min_value = EXCLUDED.min_value,
+                    max_value = EXCLUDED.max_value, description = EXCLUDED.description,
+                    unit = EXCLUDED.unit, correlation_id = EXCLUDED.correlation_id,
+                    trace_id = EXCLUDED.trace_id, retired_at = NULL, updated_at = NOW()
+                "#,
+                signal.signal_name,
+                signal.signal_type,
+                signal.min_value,
+                signal.max_value,
+                signal.description,
+                signal.unit,
+                correlation_id,
+                trace_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE signal_register_table
+            SET retired_at = NOW(), updated_at = NOW(), correlation_id = $2, trace_id = $3
+            WHERE signal_name = ANY($1) AND retired_at IS NULL
+            "#,
+            retire,
+            correlation_id,
+            trace_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
 }
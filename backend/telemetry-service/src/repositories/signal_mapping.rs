@@ -0,0 +1,108 @@
+use crate::models::signal_mapping::SignalMapping;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub struct SignalMappingRepository {
+    pool: PgPool,
+}
+
+impl SignalMappingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Mappings for one source keyed by `(message_type, field_name)`
+    pub async fn find_by_source(
+        &self,
+        source: &str,
+    ) -> Result<HashMap<(String, String), String>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT message_type, field_name, signal_name
+            FROM signal_source_mappings
+            WHERE source = $1
+            "#,
+            source
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| ((r.message_type, r.field_name), r.signal_name))
+            .collect())
+    }
+
+    pub async fn find_all(
+        &self,
+        source: Option<String>,
+    ) -> Result<Vec<SignalMapping>, sqlx::Error> {
+        sqlx::query_as!(
+            SignalMapping,
+            r#"
+            SELECT source, message_type, field_name, signal_name, created_at, updated_at, correlation_id, trace_id
+            FROM signal_source_mappings
+            WHERE ($1::text IS NULL OR source = $1)
+            ORDER BY source, message_type, field_name
+            "#,
+            source
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn upsert(
+        &self,
+        source: &str,
+        message_type: &str,
+        field_name: &str,
+        signal_name: &str,
+        correlation_id: Uuid,
+        trace_id: Option<String>,
+    ) -> Result<SignalMapping, sqlx::Error> {
+        sqlx::query_as!(
+            SignalMapping,
+            r#"
+            INSERT INTO signal_source_mappings (source, message_type, field_name, signal_name, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (source, message_type, field_name) DO UPDATE
+                SET signal_name = EXCLUDED.signal_name,
+                    correlation_id = EXCLUDED.correlation_id,
+                    trace_id = EXCLUDED.trace_id,
+                    updated_at = NOW()
+            RETURNING source, message_type, field_name, signal_name, created_at, updated_at, correlation_id, trace_id
+            "#,
+            source,
+            message_type,
+            field_name,
+            signal_name,
+            correlation_id,
+            trace_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Returns `false` when no such mapping existed
+    pub async fn delete(
+        &self,
+        source: &str,
+        message_type: &str,
+        field_name: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM signal_source_mappings
+            WHERE source = $1 AND message_type = $2 AND field_name = $3
+            "#,
+            source,
+            message_type,
+            field_name
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,7 +1,13 @@
-use crate::models::telemetry::{TelemetryFiltered, TelemetryRaw};
+use crate::config::ConflictPolicy;
+use crate::models::telemetry::{
+    FilteredRecord, FrameClaim, INGESTION_COMMITTED, INGESTION_DUPLICATE, IngestionDelayStats,
+    IngestionStatusRecord, QueuedFrame, RawRecord, StoredCursor, StoredTelemetry,
+    StoredTelemetryQuery,
+};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use serde_json::Value as JsonValue;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 pub struct TelemetryRepository {
@@ -13,88 +19,648 @@ impl TelemetryRepository {
         Self { pool }
     }
 
-    pub async fn insert_raw_batch(
+    /// Starts a buffer that collects many frames and writes them together
+    pub fn writer(&self, policy: ConflictPolicy, max_frames: usize) -> TelemetryWriter {
+        TelemetryWriter {
+            pool: self.pool.clone(),
+            policy,
+            max_frames,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Writes one frame's raw and filtered rows in a single transaction.
+    ///
+    /// With a `claim`, the client frame ID is recorded in the same transaction. Returns `false`
+    /// (and writes nothing) when that frame ID was already ingested inside the dedup window.
+    pub async fn insert_frame(
         &self,
-        records: &Vec<(String, DateTime<Utc>, i64, String, Decimal, Uuid, String)>,
+        raw: &[RawRecord],
+        filtered: &[FilteredRecord],
+        policy: ConflictPolicy,
+        claim: Option<&FrameClaim>,
+    ) -> Result<bool, sqlx::Error> {
+        let frame = FrameRows {
+            raw,
+            filtered,
+            claim,
+        };
+
+        let mut tx = self.pool.begin().await?;
+        let written = Self::write_frames(&mut tx, &[frame], policy).await?;
+        tx.commit().await?;
+
+        Ok(written[0])
+    }
+
+    /// Writes a batch of queued frames and their final status in a single transaction.
+    ///
+    /// A frame whose ID was already claimed is skipped and recorded as a duplicate; the rest of
+    /// the batch is still written. Returns the status recorded for each frame, in order.
+    pub async fn insert_queued_frames(
+        &self,
+        frames: &[QueuedFrame],
+        policy: ConflictPolicy,
+    ) -> Result<Vec<IngestionStatusRecord>, sqlx::Error> {
+        let rows: Vec<FrameRows> = frames
+            .iter()
+            .map(|frame| FrameRows {
+                raw: &frame.raw,
+                filtered: &frame.filtered,
+                claim: frame.claim.as_ref(),
+            })
+            .collect();
+
+        let mut tx = self.pool.begin().await?;
+        let written = Self::write_frames(&mut tx, &rows, policy).await?;
+
+        let statuses: Vec<IngestionStatusRecord> = frames
+            .iter()
+            .zip(written)
+            .map(|(frame, written)| match written {
+                true => IngestionStatusRecord::for_frame(frame, INGESTION_COMMITTED, None),
+                false => IngestionStatusRecord::for_frame(
+                    frame,
+                    INGESTION_DUPLICATE,
+                    Some("Frame ID was already ingested inside the dedup window".to_string()),
+                ),
+            })
+            .collect();
+        Self::write_statuses(&mut tx, &statuses).await?;
+
+        tx.commit().await?;
+        Ok(statuses)
+    }
+
+    /// Records outcomes written outside `insert_queued_frames`, such as failed frames
+    pub async fn insert_ingestion_statuses(
+        &self,
+        statuses: &[IngestionStatusRecord],
     ) -> Result<(), sqlx::Error> {
         let mut tx = self.pool.begin().await?;
+        Self::write_statuses(&mut tx, statuses).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn find_ingestion_status(
+        &self,
+        correlation_id: Uuid,
+    ) -> Result<Option<IngestionStatusRecord>, sqlx::Error> {
+        sqlx::query_as!(
+            IngestionStatusRecord,
+            r#"
+            SELECT correlation_id, vessel_id, frame_id, status, valid_signals, invalid_signals,
+                   error, accepted_at, completed_at as "completed_at?"
+            FROM async_ingestion_status
+            WHERE correlation_id = $1
+            "#,
+            correlation_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Removes outcomes older than the retention window; returns the number removed
+    pub async fn purge_ingestion_statuses(&self, retention_secs: f64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM async_ingestion_status
+            WHERE completed_at < NOW() - INTERVAL '1 second' * $1
+            "#,
+            retention_secs
+        )
+        .execute(&self.pool)
+        .await?;
 
-        for (
+        Ok(result.rows_affected())
+    }
+
+    /// Removes frame ID claims past the dedup window, which can no longer block a frame; returns
+    /// the number removed
+    pub async fn purge_ingested_frames(&self, window_secs: f64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM ingested_frames
+            WHERE created_at < NOW() - INTERVAL '1 second' * $1
+            "#,
+            window_secs
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Returns the stored response for a frame ID ingested inside the dedup window
+    pub async fn find_ingested_frame(
+        &self,
+        vessel_id: &str,
+        frame_id: &str,
+        window_secs: f64,
+    ) -> Result<Option<JsonValue>, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            SELECT response
+            FROM ingested_frames
+            WHERE vessel_id = $1
+              AND frame_id = $2
+              AND created_at > NOW() - INTERVAL '1 second' * $3
+            "#,
             vessel_id,
-            timestamp_utc,
-            epoch_utc,
-            signal_name,
-            signal_value,
-            correlation_id,
-            trace_id,
-        ) in records
-        {
-            sqlx::query!(
-                r#"
-                INSERT INTO telemetry_raw (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
-                "#,
-                vessel_id,
-                timestamp_utc,
-                epoch_utc,
-                signal_name,
-                signal_value,
-                correlation_id,
-                trace_id
+            frame_id,
+            window_secs
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.map(|r| r.response))
+    }
+
+    /// Ingestion delay distribution per vessel, counting each frame once
+    pub async fn get_ingestion_delays(
+        &self,
+        vessel_id: Option<String>,
+        hours: f64,
+    ) -> Result<Vec<IngestionDelayStats>, sqlx::Error> {
+        sqlx::query_as!(
+            IngestionDelayStats,
+            r#"
+            WITH frames AS (
+                SELECT vessel_id,
+                       EXTRACT(EPOCH FROM (MIN(ingested_at) - timestamp_utc))::DOUBLE PRECISION AS delay
+                FROM telemetry_raw
+                WHERE ingested_at > NOW() - INTERVAL '1 hour' * $2
+                  AND ($1::text IS NULL OR vessel_id = $1)
+                GROUP BY vessel_id, correlation_id, timestamp_utc
             )
-                .execute(&mut *tx)
+            SELECT
+                vessel_id as "vessel_id!",
+                COUNT(*) as "frames!",
+                MIN(delay) as "min_delay_secs!",
+                PERCENTILE_CONT(0.05) WITHIN GROUP (ORDER BY delay) as "p05_delay_secs!",
+                PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY delay) as "median_delay_secs!",
+                MAX(delay) as "max_delay_secs!"
+            FROM frames
+            GROUP BY vessel_id
+            ORDER BY vessel_id
+            "#,
+            vessel_id,
+            hours
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Up to `limit` stored samples in timestamp order, starting after `after` when given
+    pub async fn find_stored_page(
+        &self,
+        query: &StoredTelemetryQuery,
+        after: Option<&StoredCursor>,
+        limit: i64,
+    ) -> Result<Vec<StoredTelemetry>, sqlx::Error> {
+        sqlx::query_as!(
+            StoredTelemetry,
+            r#"
+            SELECT id, timestamp_utc, signal_name, signal_value, correlation_id
+            FROM telemetry_raw
+            WHERE vessel_id = $1
+              AND timestamp_utc >= $2
+              AND timestamp_utc < $3
+              AND (cardinality($4::text[]) = 0 OR signal_name = ANY($4))
+              AND ($5::timestamptz IS NULL OR (timestamp_utc, signal_name, id) > ($5, $6, $7))
+            ORDER BY timestamp_utc, signal_name, id
+            LIMIT $8
+            "#,
+            query.vessel_id,
+            query.from,
+            query.to,
+            &query.signal_names,
+            after.map(|cursor| cursor.timestamp_utc),
+            after.map(|cursor| cursor.signal_name.as_str()),
+            after.map(|cursor| cursor.id),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Records a client frame ID; `false` when it was already ingested inside the dedup window
+    async fn claim_frame(
+        tx: &mut Transaction<'_, Postgres>,
+        claim: &FrameClaim,
+    ) -> Result<bool, sqlx::Error> {
+        // A replay inside the window leaves the existing row untouched and returns nothing;
+        // an expired entry is taken over by the new frame.
+        let claimed = sqlx::query!(
+            r#"
+            INSERT INTO ingested_frames (vessel_id, frame_id, correlation_id, response)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (vessel_id, frame_id) DO UPDATE
+                SET correlation_id = EXCLUDED.correlation_id,
+                    response = EXCLUDED.response,
+                    created_at = NOW()
+                WHERE ingested_frames.created_at <= NOW() - INTERVAL '1 second' * $5
+            RETURNING frame_id
+            "#,
+            claim.vessel_id,
+            claim.frame_id,
+            claim.correlation_id,
+            claim.response,
+            claim.window_secs
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(claimed.is_some())
+    }
+
+    /// Claims frame IDs, then writes the rows of every claimed frame with one statement per table.
+    ///
+    /// Returns, per frame, whether it was written; a frame whose ID was already ingested inside
+    /// the dedup window is skipped.
+    async fn write_frames(
+        tx: &mut Transaction<'_, Postgres>,
+        frames: &[FrameRows<'_>],
+        policy: ConflictPolicy,
+    ) -> Result<Vec<bool>, sqlx::Error> {
+        let mut written = Vec::with_capacity(frames.len());
+        for frame in frames {
+            written.push(match frame.claim {
+                Some(claim) => Self::claim_frame(tx, claim).await?,
+                None => true,
+            });
+        }
+
+        let claimed = || {
+            frames
+                .iter()
+                .zip(&written)
+                .filter(|(_, written)| **written)
+                .map(|(frame, _)| frame)
+        };
+        Self::write_raw(tx, claimed().flat_map(|f| f.raw), policy).await?;
+        Self::write_filtered(tx, claimed().flat_map(|f| f.filtered)).await?;
+
+        Ok(written)
+    }
+
+    /// Inserts raw rows with a single `UNNEST` statement.
+    ///
+    /// Conflicts are settled by the unique index on the sample key, so concurrent frames for the
+    /// same sample cannot both insert. Repeats of a key within one call are resolved the way
+    /// row-by-row inserts would resolve them: `Ignore` keeps the first, `Overwrite` the last.
+    async fn write_raw<'r>(
+        tx: &mut Transaction<'_, Postgres>,
+        records: impl IntoIterator<Item = &'r RawRecord>,
+        policy: ConflictPolicy,
+    ) -> Result<(), sqlx::Error> {
+        let mut columns = RawColumns::default();
+        for record in records {
+            columns.push(record);
+        }
+        if columns.vessel_ids.is_empty() {
+            return Ok(());
+        }
+
+        match policy {
+            ConflictPolicy::KeepBoth => {
+                // The first copy of a new sample takes the indexed slot; every other copy is
+                // stored flagged as a duplicate
+                sqlx::query!(
+                    r#"
+                    WITH input AS (
+                        SELECT u.*, ROW_NUMBER() OVER (PARTITION BY u.vessel_id, u.signal_name, u.timestamp_utc ORDER BY u.ord) AS copy
+                        FROM UNNEST($1::VARCHAR[], $2::TIMESTAMPTZ[], $3::BIGINT[], $4::VARCHAR[], $5::DECIMAL[], $6::UUID[], $7::VARCHAR[])
+                             WITH ORDINALITY AS u(vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id, ord)
+                    ),
+                    first AS (
+                        INSERT INTO telemetry_raw (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id)
+                        SELECT i.vessel_id, i.timestamp_utc, i.epoch_utc, i.signal_name, i.signal_value, i.correlation_id, i.trace_id
+                        FROM input i
+                        WHERE i.copy = 1
+                        ON CONFLICT (vessel_id, signal_name, timestamp_utc) WHERE NOT duplicate_sample DO NOTHING
+                        RETURNING vessel_id, signal_name, timestamp_utc
+                    )
+                    INSERT INTO telemetry_raw (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id, duplicate_sample)
+                    SELECT i.vessel_id, i.timestamp_utc, i.epoch_utc, i.signal_name, i.signal_value, i.correlation_id, i.trace_id, TRUE
+                    FROM input i
+                    WHERE NOT (i.copy = 1 AND EXISTS (
+                        SELECT 1 FROM first f
+                        WHERE f.vessel_id = i.vessel_id AND f.signal_name = i.signal_name AND f.timestamp_utc = i.timestamp_utc
+                    ))
+                    "#,
+                    &columns.vessel_ids,
+                    &columns.timestamps,
+                    &columns.epochs,
+                    &columns.signal_names,
+                    &columns.values,
+                    &columns.correlation_ids,
+                    &columns.trace_ids
+                )
+                .execute(&mut **tx)
+                .await?;
+            }
+            ConflictPolicy::Ignore => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO telemetry_raw (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id)
+                    SELECT DISTINCT ON (u.vessel_id, u.signal_name, u.timestamp_utc)
+                           u.vessel_id, u.timestamp_utc, u.epoch_utc, u.signal_name, u.signal_value, u.correlation_id, u.trace_id
+                    FROM UNNEST($1::VARCHAR[], $2::TIMESTAMPTZ[], $3::BIGINT[], $4::VARCHAR[], $5::DECIMAL[], $6::UUID[], $7::VARCHAR[])
+                         WITH ORDINALITY AS u(vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id, ord)
+                    ORDER BY u.vessel_id, u.signal_name, u.timestamp_utc, u.ord
+                    ON CONFLICT (vessel_id, signal_name, timestamp_utc) WHERE NOT duplicate_sample DO NOTHING
+                    "#,
+                    &columns.vessel_ids,
+                    &columns.timestamps,
+                    &columns.epochs,
+                    &columns.signal_names,
+                    &columns.values,
+                    &columns.correlation_ids,
+                    &columns.trace_ids
+                )
+                .execute(&mut **tx)
                 .await?;
+            }
+            ConflictPolicy::Overwrite => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO telemetry_raw (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id)
+                    SELECT DISTINCT ON (u.vessel_id, u.signal_name, u.timestamp_utc)
+                           u.vessel_id, u.timestamp_utc, u.epoch_utc, u.signal_name, u.signal_value, u.correlation_id, u.trace_id
+                    FROM UNNEST($1::VARCHAR[], $2::TIMESTAMPTZ[], $3::BIGINT[], $4::VARCHAR[], $5::DECIMAL[], $6::UUID[], $7::VARCHAR[])
+                         WITH ORDINALITY AS u(vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, correlation_id, trace_id, ord)
+                    ORDER BY u.vessel_id, u.signal_name, u.timestamp_utc, u.ord DESC
+                    ON CONFLICT (vessel_id, signal_name, timestamp_utc) WHERE NOT duplicate_sample DO UPDATE
+                        SET epoch_utc = EXCLUDED.epoch_utc, signal_value = EXCLUDED.signal_value,
+                            correlation_id = EXCLUDED.correlation_id, trace_id = EXCLUDED.trace_id, ingested_at = NOW()
+                    "#,
+                    &columns.vessel_ids,
+                    &columns.timestamps,
+                    &columns.epochs,
+                    &columns.signal_names,
+                    &columns.values,
+                    &columns.correlation_ids,
+                    &columns.trace_ids
+                )
+                .execute(&mut **tx)
+                .await?;
+            }
         }
 
-        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn insert_filtered_batch(
-        &self,
-        records: &Vec<(
-            String,
-            DateTime<Utc>,
-            i64,
-            String,
-            Decimal,
-            String,
-            Uuid,
-            String,
-        )>,
+    async fn write_statuses(
+        tx: &mut Transaction<'_, Postgres>,
+        statuses: &[IngestionStatusRecord],
     ) -> Result<(), sqlx::Error> {
+        if statuses.is_empty() {
+            return Ok(());
+        }
+
+        let correlation_ids: Vec<Uuid> = statuses.iter().map(|s| s.correlation_id).collect();
+        let vessel_ids: Vec<String> = statuses.iter().map(|s| s.vessel_id.clone()).collect();
+        let frame_ids: Vec<Option<String>> = statuses.iter().map(|s| s.frame_id.clone()).collect();
+        let status_values: Vec<String> = statuses.iter().map(|s| s.status.clone()).collect();
+        let valid_signals: Vec<i32> = statuses.iter().map(|s| s.valid_signals).collect();
+        let invalid_signals: Vec<i32> = statuses.iter().map(|s| s.invalid_signals).collect();
+        let errors: Vec<Option<String>> = statuses.iter().map(|s| s.error.clone()).collect();
+        let accepted_at: Vec<_> = statuses.iter().map(|s| s.accepted_at).collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO async_ingestion_status
+                (correlation_id, vessel_id, frame_id, status, valid_signals, invalid_signals, error, accepted_at)
+            SELECT * FROM UNNEST($1::UUID[], $2::VARCHAR[], $3::VARCHAR[], $4::VARCHAR[], $5::INTEGER[], $6::INTEGER[], $7::TEXT[], $8::TIMESTAMPTZ[])
+            ON CONFLICT (correlation_id) DO UPDATE
+                SET status = EXCLUDED.status,
+                    error = EXCLUDED.error,
+                    completed_at = NOW()
+            "#,
+            &correlation_ids,
+            &vessel_ids,
+            &frame_ids as &[Option<String>],
+            &status_values,
+            &valid_signals,
+            &invalid_signals,
+            &errors as &[Option<String>],
+            &accepted_at
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn write_filtered<'r>(
+        tx: &mut Transaction<'_, Postgres>,
+        records: impl IntoIterator<Item = &'r FilteredRecord>,
+    ) -> Result<(), sqlx::Error> {
+        let mut columns = FilteredColumns::default();
+        for record in records {
+            columns.push(record);
+        }
+        if columns.vessel_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO telemetry_filtered (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, reason, correlation_id, trace_id)
+            SELECT * FROM UNNEST($1::VARCHAR[], $2::TIMESTAMPTZ[], $3::BIGINT[], $4::VARCHAR[], $5::DECIMAL[], $6::VARCHAR[], $7::UUID[], $8::VARCHAR[])
+            "#,
+            &columns.vessel_ids,
+            &columns.timestamps,
+            &columns.epochs,
+            &columns.signal_names,
+            &columns.values,
+            &columns.reasons,
+            &columns.correlation_ids,
+            &columns.trace_ids
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Buffers rows from many frames and writes them in one transaction.
+///
+/// Shared by streaming uploads, backfill imports and benchmarks, so a flush costs one statement
+/// per table however many frames and signals it holds. Frames with a claim are still deduplicated
+/// one by one.
+pub struct TelemetryWriter {
+    pool: PgPool,
+    policy: ConflictPolicy,
+    max_frames: usize,
+    frames: Vec<BufferedFrame>,
+}
+
+struct BufferedFrame {
+    raw: Vec<RawRecord>,
+    filtered: Vec<FilteredRecord>,
+    claim: Option<FrameClaim>,
+}
+
+impl TelemetryWriter {
+    pub fn push(
+        &mut self,
+        raw: Vec<RawRecord>,
+        filtered: Vec<FilteredRecord>,
+        claim: Option<FrameClaim>,
+    ) {
+        self.frames.push(BufferedFrame {
+            raw,
+            filtered,
+            claim,
+        });
+    }
+
+    /// Frames buffered since the last flush
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Whether the buffer reached the size it was created with
+    pub fn is_full(&self) -> bool {
+        self.frames.len() >= self.max_frames
+    }
+
+    /// Buffered (raw, filtered) row counts
+    pub fn buffered_rows(&self) -> (usize, usize) {
+        self.frames.iter().fold((0, 0), |(raw, filtered), frame| {
+            (raw + frame.raw.len(), filtered + frame.filtered.len())
+        })
+    }
+
+    /// Writes every buffered frame in one transaction and empties the buffer.
+    ///
+    /// Returns, per frame in push order, whether it was written (`false` for a replayed frame ID).
+    /// On error nothing is written and the frames stay buffered, so they can be written one at a
+    /// time with [`flush_next`](Self::flush_next).
+    pub async fn flush(&mut self) -> Result<Vec<bool>, sqlx::Error> {
+        if self.frames.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows: Vec<FrameRows> = self.frames.iter().map(BufferedFrame::rows).collect();
+
         let mut tx = self.pool.begin().await?;
+        let written = TelemetryRepository::write_frames(&mut tx, &rows, self.policy).await?;
+        tx.commit().await?;
 
-        for (
+        self.frames.clear();
+        Ok(written)
+    }
+
+    /// Writes the oldest buffered frame in its own transaction and removes it from the buffer,
+    /// whatever the outcome; `None` when nothing is buffered
+    pub async fn flush_next(&mut self) -> Option<Result<bool, sqlx::Error>> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let frame = self.frames.remove(0);
+
+        let written = async {
+            let mut tx = self.pool.begin().await?;
+            let written =
+                TelemetryRepository::write_frames(&mut tx, &[frame.rows()], self.policy).await?;
+            tx.commit().await?;
+            Ok(written[0])
+        };
+        Some(written.await)
+    }
+}
+
+impl BufferedFrame {
+    fn rows(&self) -> FrameRows<'_> {
+        FrameRows {
+            raw: &self.raw,
+            filtered: &self.filtered,
+            claim: self.claim.as_ref(),
+        }
+    }
+}
+
+/// One frame's rows, borrowed from whichever buffer holds them
+struct FrameRows<'a> {
+    raw: &'a [RawRecord],
+    filtered: &'a [FilteredRecord],
+    claim: Option<&'a FrameClaim>,
+}
+
+/// Raw rows split into one array per column for `UNNEST`
+#[derive(Default)]
+struct RawColumns {
+    vessel_ids: Vec<String>,
+    timestamps: Vec<DateTime<Utc>>,
+    epochs: Vec<i64>,
+    signal_names: Vec<String>,
+    values: Vec<Decimal>,
+    correlation_ids: Vec<Uuid>,
+    trace_ids: Vec<String>,
+}
+
+impl RawColumns {
+    fn push(&mut self, record: &RawRecord) {
+        let (
             vessel_id,
             timestamp_utc,
             epoch_utc,
             signal_name,
             signal_value,
-            reason,
             correlation_id,
             trace_id,
-        ) in records
-        {
-            sqlx::query!(
-                r#"
-                INSERT INTO telemetry_filtered (vessel_id, timestamp_utc, epoch_utc, signal_name, signal_value, reason, correlation_id, trace_id)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                "#,
-                vessel_id,
-                timestamp_utc,
-                epoch_utc,
-                signal_name,
-                signal_value,
-                reason,
-                correlation_id,
-                trace_id
-            )
-                .execute(&mut *tx)
-                .await?;
-        }
+        ) = record;
+        self.vessel_ids.push(vessel_id.clone());
+        self.timestamps.push(*timestamp_utc);
+        self.epochs.push(*epoch_utc);
+        self.signal_names.push(signal_name.clone());
+        self.values.push(*signal_value);
+        self.correlation_ids.push(*correlation_id);
+        self.trace_ids.push(trace_id.clone());
+    }
+}
 
-        tx.commit().await?;
-        Ok(())
+/// Filtered rows split into one array per column for `UNNEST`
+#[derive(Default)]
+struct FilteredColumns {
+    vessel_ids: Vec<String>,
+    timestamps: Vec<DateTime<Utc>>,
+    epochs: Vec<i64>,
+    signal_names: Vec<String>,
+    values: Vec<Decimal>,
+    reasons: Vec<String>,
+    correlation_ids: Vec<Uuid>,
+    trace_ids: Vec<String>,
+}
+
+impl FilteredColumns {
+    fn push(&mut self, record: &FilteredRecord) {
+        let (
+            vessel_id,
+            timestamp_utc,
+            epoch_utc,
+            signal_name,
+            signal_value,
+            reason,
+            correlation_id,
+            trace_id,
+        ) = record;
+        self.vessel_ids.push(vessel_id.clone());
+        self.timestamps.push(*timestamp_utc);
+        self.epochs.push(*epoch_utc);
+        self.signal_names.push(signal_name.clone());
+        self.values.push(*signal_value);
+        self.reasons.push(reason.clone());
+        self.correlation_ids.push(*correlation_id);
+        self.trace_ids.push(trace_id.clone());
     }
 }
@@ -1,4 +1,4 @@
-use crate::models::vessel::Vessel;
+use crate::models::vessel::{AisStaticUpdate, Vessel};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -15,7 +15,7 @@ impl VesselRepository {
         sqlx::query_as!(
             Vessel,
             r#"
-            SELECT vessel_id, vessel_name, is_active as "is_active!", created_at as "created_at!", updated_at as "updated_at!", correlation_id, trace_id
+            SELECT vessel_id, vessel_name, is_active as "is_active!", created_at as "created_at!", updated_at as "updated_at!", correlation_id, trace_id, mmsi, ais_callsign, ais_ship_type, ais_destination, ais_eta, ais_draught, ais_static_updated_at, vessel_class
             FROM vessel_register_table
             WHERE vessel_id = $1 AND is_active = TRUE
             "#,
@@ -29,18 +29,20 @@ impl VesselRepository {
         &self,
         vessel_id: &str,
         vessel_name: &str,
+        mmsi: Option<i32>,
         correlation_id: Uuid,
         trace_id: Option<String>,
     ) -> Result<Vessel, sqlx::Error> {
         sqlx::query_as!(
             Vessel,
             r#"
-            INSERT INTO vessel_register_table (vessel_id, vessel_name, correlation_id, trace_id)
-            VALUES ($1, $2, $3, $4)
-            RETURNING vessel_id, vessel_name, is_active as "is_active!", created_at as "created_at!", updated_at as "updated_at!", correlation_id, trace_id
+            INSERT INTO vessel_register_table (vessel_id, vessel_name, mmsi, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING vessel_id, vessel_name, is_active as "is_active!", created_at as "created_at!", updated_at as "updated_at!", correlation_id, trace_id, mmsi, ais_callsign, ais_ship_type, ais_destination, ais_eta, ais_draught, ais_static_updated_at, vessel_class
             "#,
             vessel_id,
             vessel_name,
+            mmsi,
             correlation_id,
             trace_id
         )
@@ -52,7 +54,7 @@ impl VesselRepository {
         sqlx::query_as!(
             Vessel,
             r#"
-            SELECT vessel_id, vessel_name, is_active as "is_active!", created_at as "created_at!", updated_at as "updated_at!", correlation_id, trace_id
+            SELECT vessel_id, vessel_name, is_active as "is_active!", created_at as "created_at!", updated_at as "updated_at!", correlation_id, trace_id, mmsi, ais_callsign, ais_ship_type, ais_destination, ais_eta, ais_draught, ais_static_updated_at, vessel_class
             FROM vessel_register_table
             ORDER BY created_at DESC
             "#
@@ -75,4 +77,72 @@ impl VesselRepository {
 
         Ok(())
     }
+
+    /// `false` when no active vessel has this id
+    pub async fn set_class(
+        &self,
+        vessel_id: &str,
+        vessel_class: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE vessel_register_table
+            SET vessel_class = $2, updated_at = NOW()
+            WHERE vessel_id = $1 AND is_active = TRUE
+            "#,
+            vessel_id,
+            vessel_class
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Records the MMSI the vessel's own transponder reports
+    pub async fn set_mmsi(&self, vessel_id: &str, mmsi: i32) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE vessel_register_table
+            SET mmsi = $2, updated_at = NOW()
+            WHERE vessel_id = $1
+            "#,
+            vessel_id,
+            mmsi
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stores AIS static/voyage data, keeping existing values for fields not in `update`
+    pub async fn update_ais_static(
+        &self,
+        vessel_id: &str,
+        update: &AisStaticUpdate,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE vessel_register_table
+            SET ais_callsign = COALESCE($2, ais_callsign),
+                ais_ship_type = COALESCE($3, ais_ship_type),
+                ais_destination = COALESCE($4, ais_destination),
+                ais_eta = COALESCE($5, ais_eta),
+                ais_draught = COALESCE($6, ais_draught),
+                ais_static_updated_at = NOW()
+            WHERE vessel_id = $1
+            "#,
+            vessel_id,
+            update.callsign,
+            update.ship_type,
+            update.destination,
+            update.eta,
+            update.draught
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,291 @@
+use crate::models::vessel_class::{SignalRangeOverride, VesselClass};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Vessel classes, the signals each allows, and per-vessel range overrides
+pub struct VesselClassRepository {
+    pool: PgPool,
+}
+
+impl VesselClassRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<VesselClass>, sqlx::Error> {
+        sqlx::query_as!(
+            VesselClass,
+            r#"
+            SELECT class_name, description, created_at, updated_at, correlation_id, trace_id
+            FROM vessel_class_table
+            ORDER BY class_name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_name(&self, class_name: &str) -> Result<Option<VesselClass>, sqlx::Error> {
+        sqlx::query_as!(
+            VesselClass,
+            r#"
+            SELECT class_name, description, created_at, updated_at, correlation_id, trace_id
+            FROM vessel_class_table
+            WHERE class_name = $1
+            "#,
+            class_name
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// The signals of every class, keyed by class name; classes without signals are left out
+    pub async fn class_signals(&self) -> Result<HashMap<String, HashSet<String>>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT class_name, signal_name
+            FROM vessel_class_signals
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut classes: HashMap<String, HashSet<String>> = HashMap::new();
+        for row in rows {
+            classes
+                .entry(row.class_name)
+                .or_default()
+                .insert(row.signal_name);
+        }
+
+        Ok(classes)
+    }
+
+    pub async fn signals_of(&self, class_name: &str) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT signal_name
+            FROM vessel_class_signals
+            WHERE class_name = $1
+            ORDER BY signal_name
+            "#,
+            class_name
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Vessels assigned each class, inactive ones included
+    pub async fn vessel_counts(&self) -> Result<HashMap<String, i64>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT vessel_class AS "class_name!", COUNT(*) AS "vessels!"
+            FROM vessel_register_table
+            WHERE vessel_class IS NOT NULL
+            GROUP BY vessel_class
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.class_name, r.vessels))
+            .collect())
+    }
+
+    /// Class of every active vessel that has one, keyed by vessel_id
+    pub async fn vessel_classes(&self) -> Result<HashMap<String, String>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT vessel_id, vessel_class AS "class_name!"
+            FROM vessel_register_table
+            WHERE vessel_class IS NOT NULL AND is_active = TRUE
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.vessel_id, r.class_name))
+            .collect())
+    }
+
+    pub async fn create(
+        &self,
+        class_name: &str,
+        description: Option<&str>,
+        signals: &[String],
+        correlation_id: Uuid,
+        trace_id: Option<String>,
+    ) -> Result<VesselClass, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let class = sqlx::query_as!(
+            VesselClass,
+            r#"
+            INSERT INTO vessel_class_table (class_name, description, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4)
+            RETURNING class_name, description, created_at, updated_at, correlation_id, trace_id
+            "#,
+            class_name,
+            description,
+            correlation_id,
+            trace_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        Self::insert_signals(&mut tx, class_name, signals).await?;
+
+        tx.commit().await?;
+        Ok(class)
+    }
+
+    /// Replaces a class's description and signal set in one transaction
+    pub async fn update(
+        &self,
+        class_name: &str,
+        description: Option<&str>,
+        signals: &[String],
+        correlation_id: Uuid,
+        trace_id: Option<String>,
+    ) -> Result<Option<VesselClass>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(class) = sqlx::query_as!(
+            VesselClass,
+            r#"
+            UPDATE vessel_class_table
+            SET description = $2, correlation_id = $3, trace_id = $4, updated_at = NOW()
+            WHERE class_name = $1
+            RETURNING class_name, description, created_at, updated_at, correlation_id, trace_id
+            "#,
+            class_name,
+            description,
+            correlation_id,
+            trace_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+            DELETE FROM vessel_class_signals
+            WHERE class_name = $1
+            "#,
+            class_name
+        )
+        .execute(&mut *tx)
+        .await?;
+        Self::insert_signals(&mut tx, class_name, signals).await?;
+
+        tx.commit().await?;
+        Ok(Some(class))
+    }
+
+    async fn insert_signals(
+        tx: &mut Transaction<'_, Postgres>,
+        class_name: &str,
+        signals: &[String],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO vessel_class_signals (class_name, signal_name)
+            SELECT $1, UNNEST($2::text[])
+            "#,
+            class_name,
+            signals
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// `false` when no class has this name
+    pub async fn delete(&self, class_name: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM vessel_class_table
+            WHERE class_name = $1
+            "#,
+            class_name
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Overrides of every active vessel
+    pub async fn find_all_overrides(&self) -> Result<Vec<SignalRangeOverride>, sqlx::Error> {
+        sqlx::query_as!(
+            SignalRangeOverride,
+            r#"
+            SELECT o.vessel_id, o.signal_name, o.min_value, o.max_value, o.created_at, o.updated_at, o.correlation_id, o.trace_id
+            FROM vessel_signal_overrides o
+            JOIN vessel_register_table v ON v.vessel_id = o.vessel_id
+            WHERE v.is_active = TRUE
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn upsert_override(
+        &self,
+        vessel_id: &str,
+        signal_name: &str,
+        min_value: Decimal,
+        max_value: Decimal,
+        correlation_id: Uuid,
+        trace_id: Option<String>,
+    ) -> Result<SignalRangeOverride, sqlx::Error> {
+        sqlx::query_as!(
+            SignalRangeOverride,
+            r#"
+            INSERT INTO vessel_signal_overrides (vessel_id, signal_name, min_value, max_value, correlation_id, trace_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (vessel_id, signal_name) DO UPDATE
+            SET min_value = EXCLUDED.min_value, max_value = EXCLUDED.max_value,
+                correlation_id = EXCLUDED.correlation_id, trace_id = EXCLUDED.trace_id,
+                updated_at = NOW()
+            RETURNING vessel_id, signal_name, min_value, max_value, created_at, updated_at, correlation_id, trace_id
+            "#,
+            vessel_id,
+            signal_name,
+            min_value,
+            max_value,
+            correlation_id,
+            trace_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// `false` when the vessel had no override for the signal
+    pub async fn delete_override(
+        &self,
+        vessel_id: &str,
+        signal_name: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM vessel_signal_overrides
+            WHERE vessel_id = $1 AND signal_name = $2
+            "#,
+            vessel_id,
+            signal_name
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,27 +1,85 @@
 use crate::controller::api_key::{create_api_key, list_api_keys, revoke_api_key};
+use crate::controller::backfill::{get_backfill_job, list_backfill_jobs, upload_backfill};
+use crate::controller::dead_letter::{
+    delete_dead_letter, get_dead_letter, list_dead_letters, purge_dead_letters, retry_dead_letter,
+};
 use crate::controller::metrics::{
-    get_all_vessels_metrics, get_metrics, get_metrics_summary, health_with_metrics,
+    get_admission_metrics, get_all_vessels_metrics, get_compression_metrics, get_metrics,
+    get_metrics_summary, get_signal_cache_metrics, get_udp_metrics, health_with_metrics,
+};
+use crate::controller::nmea::{ingest_ais, ingest_nmea, ingest_nmea2000};
+use crate::controller::session::{list_sessions, telemetry_websocket};
+use crate::controller::signal::{
+    create_signal, export_signal_catalogue, get_signal, import_signal_catalogue, list_signals,
+    retire_signal, update_signal,
+};
+use crate::controller::signal_mapping::{
+    delete_signal_mapping, list_signal_mappings, upsert_signal_mapping,
+};
+use crate::controller::telemetry::{
+    get_clock_drift, get_ingestion_status, ingest_telemetry, ingest_telemetry_batch,
+    ingest_telemetry_proto, ingest_telemetry_stream, telemetry_proto_schema,
+    telemetry_service_proto_schema,
 };
-use crate::controller::telemetry::ingest_telemetry;
 use crate::controller::vessel::{create_vessel, deactivate_vessel, get_vessel, list_vessels};
+use crate::controller::vessel_class::{
+    assign_vessel_class, clear_signal_range, create_vessel_class, delete_vessel_class,
+    get_vessel_class, get_vessel_signals, list_vessel_classes, set_signal_range,
+    update_vessel_class,
+};
+use crate::controller::wal::get_wal_backlog;
 use crate::middleware::admin_middleware;
+use crate::middleware::admission::admission_middleware;
 use crate::middleware::auth::auth_middleware;
+use crate::middleware::decompression::decompression_middleware;
 use crate::state::AppState;
-use axum::routing::{delete, get, post};
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{delete, get, post, put};
 use axum::{Json, Router, middleware};
 use serde_json::{Value, json};
 use tracing::{info, instrument};
 
+/// Body limit for batch uploads; store-and-forward backlogs are far larger than a single frame
+const BATCH_BODY_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+
 pub fn api_routes(state: AppState) -> Router<AppState> {
     // Public routes (no auth)
-    let public_routes = Router::new().route("/health", get(health_with_metrics));
+    let public_routes = Router::new()
+        .route("/health", get(health_with_metrics))
+        .route("/schema/telemetry.proto", get(telemetry_proto_schema))
+        .route(
+            "/schema/telemetry_service.proto",
+            get(telemetry_service_proto_schema),
+        );
 
-    // Telemetry ingestion (requires API key)
+    // Telemetry ingestion (requires API key, accepts gzip/zstd bodies)
     let telemetry_routes = Router::new()
         .route("/telemetry", post(ingest_telemetry))
+        .route(
+            "/telemetry/batch",
+            post(ingest_telemetry_batch).layer(DefaultBodyLimit::max(BATCH_BODY_LIMIT_BYTES)),
+        )
+        .route("/telemetry/stream", post(ingest_telemetry_stream))
+        .route("/telemetry/proto", post(ingest_telemetry_proto))
+        .route("/telemetry/nmea", post(ingest_nmea))
+        .route("/telemetry/nmea2000", post(ingest_nmea2000))
+        .route("/telemetry/ais", post(ingest_ais))
+        .route("/telemetry/ws", get(telemetry_websocket))
+        .route(
+            "/telemetry/status/{correlation_id}",
+            get(get_ingestion_status),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            decompression_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            admission_middleware,
         ));
 
     // Admin routes (requires admin key)
@@ -31,14 +89,67 @@ pub fn api_routes(state: AppState) -> Router<AppState> {
         .route("/vessels", get(list_vessels))
         .route("/vessels/{vessel_id}", get(get_vessel))
         .route("/vessels/{vessel_id}", delete(deactivate_vessel))
+        // Vessel classes and per-vessel signal sets
+        .route("/vessel-classes", post(create_vessel_class))
+        .route("/vessel-classes", get(list_vessel_classes))
+        .route("/vessel-classes/{class_name}", get(get_vessel_class))
+        .route("/vessel-classes/{class_name}", put(update_vessel_class))
+        .route("/vessel-classes/{class_name}", delete(delete_vessel_class))
+        .route("/vessels/{vessel_id}/class", put(assign_vessel_class))
+        .route("/vessels/{vessel_id}/signals", get(get_vessel_signals))
+        .route(
+            "/vessels/{vessel_id}/signals/{signal_name}/range",
+            put(set_signal_range),
+        )
+        .route(
+            "/vessels/{vessel_id}/signals/{signal_name}/range",
+            delete(clear_signal_range),
+        )
+        // Historical backfill imports
+        .route(
+            "/vessels/{vessel_id}/backfill",
+            post(upload_backfill).layer(DefaultBodyLimit::max(BATCH_BODY_LIMIT_BYTES)),
+        )
+        .route("/backfill-jobs", get(list_backfill_jobs))
+        .route("/backfill-jobs/{job_id}", get(get_backfill_job))
         // API key management
         .route("/api-keys", post(create_api_key))
         .route("/api-keys/vessel/{vessel_id}", get(list_api_keys))
         .route("/api-keys/revoke/{api_key}", delete(revoke_api_key))
+        // Ingestion sessions
+        .route("/sessions", get(list_sessions))
+        // Write-ahead log backlog
+        .route("/wal", get(get_wal_backlog))
+        // Frames the database refused to store
+        .route("/dead-letters", get(list_dead_letters))
+        .route("/dead-letters", delete(purge_dead_letters))
+        .route("/dead-letters/{id}", get(get_dead_letter))
+        .route("/dead-letters/{id}", delete(delete_dead_letter))
+        .route("/dead-letters/{id}/retry", post(retry_dead_letter))
+        // Signal registry
+        .route("/signals", post(create_signal))
+        .route("/signals", get(list_signals))
+        .route("/signals/{signal_name}", get(get_signal))
+        .route("/signals/{signal_name}", put(update_signal))
+        .route("/signals/{signal_name}", delete(retire_signal))
+        .route("/signal-catalogue", post(import_signal_catalogue))
+        .route("/signal-catalogue", get(export_signal_catalogue))
+        // Decoded field to signal mappings
+        .route("/signal-mappings", get(list_signal_mappings))
+        .route("/signal-mappings", put(upsert_signal_mapping))
+        .route(
+            "/signal-mappings/{source}/{message_type}/{field_name}",
+            delete(delete_signal_mapping),
+        )
         // Metrics APIs
         .route("/metrics", get(get_metrics))
         .route("/metrics/summary", get(get_metrics_summary))
         .route("/metrics/vessels", get(get_all_vessels_metrics))
+        .route("/metrics/compression", get(get_compression_metrics))
+        .route("/metrics/udp", get(get_udp_metrics))
+        .route("/metrics/admission", get(get_admission_metrics))
+        .route("/metrics/signal-cache", get(get_signal_cache_metrics))
+        .route("/metrics/clock-drift", get(get_clock_drift))
         .layer(middleware::from_fn(admin_middleware));
 
     Router::new()
@@ -57,9 +168,26 @@ pub async fn root() -> Json<Value> {
         "endpoints": {
             "health": "/api/v1/health",
             "telemetry": "/api/v1/telemetry (requires x-api-key)",
+            "telemetry_batch": "/api/v1/telemetry/batch (requires x-api-key)",
+            "telemetry_stream": "/api/v1/telemetry/stream (NDJSON, requires x-api-key)",
+            "telemetry_proto": "/api/v1/telemetry/proto (protobuf, requires x-api-key)",
+            "telemetry_nmea": "/api/v1/telemetry/nmea (NMEA 0183 sentences, requires x-api-key)",
+            "telemetry_nmea2000": "/api/v1/telemetry/nmea2000 (candump/Actisense captures, requires x-api-key)",
+            "telemetry_ais": "/api/v1/telemetry/ais (AIVDM/AIVDO sentences, requires x-api-key)",
+            "telemetry_ws": "/api/v1/telemetry/ws (WebSocket, requires x-api-key)",
+            "telemetry_status": "/api/v1/telemetry/status/{correlation_id} (async ingestion, requires x-api-key)",
+            "telemetry_schema": "/api/v1/schema/telemetry.proto",
+            "grpc_schema": "/api/v1/schema/telemetry_service.proto",
             "admin": {
                 "vessels": "/api/v1/vessels (requires x-admin-key)",
                 "api_keys": "/api/v1/api-keys (requires x-admin-key)",
+                "signals": "/api/v1/signals, /api/v1/signal-catalogue (requires x-admin-key)",
+                "vessel_classes": "/api/v1/vessel-classes, /api/v1/vessels/{vessel_id}/signals (requires x-admin-key)",
+                "signal_mappings": "/api/v1/signal-mappings (requires x-admin-key)",
+                "sessions": "/api/v1/sessions (requires x-admin-key)",
+                "wal": "/api/v1/wal (requires x-admin-key)",
+                "dead_letters": "/api/v1/dead-letters (requires x-admin-key)",
+                "backfill": "/api/v1/vessels/{vessel_id}/backfill, /api/v1/backfill-jobs (requires x-admin-key)",
                 "metrics": "/api/v1/metrics (requires x-admin-key)"
             }
         }
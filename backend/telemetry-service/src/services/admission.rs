@@ -0,0 +1,230 @@
+use crate::config::AdmissionConfig;
+use crate::error::AppError;
+use crate::models::metrics::{AdmissionMetrics, AdmissionShedSummary};
+use crate::services::metrics::MetricsService;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// Request header marking a frame as an alarm: `x-telemetry-priority: alarm`. Only honoured for
+/// keys created with `alarmPriority`
+pub const PRIORITY_HEADER: &str = "x-telemetry-priority";
+
+pub const PRIORITY_ALARM: &str = "alarm";
+pub const PRIORITY_ROUTINE: &str = "routine";
+
+/// More ingestion requests in progress than the priority's limit
+pub const SHED_IN_FLIGHT_LIMIT: &str = "in_flight_limit";
+/// Every pool connection is in use and routine requests are past `saturated_in_flight`
+pub const SHED_POOL_SATURATED: &str = "pool_saturated";
+
+type ShedCounts = HashMap<(&'static str, &'static str), u64>;
+
+/// Holds a place among the requests in progress until dropped
+pub struct AdmissionPermit {
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Decides whether an ingestion request is handled or refused with `503` right away.
+///
+/// Routine frames are shed first: once `max_in_flight` requests are in progress, or
+/// `saturated_in_flight` while the pool has no idle connection. Alarm frames are only shed past
+/// `alarm_max_in_flight`. Decisions use in-memory state only, so shedding costs no queries.
+///
+/// The alarm claim is taken on trust before authentication and checked after it; a claim from a
+/// key without alarm priority is [demoted](Self::demote_alarm) and decided again as routine.
+pub struct AdmissionService {
+    config: AdmissionConfig,
+    pool: PgPool,
+    metrics_service: Arc<MetricsService>,
+    in_flight: Arc<AtomicUsize>,
+    admitted_alarm: AtomicU64,
+    admitted_routine: AtomicU64,
+    demoted_alarm: AtomicU64,
+    shed_total: Mutex<ShedCounts>,
+    /// Not yet written to server_metrics
+    shed_pending: Mutex<ShedCounts>,
+}
+
+impl AdmissionService {
+    pub fn new(
+        config: AdmissionConfig,
+        pool: PgPool,
+        metrics_service: Arc<MetricsService>,
+    ) -> Self {
+        Self {
+            config,
+            pool,
+            metrics_service,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            admitted_alarm: AtomicU64::new(0),
+            admitted_routine: AtomicU64::new(0),
+            demoted_alarm: AtomicU64::new(0),
+            shed_total: Mutex::new(HashMap::new()),
+            shed_pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Admits a request or returns the `503` to answer it with
+    pub fn admit(&self, alarm: bool) -> Result<AdmissionPermit, AppError> {
+        // Counted before deciding so concurrent requests cannot all slip under the limit
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let permit = AdmissionPermit {
+            in_flight: self.in_flight.clone(),
+        };
+
+        self.decide(alarm, in_flight)?;
+        Ok(permit)
+    }
+
+    /// Decides again as routine for a request admitted as an alarm whose key may not claim alarm
+    /// priority. The caller keeps its permit and drops it with the refused request.
+    pub fn demote_alarm(&self) -> Result<(), AppError> {
+        self.admitted_alarm.fetch_sub(1, Ordering::Relaxed);
+        self.demoted_alarm.fetch_add(1, Ordering::Relaxed);
+
+        // The count includes this request, which admit() had not counted yet
+        let in_flight = self.in_flight.load(Ordering::SeqCst).saturating_sub(1);
+        self.decide(false, in_flight)
+    }
+
+    /// Counts the request as admitted or shed given the requests in progress before it
+    fn decide(&self, alarm: bool, in_flight: usize) -> Result<(), AppError> {
+        let shed = if alarm {
+            (in_flight >= self.config.alarm_max_in_flight).then_some(SHED_IN_FLIGHT_LIMIT)
+        } else if in_flight >= self.config.max_in_flight {
+            Some(SHED_IN_FLIGHT_LIMIT)
+        } else if in_flight >= self.config.saturated_in_flight && self.pool_saturated() {
+            Some(SHED_POOL_SATURATED)
+        } else {
+            None
+        };
+        let priority = if alarm {
+            PRIORITY_ALARM
+        } else {
+            PRIORITY_ROUTINE
+        };
+
+        let Some(reason) = shed else {
+            let admitted = if alarm {
+                &self.admitted_alarm
+            } else {
+                &self.admitted_routine
+            };
+            admitted.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        };
+
+        for counts in [&self.shed_total, &self.shed_pending] {
+            *lock(counts).entry((priority, reason)).or_insert(0) += 1;
+        }
+
+        Err(AppError::Overloaded {
+            reason: reason.to_string(),
+            message: format!(
+                "Too many ingestion requests in progress ({}), retry later",
+                in_flight
+            ),
+            retry_after_secs: self.retry_after_secs(),
+        })
+    }
+
+    /// Starts writing shed counters to server_metrics; call once at startup
+    pub fn spawn_metrics_flush(self: &Arc<Self>) {
+        let service = self.clone();
+        let interval = Duration::from_secs(self.config.metrics_flush_secs.max(1));
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                service.flush_metrics().await;
+            }
+        });
+    }
+
+    pub async fn metrics(&self, hours: f64) -> Result<AdmissionMetrics, AppError> {
+        let shed = self
+            .metrics_service
+            .get_admission_shed_summary(hours)
+            .await?;
+
+        let mut shed_since_startup = summarize(&lock(&self.shed_total));
+        shed_since_startup.sort_by(|a, b| (&a.priority, &a.reason).cmp(&(&b.priority, &b.reason)));
+
+        Ok(AdmissionMetrics {
+            in_flight: self.in_flight.load(Ordering::SeqCst),
+            max_in_flight: self.config.max_in_flight,
+            saturated_in_flight: self.config.saturated_in_flight,
+            alarm_max_in_flight: self.config.alarm_max_in_flight,
+            pool_size: self.pool.size(),
+            pool_idle: self.pool.num_idle(),
+            pool_max_connections: self.pool.options().get_max_connections(),
+            pool_saturated: self.pool_saturated(),
+            admitted_alarm: self.admitted_alarm.load(Ordering::Relaxed),
+            admitted_routine: self.admitted_routine.load(Ordering::Relaxed),
+            demoted_alarm: self.demoted_alarm.load(Ordering::Relaxed),
+            shed_since_startup,
+            time_range: format!("{} hours", hours),
+            shed,
+        })
+    }
+
+    /// Writes the pending counters, keeping them for the next flush if the write fails
+    async fn flush_metrics(&self) {
+        let pending = std::mem::take(&mut *lock(&self.shed_pending));
+
+        for ((priority, reason), count) in pending {
+            if let Err(e) = self
+                .metrics_service
+                .record_admission_sheds(priority, reason, count)
+                .await
+            {
+                error!(error = %e, priority, reason, "Failed to record shed requests");
+                *lock(&self.shed_pending)
+                    .entry((priority, reason))
+                    .or_insert(0) += count;
+            } else {
+                warn!(priority, reason, count, "Ingestion requests shed");
+            }
+        }
+    }
+
+    fn pool_saturated(&self) -> bool {
+        self.pool.size() >= self.pool.options().get_max_connections() && self.pool.num_idle() == 0
+    }
+
+    /// Spreads clients over `retry_after_secs` to twice that, so a fleet that was shed together
+    /// does not come back together
+    fn retry_after_secs(&self) -> u64 {
+        let base = self.config.retry_after_secs.max(1);
+        base + (Uuid::new_v4().as_u128() % (base as u128 + 1)) as u64
+    }
+}
+
+fn lock(counts: &Mutex<ShedCounts>) -> std::sync::MutexGuard<'_, ShedCounts> {
+    counts.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn summarize(counts: &ShedCounts) -> Vec<AdmissionShedSummary> {
+    counts
+        .iter()
+        .map(|(&(priority, reason), &count)| AdmissionShedSummary {
+            priority: priority.to_string(),
+            reason: reason.to_string(),
+            requests: count as i64,
+        })
+        .collect()
+}
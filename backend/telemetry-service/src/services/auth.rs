@@ -1,49 +1,189 @@
 use crate::error::AppError;
-use crate::models::api_key::ApiKeyResponse;
+use crate::models::api_key::{ApiKeyResponse, AuthenticatedKey};
 use crate::repositories::auth::AuthRepository;
+use crate::services::vessel_lookup::{KeyLookup, VesselLookupCache};
 use chrono::{DateTime, Utc};
-use std::sync::Arc;
-use tracing::warn;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, error, warn};
 use uuid::Uuid;
 
+type HmacSha256 = Hmac<Sha256>;
+
+/// Context string for deriving the UDP signing key from an API key
+pub const UDP_KEY_CONTEXT: &[u8] = b"nautilus-udp-v1";
+
 pub struct AuthService {
     auth_repo: Arc<AuthRepository>,
+    /// Keys validated within the TTL skip the database. Keys validated since startup are also
+    /// honoured past the TTL while the database is unreachable, so frames can still reach the
+    /// write-ahead log
+    vessel_lookup: Arc<VesselLookupCache>,
+    last_used_flush: Duration,
+    /// Latest use of each key since `last_used_at` was last written
+    last_used: Mutex<HashMap<String, DateTime<Utc>>>,
 }
 
 impl AuthService {
-    pub fn new(auth_repo: Arc<AuthRepository>) -> Self {
-        Self { auth_repo }
+    pub fn new(
+        auth_repo: Arc<AuthRepository>,
+        vessel_lookup: Arc<VesselLookupCache>,
+        last_used_flush: Duration,
+    ) -> Self {
+        Self {
+            auth_repo,
+            vessel_lookup,
+            last_used_flush,
+            last_used: Mutex::new(HashMap::new()),
+        }
     }
 
+    /// The vessel a key belongs to
     pub async fn validate_api_key(&self, api_key: &str) -> Result<String, AppError> {
-        let vessel_id = self.auth_repo.validate_api_key(api_key).await?;
-
-        if let Some(vid) = vessel_id {
-            // Update last_used_at asynchronously (fire and forget)
-            let repo = self.auth_repo.clone();
-            let key = api_key.to_string();
-            tokio::spawn(async move {
-                let _ = repo.update_last_used(&key).await;
-            });
-
-            Ok(vid)
-        } else {
+        self.authenticate(api_key).await.map(|key| key.vessel_id)
+    }
+
+    /// The vessel a key belongs to and what else the key grants
+    pub async fn authenticate(&self, api_key: &str) -> Result<AuthenticatedKey, AppError> {
+        let stale = match self.vessel_lookup.key(api_key) {
+            KeyLookup::Fresh(key) => {
+                self.record_use(api_key);
+                return Ok(key);
+            }
+            KeyLookup::Stale(key) => Some(key),
+            KeyLookup::Missing => None,
+        };
+
+        let generation = self.vessel_lookup.generation();
+        let lookup = match self.auth_repo.validate_api_key(api_key).await {
+            Ok(lookup) => lookup,
+            Err(e) => {
+                let e = AppError::from(e);
+                return match stale {
+                    Some(key) if e.is_database_outage() => {
+                        warn!(vessel_id = %key.vessel_id, "Database unavailable, accepting previously validated API key");
+                        Ok(key)
+                    }
+                    _ => Err(e),
+                };
+            }
+        };
+
+        let Some(lookup) = lookup else {
+            self.vessel_lookup.remove_key(api_key);
             warn!("Invalid API key attempt");
-            Err(AppError::Unauthorized("Invalid API key".to_string()))
+            return Err(AppError::Unauthorized("Invalid API key".to_string()));
+        };
+
+        let key = AuthenticatedKey {
+            vessel_id: lookup.vessel_id,
+            alarm_priority: lookup.alarm_priority,
+        };
+        self.vessel_lookup
+            .insert_key(api_key, key.clone(), lookup.expires_at, generation);
+        if lookup.vessel_active {
+            self.vessel_lookup.insert_vessel(&key.vessel_id, generation);
+        }
+        self.record_use(api_key);
+
+        Ok(key)
+    }
+
+    /// Starts writing `last_used_at` for recently used keys; call once at startup
+    pub fn spawn_last_used_flush(self: &Arc<Self>) {
+        let service = self.clone();
+        let interval = self.last_used_flush.max(Duration::from_secs(1));
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                service.flush_last_used().await;
+            }
+        });
+    }
+
+    /// Writes pending `last_used_at` values in one statement, keeping them for the next flush
+    /// if the write fails
+    pub async fn flush_last_used(&self) {
+        let pending = std::mem::take(&mut *self.last_used());
+        if pending.is_empty() {
+            return;
+        }
+
+        let (api_keys, used_at): (Vec<String>, Vec<DateTime<Utc>>) =
+            pending.iter().map(|(k, t)| (k.clone(), *t)).unzip();
+        match self.auth_repo.update_last_used(&api_keys, &used_at).await {
+            Ok(()) => debug!(keys = api_keys.len(), "API key last use recorded"),
+            Err(e) => {
+                error!(error = %e, keys = api_keys.len(), "Failed to record API key last use");
+                let mut last_used = self.last_used();
+                for (api_key, used_at) in pending {
+                    let latest = last_used.entry(api_key).or_insert(used_at);
+                    *latest = (*latest).max(used_at);
+                }
+            }
+        }
+    }
+
+    /// Checks a datagram's truncated HMAC-SHA256 tag against every active key of the vessel.
+    ///
+    /// The signing key is `HMAC-SHA256(api_key, "nautilus-udp-v1")`, so devices never need to
+    /// hold the key used for HTTP.
+    pub async fn verify_datagram(
+        &self,
+        vessel_id: &str,
+        signed: &[u8],
+        tag: &[u8],
+    ) -> Result<(), AppError> {
+        let keys = self.auth_repo.find_active_keys(vessel_id).await?;
+
+        let matching_key = keys.into_iter().find(|api_key| {
+            let mut mac = HmacSha256::new_from_slice(&Self::derive_udp_key(api_key))
+                .expect("HMAC accepts keys of any length");
+            mac.update(signed);
+            mac.verify_truncated_left(tag).is_ok()
+        });
+
+        match matching_key {
+            Some(api_key) => {
+                self.record_use(&api_key);
+                Ok(())
+            }
+            None => {
+                warn!(vessel_id = %vessel_id, "Invalid datagram signature");
+                Err(AppError::Unauthorized(
+                    "Invalid datagram signature".to_string(),
+                ))
+            }
         }
     }
 
+    /// UDP signing key for an API key
+    pub fn derive_udp_key(api_key: &str) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(api_key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(UDP_KEY_CONTEXT);
+        mac.finalize().into_bytes().to_vec()
+    }
+
     pub async fn create_api_key(
         &self,
         vessel_id: &str,
         expires_at: Option<DateTime<Utc>>,
+        alarm_priority: bool,
     ) -> Result<ApiKeyResponse, AppError> {
         // Generate secure random API key
         let api_key = format!("sk_{}", Uuid::new_v4().simple());
 
         let id = self
             .auth_repo
-            .create_api_key(vessel_id, &api_key, expires_at)
+            .create_api_key(vessel_id, &api_key, expires_at, alarm_priority)
             .await?;
 
         Ok(ApiKeyResponse {
@@ -54,32 +194,32 @@ impl AuthService {
             created_at: Utc::now(),
             expires_at,
             last_used_at: None,
+            alarm_priority,
         })
     }
 
     pub async fn list_api_keys(&self, vessel_id: &str) -> Result<Vec<ApiKeyResponse>, AppError> {
-        let keys = self.auth_repo.list_api_keys(vessel_id).await?;
-
-        Ok(keys
-            .into_iter()
-            .map(
-                |(id, vessel_id, api_key, is_active, created_at, expires_at, last_used_at)| {
-                    ApiKeyResponse {
-                        id,
-                        vessel_id,
-                        api_key,
-                        is_active,
-                        created_at,
-                        expires_at,
-                        last_used_at,
-                    }
-                },
-            )
-            .collect())
+        Ok(self.auth_repo.list_api_keys(vessel_id).await?)
     }
 
     pub async fn revoke_api_key(&self, api_key: &str) -> Result<(), AppError> {
         self.auth_repo.revoke_api_key(api_key).await?;
+        self.vessel_lookup.remove_key(api_key);
         Ok(())
     }
+
+    fn record_use(&self, api_key: &str) {
+        let now = Utc::now();
+        let mut last_used = self.last_used();
+        match last_used.get_mut(api_key) {
+            Some(used_at) => *used_at = now,
+            None => {
+                last_used.insert(api_key.to_string(), now);
+            }
+        }
+    }
+
+    fn last_used(&self) -> std::sync::MutexGuard<'_, HashMap<String, DateTime<Utc>>> {
+        self.last_used.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
@@ -0,0 +1,447 @@
+use crate::error::AppError;
+use crate::models::backfill::{
+    BackfillChunkResult, BackfillJobQuery, BackfillJobResponse, BackfillMapping, BackfillProgress,
+    BackfillRowError, JOB_COMPLETED, JOB_FAILED, JOB_QUEUED, JOB_RUNNING, NewBackfillJob,
+};
+use crate::models::telemetry::TelemetryRequest;
+use crate::repositories::{
+    backfill::BackfillRepository, signal::SignalRepository, vessel::VesselRepository,
+};
+use crate::services::telemetry::TelemetryService;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Rows validated and committed together
+pub const BACKFILL_CHUNK_ROWS: usize = 500;
+
+/// Largest file accepted, in data rows
+pub const MAX_BACKFILL_ROWS: usize = 1_000_000;
+
+/// Row errors stored per job; the rejected count keeps counting past this
+pub const MAX_STORED_ROW_ERRORS: usize = 1_000;
+
+/// Most jobs returned by one listing
+const MAX_JOB_LIST: i64 = 1000;
+
+/// One data row of the file: its line number, and the frame or why it could not be built
+type ParsedRow = (i64, Result<TelemetryRequest, AppError>);
+
+pub struct BackfillService {
+    backfill_repo: Arc<BackfillRepository>,
+    vessel_repo: Arc<VesselRepository>,
+    signal_repo: Arc<SignalRepository>,
+    telemetry_service: Arc<TelemetryService>,
+    /// Imports run one at a time so they never hold more than one pooled connection
+    import_slot: Arc<Semaphore>,
+}
+
+impl BackfillService {
+    pub fn new(
+        backfill_repo: Arc<BackfillRepository>,
+        vessel_repo: Arc<VesselRepository>,
+        signal_repo: Arc<SignalRepository>,
+        telemetry_service: Arc<TelemetryService>,
+    ) -> Self {
+        Self {
+            backfill_repo,
+            vessel_repo,
+            signal_repo,
+            telemetry_service,
+            import_slot: Arc::new(Semaphore::new(1)),
+        }
+    }
+
+    /// Parses the file, records a job and imports it in the background.
+    ///
+    /// Problems with the file as a whole (header, mapping, size, repeated upload) are returned
+    /// right away; problems with single rows are recorded on the job.
+    pub async fn start_import(
+        &self,
+        vessel_id: &str,
+        mapping: BackfillMapping,
+        file_name: Option<String>,
+        contents: &[u8],
+        force: bool,
+    ) -> Result<BackfillJobResponse, AppError> {
+        if self.vessel_repo.find_by_id(vessel_id).await?.is_none() {
+            return Err(AppError::NotFound(format!(
+                "Vessel {} not found",
+                vessel_id
+            )));
+        }
+
+        let registered: HashSet<String> = self
+            .signal_repo
+            .find_all()
+            .await?
+            .into_values()
+            .filter(|signal| signal.retired_at.is_none())
+            .map(|signal| signal.signal_name)
+            .collect();
+        let rows = parse_file(vessel_id, &mapping, contents, &registered)?;
+
+        let file_sha256 = format!("{:x}", Sha256::digest(contents));
+        if !force
+            && let Some(job_id) = self
+                .backfill_repo
+                .find_by_file(vessel_id, &file_sha256)
+                .await?
+        {
+            return Err(AppError::Conflict(format!(
+                "This file was already imported for vessel {} as job {}; pass force=true to import it again",
+                vessel_id, job_id
+            )));
+        }
+
+        let job = self
+            .backfill_repo
+            .create(&NewBackfillJob {
+                job_id: Uuid::new_v4(),
+                vessel_id: vessel_id.to_string(),
+                file_name,
+                file_sha256,
+                column_mapping: serde_json::to_value(&mapping)
+                    .map_err(|e| AppError::Internal(e.to_string()))?,
+                status: JOB_QUEUED.to_string(),
+                rows_total: rows.len() as i64,
+                correlation_id: Uuid::new_v4(),
+                trace_id: Uuid::new_v4().to_string(),
+            })
+            .await?;
+
+        info!(
+            job_id = %job.job_id,
+            vessel_id = %vessel_id,
+            rows = rows.len(),
+            "Backfill job queued"
+        );
+
+        tokio::spawn(run_import(
+            self.backfill_repo.clone(),
+            self.telemetry_service.clone(),
+            self.import_slot.clone(),
+            job.job_id,
+            vessel_id.to_string(),
+            job.correlation_id,
+            rows,
+        ));
+
+        Ok(job.into())
+    }
+
+    pub async fn get_job(&self, job_id: Uuid) -> Result<BackfillJobResponse, AppError> {
+        let job = self
+            .backfill_repo
+            .find_by_id(job_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Backfill job {} not found", job_id)))?;
+        let row_errors = self
+            .backfill_repo
+            .find_row_errors(job_id, MAX_STORED_ROW_ERRORS as i64)
+            .await?;
+
+        let mut response = BackfillJobResponse::from(job);
+        response.row_errors = Some(row_errors);
+        Ok(response)
+    }
+
+    pub async fn list_jobs(
+        &self,
+        query: BackfillJobQuery,
+    ) -> Result<Vec<BackfillJobResponse>, AppError> {
+        let limit = query.limit.unwrap_or(100).clamp(1, MAX_JOB_LIST);
+        let jobs = self.backfill_repo.find_all(query.vessel_id, limit).await?;
+        Ok(jobs.into_iter().map(Into::into).collect())
+    }
+
+    /// Fails jobs a previous run left unfinished; call before accepting uploads
+    pub async fn fail_interrupted_jobs(&self) -> Result<u64, AppError> {
+        let failed = self
+            .backfill_repo
+            .fail_unfinished("Interrupted by a service restart; upload the file again")
+            .await?;
+
+        if failed > 0 {
+            warn!(
+                jobs = failed,
+                "Failed backfill jobs left unfinished by previous run"
+            );
+        }
+
+        Ok(failed)
+    }
+}
+
+async fn run_import(
+    backfill_repo: Arc<BackfillRepository>,
+    telemetry_service: Arc<TelemetryService>,
+    import_slot: Arc<Semaphore>,
+    job_id: Uuid,
+    vessel_id: String,
+    correlation_id: Uuid,
+    rows: Vec<ParsedRow>,
+) {
+    let _slot = import_slot.acquire_owned().await;
+    let trace_id = Uuid::new_v4().to_string();
+
+    let result = async {
+        backfill_repo.set_status(job_id, JOB_RUNNING).await?;
+
+        let mut progress = BackfillProgress::default();
+        let mut stored_errors = 0;
+
+        let mut rows = rows.into_iter().peekable();
+        while rows.peek().is_some() {
+            let mut row_errors = Vec::new();
+            let mut requests = Vec::new();
+            let mut row_numbers = Vec::new();
+            let mut chunk_rows = 0;
+            for (row_number, row) in rows.by_ref().take(BACKFILL_CHUNK_ROWS) {
+                chunk_rows += 1;
+                match row {
+                    Ok(request) => {
+                        requests.push(request);
+                        row_numbers.push(row_number);
+                    }
+                    Err(e) => row_errors.push(BackfillRowError::from_error(row_number, &e)),
+                }
+            }
+
+            let chunk = if requests.is_empty() {
+                BackfillChunkResult::default()
+            } else {
+                telemetry_service
+                    .ingest_backfill_chunk(&vessel_id, &requests, correlation_id, &trace_id)
+                    .await?
+            };
+            row_errors.extend(
+                chunk
+                    .rejected
+                    .iter()
+                    .map(|(index, e)| BackfillRowError::from_error(row_numbers[*index], e)),
+            );
+            row_errors.sort_by_key(|e| e.row_number);
+
+            progress.rows_processed += chunk_rows;
+            progress.rows_accepted += chunk.rows_accepted as i64;
+            progress.rows_rejected += row_errors.len() as i64;
+            progress.valid_signals += chunk.valid_signals as i64;
+            progress.invalid_signals += chunk.invalid_signals as i64;
+
+            row_errors.truncate(MAX_STORED_ROW_ERRORS.saturating_sub(stored_errors));
+            stored_errors += row_errors.len();
+
+            backfill_repo
+                .record_progress(job_id, &progress, &row_errors)
+                .await?;
+        }
+
+        Ok::<_, AppError>(progress)
+    }
+    .await;
+
+    match result {
+        Ok(progress) => {
+            info!(
+                job_id = %job_id,
+                rows_accepted = progress.rows_accepted,
+                rows_rejected = progress.rows_rejected,
+                "Backfill job completed"
+            );
+            if let Err(e) = backfill_repo.finish(job_id, JOB_COMPLETED, None).await {
+                error!(job_id = %job_id, error = %e, "Failed to mark backfill job completed");
+            }
+        }
+        Err(e) => {
+            error!(job_id = %job_id, error = %e, "Backfill job failed");
+            if let Err(e) = backfill_repo
+                .finish(job_id, JOB_FAILED, Some(&e.to_string()))
+                .await
+            {
+                error!(job_id = %job_id, error = %e, "Failed to mark backfill job failed");
+            }
+        }
+    }
+}
+
+/// Reads the header and turns every data row into a frame for `vessel_id`
+fn parse_file(
+    vessel_id: &str,
+    mapping: &BackfillMapping,
+    contents: &[u8],
+    registered: &HashSet<String>,
+) -> Result<Vec<ParsedRow>, AppError> {
+    let delimiter = match mapping.delimiter {
+        None => b',',
+        Some(c) if c.is_ascii() => c as u8,
+        Some(c) => {
+            return Err(AppError::Validation(format!(
+                "Delimiter '{}' is not an ASCII character",
+                c
+            )));
+        }
+    };
+    // Spreadsheet exports often start with a UTF-8 byte order mark
+    let contents = contents.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(contents);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(contents);
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| AppError::Validation(format!("Failed to read CSV header: {}", e)))?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    let column_index = |name: &str| {
+        headers.iter().position(|h| h == name).ok_or_else(|| {
+            AppError::Validation(format!("Column '{}' not found in CSV header", name))
+        })
+    };
+
+    let timestamp_index = column_index(&mapping.timestamp_column)?;
+    let columns: Vec<(usize, String)> = match &mapping.columns {
+        Some(columns) => {
+            let unregistered: Vec<&str> = columns
+                .values()
+                .filter(|signal| !registered.contains(*signal))
+                .map(String::as_str)
+                .collect();
+            if !unregistered.is_empty() {
+                return Err(AppError::Validation(format!(
+                    "Mapping targets unregistered or retired signals: {}",
+                    unregistered.join(", ")
+                )));
+            }
+            columns
+                .iter()
+                .map(|(column, signal)| Ok((column_index(column)?, signal.clone())))
+                .collect::<Result<_, AppError>>()?
+        }
+        None => headers
+            .iter()
+            .enumerate()
+            .filter(|(index, header)| *index != timestamp_index && !header.is_empty())
+            .map(|(index, header)| (index, header.clone()))
+            .collect(),
+    };
+    if columns.is_empty() {
+        return Err(AppError::Validation(
+            "No signal columns to import".to_string(),
+        ));
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        if rows.len() == MAX_BACKFILL_ROWS {
+            return Err(AppError::Validation(format!(
+                "File has more than {} rows; split it into smaller uploads",
+                MAX_BACKFILL_ROWS
+            )));
+        }
+
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let row_number = e.position().map_or(0, |p| p.line() as i64);
+                rows.push((
+                    row_number,
+                    Err(AppError::Validation(format!("Unreadable row: {}", e))),
+                ));
+                continue;
+            }
+        };
+        let row_number = record.position().map_or(0, |p| p.line() as i64);
+        if record.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+
+        rows.push((
+            row_number,
+            build_request(vessel_id, mapping, &record, timestamp_index, &columns),
+        ));
+    }
+
+    Ok(rows)
+}
+
+fn build_request(
+    vessel_id: &str,
+    mapping: &BackfillMapping,
+    record: &csv::StringRecord,
+    timestamp_index: usize,
+    columns: &[(usize, String)],
+) -> Result<TelemetryRequest, AppError> {
+    let raw_timestamp = record.get(timestamp_index).unwrap_or_default().trim();
+    let timestamp_utc = parse_timestamp(raw_timestamp, mapping.timestamp_format.as_deref())
+        .ok_or_else(|| {
+            AppError::Validation(format!("Cannot parse timestamp '{}'", raw_timestamp))
+        })?;
+
+    let signals: HashMap<String, serde_json::Value> = columns
+        .iter()
+        .filter_map(|(index, signal)| {
+            let cell = record.get(*index)?.trim();
+            (!cell.is_empty()).then(|| (signal.clone(), cell_value(cell, mapping.decimal_comma)))
+        })
+        .collect();
+    if signals.is_empty() {
+        return Err(AppError::Validation("Row has no signal values".to_string()));
+    }
+
+    Ok(TelemetryRequest {
+        vessel_id: vessel_id.to_string(),
+        timestamp_utc,
+        epoch_utc: timestamp_utc.timestamp().to_string(),
+        signals,
+        frame_id: None,
+    })
+}
+
+/// Numeric cells become numbers; anything else is passed on as text and filtered by validation
+fn cell_value(cell: &str, decimal_comma: bool) -> serde_json::Value {
+    let number = if decimal_comma {
+        cell.replace(',', ".").parse::<f64>()
+    } else {
+        cell.parse::<f64>()
+    };
+
+    number
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(serde_json::Value::Number)
+        .unwrap_or_else(|| serde_json::Value::String(cell.to_string()))
+}
+
+fn parse_timestamp(value: &str, format: Option<&str>) -> Option<DateTime<Utc>> {
+    match format {
+        Some("epoch_s") => value
+            .parse()
+            .ok()
+            .and_then(|s| DateTime::from_timestamp(s, 0)),
+        Some("epoch_ms") => value.parse().ok().and_then(DateTime::from_timestamp_millis),
+        Some(pattern) => DateTime::parse_from_str(value, pattern)
+            .map(|t| t.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(value, pattern)
+                    .ok()
+                    .map(|t| t.and_utc())
+            }),
+        None => DateTime::parse_from_rfc3339(value)
+            .map(|t| t.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+                    .ok()
+                    .map(|t| t.and_utc())
+            }),
+    }
+}
@@ -0,0 +1,171 @@
+use crate::error::AppError;
+use crate::models::dead_letter::{
+    DeadLetter, DeadLetterPurgeQuery, DeadLetterPurgeResponse, DeadLetterQuery, DeadLetterResponse,
+    DeadLetterRetryResponse,
+};
+use crate::models::telemetry::{DecodeRejection, TelemetryRequest};
+use crate::repositories::dead_letter::DeadLetterRepository;
+use crate::services::telemetry::{RecoveredFrame, TelemetryService, recovery_failure_class};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Most dead letters returned by one listing
+const MAX_DEAD_LETTER_LIST: i64 = 1000;
+
+pub struct DeadLetterService {
+    dead_letter_repo: Arc<DeadLetterRepository>,
+    telemetry_service: Arc<TelemetryService>,
+}
+
+impl DeadLetterService {
+    pub fn new(
+        dead_letter_repo: Arc<DeadLetterRepository>,
+        telemetry_service: Arc<TelemetryService>,
+    ) -> Self {
+        Self {
+            dead_letter_repo,
+            telemetry_service,
+        }
+    }
+
+    pub async fn list(&self, query: DeadLetterQuery) -> Result<Vec<DeadLetterResponse>, AppError> {
+        let limit = query.limit.unwrap_or(100).clamp(1, MAX_DEAD_LETTER_LIST);
+        let dead_letters = self
+            .dead_letter_repo
+            .find_all(query.vessel_id, query.error_class, limit)
+            .await?;
+        Ok(dead_letters.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<DeadLetterResponse, AppError> {
+        Ok(self.find(id).await?.into())
+    }
+
+    /// Stores a dead letter again, with `edited` replacing its payload if given.
+    ///
+    /// The frame keeps its original correlation_id and skips the age limit, like a write-ahead
+    /// log replay. On success the dead letter is removed; on failure it keeps the payload that
+    /// was tried and the new error.
+    pub async fn retry(
+        &self,
+        id: Uuid,
+        edited: Option<TelemetryRequest>,
+    ) -> Result<DeadLetterRetryResponse, AppError> {
+        let dead_letter = self.find(id).await?;
+
+        let request = match edited {
+            Some(request) if request.vessel_id != dead_letter.vessel_id => {
+                return Err(AppError::Validation(format!(
+                    "vesselId cannot be changed: dead letter {} belongs to '{}'",
+                    id, dead_letter.vessel_id
+                )));
+            }
+            Some(request) => request,
+            None => serde_json::from_value(dead_letter.payload).map_err(|e| {
+                AppError::Internal(format!(
+                    "Dead letter {} has an unreadable payload: {}",
+                    id, e
+                ))
+            })?,
+        };
+        let rejected: Vec<DecodeRejection> = serde_json::from_value(dead_letter.decode_rejections)
+            .map_err(|e| {
+                AppError::Internal(format!(
+                    "Dead letter {} has unreadable decode rejections: {}",
+                    id, e
+                ))
+            })?;
+        let trace_id = dead_letter
+            .trace_id
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let frame = RecoveredFrame {
+            request: &request,
+            rejected: &rejected,
+            correlation_id: dead_letter.correlation_id,
+            trace_id: &trace_id,
+            fallback_frame_id: format!("dead-letter:{}", id),
+        };
+        let error = match self
+            .telemetry_service
+            .store_recovered_frame(&frame, &mut HashMap::new())
+            .await
+        {
+            Ok(stored) => {
+                self.dead_letter_repo.delete(id).await?;
+                info!(dead_letter_id = %id, duplicate = stored.is_none(), "Dead letter retried");
+
+                return Ok(DeadLetterRetryResponse {
+                    id,
+                    status: if stored.is_some() {
+                        "stored"
+                    } else {
+                        "duplicate"
+                    }
+                    .to_string(),
+                    correlation_id: dead_letter.correlation_id,
+                    valid_signals: stored.as_ref().map_or(0, |r| r.valid_signals),
+                    invalid_signals: stored.as_ref().map_or(0, |r| r.invalid_signals),
+                });
+            }
+            Err(e) if e.is_database_outage() => return Err(e),
+            Err(e) => e,
+        };
+
+        let error_class = recovery_failure_class(&error);
+        let payload = serde_json::to_value(&request)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize dead letter: {}", e)))?;
+        self.dead_letter_repo
+            .record_retry_failure(
+                id,
+                &payload,
+                error_class,
+                &error.to_string(),
+                error.sqlstate().as_deref(),
+            )
+            .await?;
+        warn!(dead_letter_id = %id, error_class, error = %error, "Dead letter retry failed");
+
+        Err(AppError::Rejected {
+            reason: error_class.to_string(),
+            message: format!("Retry failed ({}); dead letter {} kept", error, id),
+        })
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        if !self.dead_letter_repo.delete(id).await? {
+            return Err(AppError::NotFound(format!("Dead letter {} not found", id)));
+        }
+
+        info!(dead_letter_id = %id, "Dead letter deleted");
+        Ok(())
+    }
+
+    pub async fn purge(
+        &self,
+        query: DeadLetterPurgeQuery,
+    ) -> Result<DeadLetterPurgeResponse, AppError> {
+        if query.older_than_hours.is_some_and(|hours| hours < 0.0) {
+            return Err(AppError::Validation(
+                "older_than_hours cannot be negative".to_string(),
+            ));
+        }
+
+        let purged = self
+            .dead_letter_repo
+            .purge(query.vessel_id, query.error_class, query.older_than_hours)
+            .await?;
+
+        info!(purged, "Dead letters purged");
+        Ok(DeadLetterPurgeResponse { purged })
+    }
+
+    async fn find(&self, id: Uuid) -> Result<DeadLetter, AppError> {
+        self.dead_letter_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Dead letter {} not found", id)))
+    }
+}
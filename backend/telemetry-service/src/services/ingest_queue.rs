@@ -0,0 +1,312 @@
+use crate::config::AsyncIngestionConfig;
+use crate::error::AppError;
+use crate::models::telemetry::{
+    INGESTION_BUFFERED, INGESTION_FAILED, INGESTION_QUEUED, IngestionStatusRecord,
+    IngestionStatusResponse, QueuedFrame, TelemetryRequest, TelemetryResponse,
+};
+use crate::services::telemetry::{PreparedFrame, TelemetryService};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{Mutex as AsyncMutex, mpsc};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// How often statuses past their retention are removed
+const STATUS_PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Outcome of handing a frame to the queue
+pub enum Enqueued {
+    /// Validated and queued; the caller gets `202 Accepted`
+    Accepted(TelemetryResponse),
+    /// The frame ID was already ingested; the caller gets the stored response
+    Replayed(TelemetryResponse),
+}
+
+/// Bounded in-process queue between the `POST /telemetry` handler and background writers.
+///
+/// Frames are validated before they are queued, so a `202` only means the frame is waiting
+/// to be written. Workers commit whatever is queued, up to `batch_frames` at a time, in one
+/// transaction. With a write-ahead log, frames that meet a database outage, before or after
+/// they are queued, are appended to the log instead of failing.
+pub struct IngestQueueService {
+    telemetry_service: Arc<TelemetryService>,
+    config: AsyncIngestionConfig,
+    sender: mpsc::Sender<QueuedFrame>,
+    receiver: AsyncMutex<mpsc::Receiver<QueuedFrame>>,
+    /// Frames not yet written, frames handed to the write-ahead log, and failures that could not
+    /// be recorded in the database
+    pending: Mutex<HashMap<Uuid, IngestionStatusRecord>>,
+    shutdown: CancellationToken,
+    workers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl IngestQueueService {
+    pub fn new(telemetry_service: Arc<TelemetryService>, config: AsyncIngestionConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.queue_capacity);
+
+        Self {
+            telemetry_service,
+            config,
+            sender,
+            receiver: AsyncMutex::new(receiver),
+            pending: Mutex::new(HashMap::new()),
+            shutdown: CancellationToken::new(),
+            workers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Starts the writers and the status purge; call once at startup
+    pub fn spawn_workers(self: &Arc<Self>) {
+        let mut workers = self.workers.lock().unwrap_or_else(|e| e.into_inner());
+        for _ in 0..self.config.workers.max(1) {
+            workers.push(tokio::spawn(self.clone().run_worker()));
+        }
+        tokio::spawn(self.clone().purge_statuses());
+
+        info!(
+            workers = workers.len(),
+            queue_capacity = self.config.queue_capacity,
+            "Async ingestion workers started"
+        );
+    }
+
+    /// Validates a frame and queues it, refusing it when the queue is full
+    pub async fn enqueue(&self, request: TelemetryRequest) -> Result<Enqueued, AppError> {
+        let (frame, response) = match self.telemetry_service.prepare_queued_frame(request).await? {
+            PreparedFrame::Replayed(response) => return Ok(Enqueued::Replayed(response)),
+            PreparedFrame::Queued(frame, response) => (frame, response),
+            PreparedFrame::Buffered(status, response) => {
+                self.pending_statuses()
+                    .insert(status.correlation_id, *status);
+                return Ok(Enqueued::Accepted(response));
+            }
+        };
+
+        let correlation_id = frame.correlation_id;
+        self.pending_statuses().insert(
+            correlation_id,
+            IngestionStatusRecord::for_frame(&frame, INGESTION_QUEUED, None),
+        );
+
+        match self.sender.try_send(*frame) {
+            Ok(()) => Ok(Enqueued::Accepted(response)),
+            Err(e) => {
+                self.pending_statuses().remove(&correlation_id);
+                let message = match e {
+                    TrySendError::Full(_) => "Ingestion queue is full, retry later",
+                    TrySendError::Closed(_) => "Service is shutting down, retry later",
+                };
+                warn!(correlation_id = %correlation_id, "{}", message);
+                Err(AppError::ServiceUnavailable(message.to_string()))
+            }
+        }
+    }
+
+    /// Status of a frame accepted for `vessel_id`; other vessels' frames are reported as not found
+    pub async fn get_status(
+        &self,
+        vessel_id: &str,
+        correlation_id: Uuid,
+    ) -> Result<IngestionStatusResponse, AppError> {
+        let pending = self.pending_statuses().get(&correlation_id).cloned();
+        let status = match pending {
+            Some(status) => Some(status),
+            None => {
+                self.telemetry_service
+                    .find_ingestion_status(correlation_id)
+                    .await?
+            }
+        };
+
+        status
+            .filter(|status| status.vessel_id == vessel_id)
+            .map(Into::into)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("No async ingestion found for {}", correlation_id))
+            })
+    }
+
+    /// Frames waiting for a worker
+    pub fn depth(&self) -> usize {
+        self.config.queue_capacity - self.sender.capacity()
+    }
+
+    /// Stops accepting frames and waits until every queued frame is written
+    pub async fn shutdown(&self) {
+        info!(
+            queued_frames = self.depth(),
+            "Flushing async ingestion queue"
+        );
+        self.shutdown.cancel();
+
+        let workers = std::mem::take(&mut *self.workers.lock().unwrap_or_else(|e| e.into_inner()));
+        for worker in workers {
+            if let Err(e) = worker.await {
+                error!(error = %e, "Async ingestion worker panicked");
+            }
+        }
+
+        info!("Async ingestion queue flushed");
+    }
+
+    async fn run_worker(self: Arc<Self>) {
+        loop {
+            let mut batch = Vec::with_capacity(self.config.batch_frames);
+            let received = {
+                let mut receiver = self.receiver.lock().await;
+                tokio::select! {
+                    received = receiver.recv_many(&mut batch, self.config.batch_frames) => received,
+                    _ = self.shutdown.cancelled() => {
+                        // Closing keeps queued frames readable, so the loop drains them before exiting
+                        receiver.close();
+                        receiver.recv_many(&mut batch, self.config.batch_frames).await
+                    }
+                }
+            };
+            if received == 0 {
+                break;
+            }
+
+            self.write_batch(batch).await;
+        }
+    }
+
+    /// Commits a batch, falling back to one frame at a time so a bad frame cannot sink the rest.
+    ///
+    /// A batch that fails because the database is unreachable goes to the write-ahead log whole,
+    /// rather than waiting out the outage once per frame.
+    async fn write_batch(&self, frames: Vec<QueuedFrame>) {
+        let statuses = match self.telemetry_service.commit_queued_frames(&frames).await {
+            Ok(statuses) => statuses,
+            Err(e) if e.is_database_outage() && self.telemetry_service.has_write_ahead_log() => {
+                warn!(
+                    frames = frames.len(),
+                    error = %e,
+                    "Database unavailable, buffering queued batch in the write-ahead log"
+                );
+
+                let mut statuses = Vec::new();
+                for frame in &frames {
+                    statuses.extend(self.buffer_frame(frame).await);
+                }
+                statuses
+            }
+            Err(e) => {
+                warn!(
+                    frames = frames.len(),
+                    error = %e,
+                    "Queued batch failed, writing frames one at a time"
+                );
+
+                let mut statuses = Vec::with_capacity(frames.len());
+                for frame in frames {
+                    let frame = std::slice::from_ref(&frame);
+                    match self.telemetry_service.commit_queued_frames(frame).await {
+                        Ok(written) => statuses.extend(written),
+                        Err(e) => statuses.extend(self.record_failure(&frame[0], e).await),
+                    }
+                }
+                statuses
+            }
+        };
+
+        let mut pending = self.pending_statuses();
+        for status in statuses {
+            pending.remove(&status.correlation_id);
+        }
+    }
+
+    /// Handles a frame that could not be written: buffered in the write-ahead log when the
+    /// database is unreachable and there is one, otherwise recorded as failed
+    async fn record_failure(
+        &self,
+        frame: &QueuedFrame,
+        error: AppError,
+    ) -> Vec<IngestionStatusRecord> {
+        if error.is_database_outage() && self.telemetry_service.has_write_ahead_log() {
+            return self.buffer_frame(frame).await;
+        }
+
+        self.mark_failed(frame, error).await
+    }
+
+    /// Appends a frame to the write-ahead log and keeps its `buffered` status in memory, since the
+    /// database cannot take it either; falls back to marking it failed if the append fails
+    async fn buffer_frame(&self, frame: &QueuedFrame) -> Vec<IngestionStatusRecord> {
+        if let Err(e) = self.telemetry_service.buffer_queued_frame(frame).await {
+            return self.mark_failed(frame, e).await;
+        }
+
+        let mut status = IngestionStatusRecord::for_frame(frame, INGESTION_BUFFERED, None);
+        status.completed_at = Some(Utc::now());
+        self.pending_statuses().insert(frame.correlation_id, status);
+        Vec::new()
+    }
+
+    /// Records a failed frame, keeping the failure in memory when it cannot be stored either
+    async fn mark_failed(
+        &self,
+        frame: &QueuedFrame,
+        error: AppError,
+    ) -> Vec<IngestionStatusRecord> {
+        error!(
+            correlation_id = %frame.correlation_id,
+            vessel_id = %frame.vessel_id,
+            error = %error,
+            "Queued frame could not be written"
+        );
+        let error = self
+            .telemetry_service
+            .dead_letter_queued_frame(frame, error)
+            .await;
+
+        match self
+            .telemetry_service
+            .record_failed_frames(std::slice::from_ref(frame), &error)
+            .await
+        {
+            Ok(statuses) => statuses,
+            Err(e) => {
+                error!(error = %e, "Failed to record async ingestion failure");
+                let mut status = IngestionStatusRecord::for_frame(
+                    frame,
+                    INGESTION_FAILED,
+                    Some(error.to_string()),
+                );
+                status.completed_at = Some(Utc::now());
+                self.pending_statuses().insert(frame.correlation_id, status);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn purge_statuses(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(STATUS_PURGE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = self.shutdown.cancelled() => break,
+            }
+
+            match self
+                .telemetry_service
+                .purge_ingestion_statuses(self.config.status_retention_secs)
+                .await
+            {
+                Ok(0) => {}
+                Ok(purged) => info!(purged, "Purged expired async ingestion statuses"),
+                Err(e) => warn!(error = %e, "Failed to purge async ingestion statuses"),
+            }
+        }
+    }
+
+    fn pending_statuses(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, IngestionStatusRecord>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -1,8 +1,12 @@
 use crate::error::AppError;
-use crate::models::metrics::{MetricData, MetricsSummary};
+use crate::models::metrics::{
+    AdmissionShedSummary, CompressionSummary, MetricData, MetricsSummary, UdpRejectionSummary,
+};
 use crate::repositories::metrics::MetricsRepository;
+use rust_decimal::Decimal;
 use std::sync::Arc;
 use std::time::Instant;
+use uuid::Uuid;
 
 pub struct MetricsService {
     metrics_repo: Arc<MetricsRepository>,
@@ -25,7 +29,7 @@ impl MetricsService {
         self.metrics_repo
             .get_request_count_last_n_minutes(1.0)
             .await
-            .map_err(|e| AppError::Database(e))
+            .map_err(AppError::Database)
     }
 
     pub async fn get_metrics(
@@ -89,4 +93,124 @@ impl MetricsService {
             )
             .collect())
     }
+
+    /// Records how much a compressed request body shrank on the wire.
+    ///
+    /// The metric value is compressed bytes divided by decompressed bytes; the raw byte counts
+    /// and encoding are kept in `additional_metadata`.
+    pub async fn record_compression(
+        &self,
+        vessel_id: &str,
+        encoding: &str,
+        compressed_bytes: u64,
+        decompressed_bytes: u64,
+    ) -> Result<(), AppError> {
+        if decompressed_bytes == 0 {
+            return Ok(());
+        }
+
+        let ratio =
+            (Decimal::from(compressed_bytes) / Decimal::from(decompressed_bytes)).round_dp(3);
+
+        self.metrics_repo
+            .insert_metric_with_metadata(
+                Some(vessel_id.to_string()),
+                "compression_ratio".to_string(),
+                ratio,
+                serde_json::json!({
+                    "encoding": encoding,
+                    "compressed_bytes": compressed_bytes,
+                    "decompressed_bytes": decompressed_bytes,
+                }),
+                Uuid::new_v4(),
+                Uuid::new_v4().to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_compression_summary(
+        &self,
+        hours: f64,
+    ) -> Result<Vec<CompressionSummary>, AppError> {
+        let stats = self.metrics_repo.get_compression_summary(hours).await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|s| {
+                let compressed_bytes: u64 = s.compressed_bytes.to_string().parse().unwrap_or(0);
+                let decompressed_bytes: u64 = s.decompressed_bytes.to_string().parse().unwrap_or(0);
+                CompressionSummary {
+                    vessel_id: s.vessel_id,
+                    time_range: format!("Last {} hours", hours),
+                    compressed_requests: s.compressed_requests,
+                    compressed_bytes,
+                    decompressed_bytes,
+                    bytes_saved: decompressed_bytes.saturating_sub(compressed_bytes),
+                    avg_compression_ratio: s
+                        .avg_compression_ratio
+                        .to_string()
+                        .parse()
+                        .unwrap_or(0.0),
+                }
+            })
+            .collect())
+    }
+
+    /// Stores the number of UDP datagrams rejected for one reason since the last flush
+    pub async fn record_udp_rejections(
+        &self,
+        vessel_id: Option<String>,
+        reason: &str,
+        count: u64,
+    ) -> Result<(), AppError> {
+        self.metrics_repo
+            .insert_metric_with_metadata(
+                vessel_id,
+                "udp_datagram_rejected".to_string(),
+                Decimal::from(count),
+                serde_json::json!({ "reason": reason }),
+                Uuid::new_v4(),
+                Uuid::new_v4().to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_udp_rejection_summary(
+        &self,
+        hours: f64,
+    ) -> Result<Vec<UdpRejectionSummary>, AppError> {
+        Ok(self.metrics_repo.get_udp_rejection_summary(hours).await?)
+    }
+
+    /// Stores the number of requests shed for one priority and reason since the last flush
+    pub async fn record_admission_sheds(
+        &self,
+        priority: &str,
+        reason: &str,
+        count: u64,
+    ) -> Result<(), AppError> {
+        self.metrics_repo
+            .insert_metric_with_metadata(
+                None,
+                "admission_shed".to_string(),
+                Decimal::from(count),
+                serde_json::json!({ "priority": priority, "reason": reason }),
+                Uuid::new_v4(),
+                Uuid::new_v4().to_string(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_admission_shed_summary(
+        &self,
+        hours: f64,
+    ) -> Result<Vec<AdmissionShedSummary>, AppError> {
+        Ok(self.metrics_repo.get_admission_shed_summary(hours).await?)
+    }
 }
@@ -1,18 +1,44 @@
+pub mod admission;
 pub mod auth;
+pub mod backfill;
+pub mod dead_letter;
+pub mod ingest_queue;
 pub mod metrics;
+pub mod nmea;
+pub mod session;
+pub mod signal;
+pub mod signal_catalogue;
+pub mod signal_mapping;
+pub mod signal_registry;
 pub mod telemetry;
 pub mod vessel;
+pub mod vessel_class;
+pub mod vessel_lookup;
+pub mod wal;
 // Add this
 
-use crate::repositories::{
-    auth::AuthRepository, metrics::MetricsRepository, signal::SignalRepository,
-    telemetry::TelemetryRepository, vessel::VesselRepository,
-};
+use crate::config::{AdmissionConfig, AsyncIngestionConfig, CacheConfig, IngestionConfig};
+use crate::repositories::Repositories;
+use crate::services::admission::AdmissionService;
 use crate::services::auth::AuthService;
+use crate::services::backfill::BackfillService;
+use crate::services::dead_letter::DeadLetterService;
+use crate::services::ingest_queue::IngestQueueService;
 use crate::services::metrics::MetricsService;
+use crate::services::nmea::NmeaService;
+use crate::services::session::SessionService;
+use crate::services::signal::SignalService;
+use crate::services::signal_mapping::SignalMappingService;
+use crate::services::signal_registry::SignalRegistryCache;
 use crate::services::telemetry::TelemetryService;
 use crate::services::vessel::VesselService; // Add this
+use crate::services::vessel_class::VesselClassService;
+use crate::services::vessel_lookup::VesselLookupCache;
+use crate::services::wal::WalService;
+use crate::wal::WriteAheadLog;
+use sqlx::PgPool;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct Services {
@@ -20,34 +46,146 @@ pub struct Services {
     auth_service: Arc<AuthService>,
     vessel_service: Arc<VesselService>, // Add this
     metrics_service: Arc<MetricsService>,
+    nmea_service: Arc<NmeaService>,
+    signal_mapping_service: Arc<SignalMappingService>,
+    signal_service: Arc<SignalService>,
+    signal_registry: Arc<SignalRegistryCache>,
+    vessel_class_service: Arc<VesselClassService>,
+    session_service: Arc<SessionService>,
+    backfill_service: Arc<BackfillService>,
+    dead_letter_service: Arc<DeadLetterService>,
+    ingest_queue: Option<Arc<IngestQueueService>>,
+    wal_service: Option<Arc<WalService>>,
+    admission_service: Arc<AdmissionService>,
 }
 
 impl Services {
     pub fn new(
-        vessel_repo: Arc<VesselRepository>,
-        signal_repo: Arc<SignalRepository>,
-        telemetry_repo: Arc<TelemetryRepository>,
-        metrics_repo: Arc<MetricsRepository>,
-        auth_repo: Arc<AuthRepository>,
+        repos: Repositories,
+        ingestion_config: IngestionConfig,
+        async_ingestion: Option<AsyncIngestionConfig>,
+        wal: Option<Arc<WriteAheadLog>>,
+        admission_config: AdmissionConfig,
+        cache_config: CacheConfig,
+        db: PgPool,
     ) -> Self {
-        let vessel_service = Arc::new(VesselService::new(vessel_repo.clone())); // Add this
+        let Repositories {
+            vessel: vessel_repo,
+            signal: signal_repo,
+            telemetry: telemetry_repo,
+            metrics: metrics_repo,
+            auth: auth_repo,
+            signal_mapping: signal_mapping_repo,
+            session: session_repo,
+            backfill: backfill_repo,
+            dead_letter: dead_letter_repo,
+            vessel_class: vessel_class_repo,
+        } = repos;
+        let session_idle_timeout = Duration::from_secs(ingestion_config.session_idle_timeout_secs);
+
+        let vessel_lookup = Arc::new(VesselLookupCache::new(
+            vessel_repo.clone(),
+            Duration::from_secs(cache_config.api_key_ttl_secs),
+        ));
+
+        let vessel_service = Arc::new(VesselService::new(
+            vessel_repo.clone(),
+            vessel_lookup.clone(),
+        )); // Add this
+
+        let signal_registry = Arc::new(SignalRegistryCache::new(
+            signal_repo.clone(),
+            vessel_class_repo.clone(),
+            db.clone(),
+            cache_config.signal_refresh_interval_secs,
+        ));
+
+        let signal_service = Arc::new(SignalService::new(
+            signal_repo.clone(),
+            signal_registry.clone(),
+        ));
+
+        let vessel_class_service = Arc::new(VesselClassService::new(
+            vessel_class_repo,
+            vessel_repo.clone(),
+            signal_repo.clone(),
+            signal_registry.clone(),
+        ));
+
+        let signal_mapping_service = Arc::new(SignalMappingService::new(
+            signal_mapping_repo.clone(),
+            signal_repo.clone(),
+        ));
 
         let telemetry_service = Arc::new(TelemetryService::new(
-            vessel_repo,
-            signal_repo,
+            vessel_lookup.clone(),
+            signal_registry.clone(),
             telemetry_repo,
             metrics_repo.clone(),
+            dead_letter_repo.clone(),
+            ingestion_config,
+            wal.clone(),
         ));
 
-        let auth_service = Arc::new(AuthService::new(auth_repo));
+        let auth_service = Arc::new(AuthService::new(
+            auth_repo,
+            vessel_lookup,
+            Duration::from_secs(cache_config.last_used_flush_secs),
+        ));
 
         let metrics_service = Arc::new(MetricsService::new(metrics_repo));
 
+        let admission_service = Arc::new(AdmissionService::new(
+            admission_config,
+            db,
+            metrics_service.clone(),
+        ));
+
+        let nmea_service = Arc::new(NmeaService::new(
+            telemetry_service.clone(),
+            signal_mapping_repo,
+            vessel_repo.clone(),
+        ));
+
+        let session_service = Arc::new(SessionService::new(
+            session_repo,
+            telemetry_service.clone(),
+            session_idle_timeout,
+        ));
+
+        let backfill_service = Arc::new(BackfillService::new(
+            backfill_repo,
+            vessel_repo,
+            signal_repo,
+            telemetry_service.clone(),
+        ));
+
+        let dead_letter_service = Arc::new(DeadLetterService::new(
+            dead_letter_repo,
+            telemetry_service.clone(),
+        ));
+
+        let ingest_queue = async_ingestion
+            .map(|config| Arc::new(IngestQueueService::new(telemetry_service.clone(), config)));
+
+        let wal_service = wal.map(|wal| Arc::new(WalService::new(telemetry_service.clone(), wal)));
+
         Self {
             telemetry_service,
             auth_service,
             vessel_service, // Add this
             metrics_service,
+            nmea_service,
+            signal_mapping_service,
+            signal_service,
+            signal_registry,
+            vessel_class_service,
+            session_service,
+            backfill_service,
+            dead_letter_service,
+            ingest_queue,
+            wal_service,
+            admission_service,
         }
     }
 
@@ -67,4 +205,50 @@ impl Services {
     pub fn metrics_service(&self) -> Arc<MetricsService> {
         self.metrics_service.clone()
     }
+
+    pub fn nmea_service(&self) -> Arc<NmeaService> {
+        self.nmea_service.clone()
+    }
+
+    pub fn signal_mapping_service(&self) -> Arc<SignalMappingService> {
+        self.signal_mapping_service.clone()
+    }
+
+    pub fn signal_service(&self) -> Arc<SignalService> {
+        self.signal_service.clone()
+    }
+
+    pub fn signal_registry(&self) -> Arc<SignalRegistryCache> {
+        self.signal_registry.clone()
+    }
+
+    pub fn vessel_class_service(&self) -> Arc<VesselClassService> {
+        self.vessel_class_service.clone()
+    }
+
+    pub fn session_service(&self) -> Arc<SessionService> {
+        self.session_service.clone()
+    }
+
+    pub fn backfill_service(&self) -> Arc<BackfillService> {
+        self.backfill_service.clone()
+    }
+
+    pub fn dead_letter_service(&self) -> Arc<DeadLetterService> {
+        self.dead_letter_service.clone()
+    }
+
+    /// Only present when async ingestion is enabled
+    pub fn ingest_queue(&self) -> Option<Arc<IngestQueueService>> {
+        self.ingest_queue.clone()
+    }
+
+    /// Only present when the write-ahead log is enabled
+    pub fn wal_service(&self) -> Option<Arc<WalService>> {
+        self.wal_service.clone()
+    }
+
+    pub fn admission_service(&self) -> Arc<AdmissionService> {
+        self.admission_service.clone()
+    }
 }
@@ -0,0 +1,603 @@
+use crate::decoders::ais::{self, AisBody};
+use crate::decoders::{nmea0183, nmea2000};
+use crate::error::AppError;
+use crate::models::signal_mapping::{SOURCE_AIS, SOURCE_NMEA0183, SOURCE_NMEA2000};
+use crate::models::telemetry::{DecodeRejection, DecodedIngestResponse, TelemetryRequest};
+use crate::models::vessel::AisStaticUpdate;
+use crate::repositories::{signal_mapping::SignalMappingRepository, vessel::VesselRepository};
+use crate::services::telemetry::TelemetryService;
+use chrono::{DateTime, Datelike, Duration, DurationRound, NaiveDate, TimeZone, Timelike, Utc};
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Upper bound on lines (sentences or CAN frames) accepted in one upload
+pub const MAX_NMEA_LINES: usize = 10_000;
+
+/// AIS fields are grouped under these message types in signal_source_mappings
+pub const AIS_POSITION_MESSAGE: &str = "position";
+pub const AIS_STATIC_MESSAGE: &str = "static";
+
+/// An ETA more than this far in the past refers to next year (AIS ETAs carry no year)
+const AIS_ETA_ROLLOVER_DAYS: i64 = 180;
+
+/// CAN messages captured within the same whole second share a frame
+const N2K_FRAME_BUCKET: Duration = Duration::seconds(1);
+
+pub struct NmeaService {
+    telemetry_service: Arc<TelemetryService>,
+    mapping_repo: Arc<SignalMappingRepository>,
+    vessel_repo: Arc<VesselRepository>,
+}
+
+/// Output of a decoder run, ready to be mapped and ingested
+struct DecodedUpload {
+    /// Identifies the upload in its frames' IDs, so a retried upload is deduplicated
+    body_digest: String,
+    lines_received: usize,
+    messages: Vec<DecodedMessage>,
+    rejected: Vec<DecodeRejection>,
+    ignored: usize,
+}
+
+struct DecodedMessage {
+    message_type: String,
+    fields: Vec<(String, f64)>,
+    /// When the input says the message was sampled; `None` joins the frame of the message before
+    sampled_at: Option<DateTime<Utc>>,
+}
+
+impl DecodedMessage {
+    fn new(message_type: impl Into<String>, fields: Vec<(String, f64)>) -> Self {
+        Self {
+            message_type: message_type.into(),
+            fields,
+            sampled_at: None,
+        }
+    }
+}
+
+/// Turns the time of day in GGA, RMC and ZDA sentences into sample times.
+///
+/// RMC and ZDA carry the date. Until one arrives, times take the date of the upload timestamp,
+/// or the day before if that would put them after it. A time more than 12 hours before the
+/// previous one is taken to have passed midnight.
+struct FixClock {
+    upload_time: DateTime<Utc>,
+    date: Option<NaiveDate>,
+    last: Option<DateTime<Utc>>,
+}
+
+impl FixClock {
+    fn new(upload_time: DateTime<Utc>) -> Self {
+        Self {
+            upload_time,
+            date: None,
+            last: None,
+        }
+    }
+
+    fn sample_time(&mut self, sentence: &nmea0183::DecodedSentence) -> Option<DateTime<Utc>> {
+        let time = sentence.time?;
+        if sentence.date.is_some() {
+            self.date = sentence.date;
+        }
+
+        let date = match self.date {
+            Some(date) => date,
+            None if time > self.upload_time.time() => self.upload_time.date_naive().pred_opt()?,
+            None => self.upload_time.date_naive(),
+        };
+        let mut at = date.and_time(time).and_utc();
+        if let Some(last) = self.last
+            && sentence.date.is_none()
+            && at < last - Duration::hours(12)
+        {
+            at += Duration::days(1);
+            self.date = Some(at.date_naive());
+        }
+
+        self.last = Some(at);
+        Some(at)
+    }
+}
+
+impl NmeaService {
+    pub fn new(
+        telemetry_service: Arc<TelemetryService>,
+        mapping_repo: Arc<SignalMappingRepository>,
+        vessel_repo: Arc<VesselRepository>,
+    ) -> Self {
+        Self {
+            telemetry_service,
+            mapping_repo,
+            vessel_repo,
+        }
+    }
+
+    /// Decodes a block of NMEA 0183 sentences into one telemetry frame per fix.
+    ///
+    /// Decoded fields are renamed through signal_source_mappings and validated like any other
+    /// frame. Each GGA, RMC or ZDA time starts a frame that the following sentences join.
+    /// Sentences with a bad checksum, an unsupported type or unparseable fields are stored as
+    /// filtered rows.
+    pub async fn ingest_sentences(
+        &self,
+        vessel_id: &str,
+        body: &str,
+        timestamp_utc: DateTime<Utc>,
+    ) -> Result<DecodedIngestResponse, AppError> {
+        let lines = Self::split_lines(body, "NMEA sentence")?;
+
+        info!(
+            vessel_id = %vessel_id,
+            sentence_count = lines.len(),
+            "Starting NMEA 0183 ingestion"
+        );
+
+        let mut clock = FixClock::new(timestamp_utc);
+        let mut messages = Vec::new();
+        let mut rejected = Vec::new();
+
+        for line in &lines {
+            match nmea0183::decode_sentence(line) {
+                Ok(sentence) => messages.push(DecodedMessage {
+                    sampled_at: clock.sample_time(&sentence),
+                    ..DecodedMessage::new(sentence.sentence_type, sentence.fields)
+                }),
+                Err(e) => {
+                    warn!(sentence = %line, error = %e, "NMEA sentence rejected");
+                    let label = match nmea0183::sentence_label(line) {
+                        label if label.is_empty() => "unknown".to_string(),
+                        label => label,
+                    };
+                    rejected.push(DecodeRejection {
+                        label: format!("{}:{}", SOURCE_NMEA0183, label),
+                        reason: e.reason().to_string(),
+                    });
+                }
+            }
+        }
+
+        let upload = DecodedUpload {
+            body_digest: Self::body_digest(body),
+            lines_received: lines.len(),
+            messages,
+            rejected,
+            ignored: 0,
+        };
+        self.ingest_decoded(vessel_id, SOURCE_NMEA0183, upload, timestamp_utc)
+            .await
+    }
+
+    /// Decodes an NMEA 2000 / J1939 capture into one telemetry frame per second of capture time.
+    ///
+    /// Fields are keyed by PGN in signal_source_mappings. Lines without a capture timestamp join
+    /// the frame before them. Unsupported PGNs, unparseable lines and fast packets missing frames
+    /// are stored as filtered rows.
+    pub async fn ingest_pgns(
+        &self,
+        vessel_id: &str,
+        body: &str,
+        timestamp_utc: DateTime<Utc>,
+    ) -> Result<DecodedIngestResponse, AppError> {
+        let lines = Self::split_lines(body, "CAN frame")?;
+
+        info!(
+            vessel_id = %vessel_id,
+            line_count = lines.len(),
+            "Starting NMEA 2000 ingestion"
+        );
+
+        let mut decoder = nmea2000::Decoder::new();
+        let mut messages = Vec::new();
+        let mut errors = Vec::new();
+
+        for line in &lines {
+            match decoder.push_line(line) {
+                Ok(Some(message)) => messages.push(DecodedMessage {
+                    sampled_at: message
+                        .timestamp
+                        .and_then(|at| at.duration_trunc(N2K_FRAME_BUCKET).ok()),
+                    ..DecodedMessage::new(message.pgn.to_string(), message.fields)
+                }),
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(line = %line, error = %e, "NMEA 2000 frame rejected");
+                    errors.push(e);
+                }
+            }
+        }
+        errors.extend(decoder.finish());
+
+        let rejected = errors
+            .iter()
+            .map(|e| DecodeRejection {
+                label: format!("{}:{}", SOURCE_NMEA2000, nmea2000::error_label(e)),
+                reason: e.reason().to_string(),
+            })
+            .collect();
+
+        let upload = DecodedUpload {
+            body_digest: Self::body_digest(body),
+            lines_received: lines.len(),
+            messages,
+            rejected,
+            ignored: 0,
+        };
+        self.ingest_decoded(vessel_id, SOURCE_NMEA2000, upload, timestamp_utc)
+            .await
+    }
+
+    /// Decodes AIVDM/AIVDO sentences, keeping only reports about the vessel itself.
+    ///
+    /// AIVDO sentences are always own-ship and also establish the vessel's MMSI; AIVDM reports
+    /// count when their MMSI matches the registered one, other traffic is ignored. Each position
+    /// report (types 1, 2, 3, 18) becomes a telemetry frame under the `position` message type.
+    /// Static data (types 5, 24) updates the voyage columns of vessel_register_table.
+    pub async fn ingest_ais(
+        &self,
+        vessel_id: &str,
+        body: &str,
+        timestamp_utc: DateTime<Utc>,
+    ) -> Result<DecodedIngestResponse, AppError> {
+        let lines = Self::split_lines(body, "AIS sentence")?;
+
+        let vessel = self
+            .vessel_repo
+            .find_by_id(vessel_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::Forbidden(format!(
+                    "Vessel {} is not registered in vessel_register_table",
+                    vessel_id
+                ))
+            })?;
+
+        info!(
+            vessel_id = %vessel_id,
+            sentence_count = lines.len(),
+            "Starting AIS ingestion"
+        );
+
+        let mut decoder = ais::Decoder::new();
+        let mut decoded = Vec::new();
+        let mut upload = DecodedUpload {
+            body_digest: Self::body_digest(body),
+            lines_received: lines.len(),
+            messages: Vec::new(),
+            rejected: Vec::new(),
+            ignored: 0,
+        };
+
+        for line in &lines {
+            match decoder.push_line(line) {
+                Ok(Some(message)) => decoded.push(message),
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(sentence = %line, error = %e, "AIS sentence rejected");
+                    upload
+                        .rejected
+                        .push(Self::ais_rejection(&nmea0183::sentence_label(line), &e));
+                }
+            }
+        }
+        for e in decoder.finish() {
+            upload.rejected.push(Self::ais_rejection("fragments", &e));
+        }
+
+        let reported_mmsi = decoded
+            .iter()
+            .find(|m| m.own_ship)
+            .and_then(|m| i32::try_from(m.mmsi).ok());
+        let own_mmsi = reported_mmsi.or(vessel.mmsi);
+
+        let decoded_count = decoded.len();
+        let own: Vec<_> = decoded
+            .into_iter()
+            .filter(|m| m.own_ship || i32::try_from(m.mmsi).ok() == own_mmsi)
+            .collect();
+        upload.ignored = decoded_count - own.len();
+        let report_seconds: Vec<_> = own
+            .iter()
+            .filter_map(|m| match &m.body {
+                AisBody::Position(report) => Some(report.utc_second),
+                _ => None,
+            })
+            .collect();
+        let mut report_times = Self::report_times(&report_seconds, timestamp_utc).into_iter();
+
+        let mut static_update: Option<AisStaticUpdate> = None;
+        for message in own {
+            match message.body {
+                AisBody::Position(report) => upload.messages.push(DecodedMessage {
+                    sampled_at: report_times.next(),
+                    ..DecodedMessage::new(AIS_POSITION_MESSAGE, report.fields)
+                }),
+                AisBody::Static(data) => {
+                    let fields = data
+                        .draught_m
+                        .map(|draught| ("draught_m".to_string(), draught))
+                        .into_iter()
+                        .collect();
+                    upload
+                        .messages
+                        .push(DecodedMessage::new(AIS_STATIC_MESSAGE, fields));
+                    Self::merge_static(static_update.get_or_insert_default(), data);
+                }
+                AisBody::Other => upload.rejected.push(Self::ais_rejection(
+                    &message.message_type.to_string(),
+                    &ais::AisError::UnsupportedMessage(message.message_type),
+                )),
+            }
+        }
+
+        let response = self
+            .ingest_decoded(vessel_id, SOURCE_AIS, upload, timestamp_utc)
+            .await?;
+
+        if let Some(mmsi) = reported_mmsi
+            && vessel.mmsi != Some(mmsi)
+        {
+            info!(vessel_id = %vessel_id, mmsi, "Own-ship MMSI learned from AIVDO");
+            self.vessel_repo.set_mmsi(vessel_id, mmsi).await?;
+        }
+        if let Some(update) = static_update {
+            info!(
+                vessel_id = %vessel_id,
+                destination = ?update.destination,
+                eta = ?update.eta,
+                draught = ?update.draught,
+                "Updating AIS voyage data"
+            );
+            self.vessel_repo
+                .update_ais_static(vessel_id, &update)
+                .await?;
+        }
+
+        Ok(response)
+    }
+
+    /// Places position reports in time from the second of the minute each one carries.
+    ///
+    /// Working back from the upload time, the last report takes the latest matching second at or
+    /// before it and each earlier report the latest matching second before the one after it, so
+    /// reports less than a minute apart land on their own seconds. A report without a second
+    /// takes the second before the next report.
+    fn report_times(seconds: &[Option<u32>], upload_time: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let second = Duration::seconds(1);
+        let mut next = upload_time.duration_trunc(second).unwrap_or(upload_time) + second;
+        let mut times = Vec::with_capacity(seconds.len());
+
+        for utc_second in seconds.iter().rev() {
+            let latest = next - second;
+            next = match utc_second {
+                Some(utc_second) => {
+                    let back = (i64::from(latest.second()) - i64::from(*utc_second)).rem_euclid(60);
+                    latest - Duration::seconds(back)
+                }
+                None => latest,
+            };
+            times.push(next);
+        }
+
+        times.reverse();
+        times
+    }
+
+    fn ais_rejection(label: &str, error: &ais::AisError) -> DecodeRejection {
+        let label = if label.is_empty() { "unknown" } else { label };
+        DecodeRejection {
+            label: format!("{}:{}", SOURCE_AIS, label),
+            reason: error.reason().to_string(),
+        }
+    }
+
+    /// Folds a static report into the pending update; later reports win field by field
+    fn merge_static(update: &mut AisStaticUpdate, data: ais::StaticData) {
+        if data.callsign.is_some() {
+            update.callsign = data.callsign;
+        }
+        if let Some(ship_type) = data.ship_type {
+            update.ship_type = Some(i16::from(ship_type));
+        }
+        if data.destination.is_some() {
+            update.destination = data.destination;
+        }
+        if let Some(eta) = data.eta.and_then(|eta| Self::resolve_eta(eta, Utc::now())) {
+            update.eta = Some(eta);
+        }
+        if let Some(draught) = data.draught_m.and_then(|d| Decimal::try_from(d).ok()) {
+            update.draught = Some(draught.round_dp(1));
+        }
+    }
+
+    /// Places a year-less ETA in the current year, or the next one if that is long past
+    fn resolve_eta(eta: ais::Eta, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let at = |year| {
+            Utc.with_ymd_and_hms(year, eta.month, eta.day, eta.hour, eta.minute, 0)
+                .single()
+        };
+        let this_year = at(now.year())?;
+        if this_year < now - Duration::days(AIS_ETA_ROLLOVER_DAYS) {
+            at(now.year() + 1)
+        } else {
+            Some(this_year)
+        }
+    }
+
+    /// First 128 bits of the body's SHA-256, in hex
+    fn body_digest(body: &str) -> String {
+        let mut digest = format!("{:x}", Sha256::digest(body.as_bytes()));
+        digest.truncate(32);
+        digest
+    }
+
+    /// Non-empty trimmed lines of an upload, bounded by `MAX_NMEA_LINES`
+    fn split_lines<'a>(body: &'a str, unit: &str) -> Result<Vec<&'a str>, AppError> {
+        let lines: Vec<&str> = body
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        if lines.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Body must contain at least one {}",
+                unit
+            )));
+        }
+        if lines.len() > MAX_NMEA_LINES {
+            return Err(AppError::Validation(format!(
+                "Body contains {} lines, maximum is {}",
+                lines.len(),
+                MAX_NMEA_LINES
+            )));
+        }
+
+        Ok(lines)
+    }
+
+    /// Renames decoded fields through the source's mappings and ingests them as one frame per
+    /// sample time.
+    ///
+    /// A message with a new `sampled_at` starts a frame, and untimed messages join the current one;
+    /// messages before the first timed one join the first frame. Without any time the whole upload
+    /// is one frame stamped `timestamp_utc`, and a signal sampled twice with different values is
+    /// refused since the samples cannot be told apart. Within a timed frame the last value wins.
+    /// Decode rejections are stored with the first frame that is accepted.
+    ///
+    /// Frames are committed one by one, each claimed under an ID made of the source, the body
+    /// digest, its time and its position, so retrying an upload that failed part-way only writes
+    /// the frames that are missing.
+    async fn ingest_decoded(
+        &self,
+        vessel_id: &str,
+        source: &str,
+        upload: DecodedUpload,
+        timestamp_utc: DateTime<Utc>,
+    ) -> Result<DecodedIngestResponse, AppError> {
+        let mappings = self.mapping_repo.find_by_source(source).await?;
+
+        let messages_decoded = upload.messages.len();
+        let rejected = upload.rejected;
+        let first_time = upload.messages.iter().find_map(|m| m.sampled_at);
+        let mut unmapped = BTreeSet::new();
+        let mut frames = vec![(first_time.unwrap_or(timestamp_utc), HashMap::new())];
+
+        for message in upload.messages {
+            if let Some(at) = message.sampled_at
+                && frames.last().is_some_and(|(current, _)| *current != at)
+            {
+                frames.push((at, HashMap::new()));
+            }
+            let (_, signals) = frames.last_mut().expect("at least one frame");
+
+            for (field, value) in message.fields {
+                let key = (message.message_type.clone(), field);
+                let Some(signal_name) = mappings.get(&key) else {
+                    unmapped.insert(format!("{}.{}", key.0, key.1));
+                    continue;
+                };
+
+                let value = serde_json::Value::from(value);
+                let previous = signals.insert(signal_name.clone(), value.clone());
+                if first_time.is_none() && previous.is_some_and(|p| p != value) {
+                    return Err(AppError::Validation(format!(
+                        "Signal {} has more than one sample but the upload carries no time to tell them apart",
+                        signal_name
+                    )));
+                }
+            }
+        }
+
+        // Frames without a mapped signal are dropped, but one is always sent so the decode
+        // rejections are stored
+        frames.retain(|(_, signals)| !signals.is_empty());
+        if frames.is_empty() {
+            frames.push((first_time.unwrap_or(timestamp_utc), HashMap::new()));
+        }
+
+        let mut correlation_ids = Vec::new();
+        let mut valid_signals = 0;
+        let mut invalid_signals = 0;
+        let mut frames_rejected = 0;
+        let mut first_rejection = None;
+        let single_frame = frames.len() == 1;
+
+        for (index, (timestamp_utc, signals)) in frames.into_iter().enumerate() {
+            let frame_rejected: &[DecodeRejection] = if correlation_ids.is_empty() {
+                &rejected
+            } else {
+                &[]
+            };
+            let request = TelemetryRequest {
+                vessel_id: vessel_id.to_string(),
+                timestamp_utc,
+                epoch_utc: timestamp_utc.timestamp().to_string(),
+                signals,
+                frame_id: Some(format!(
+                    "{}:{}:{}:{}",
+                    source,
+                    upload.body_digest,
+                    timestamp_utc.timestamp_millis(),
+                    index
+                )),
+            };
+
+            match self
+                .telemetry_service
+                .ingest_decoded_frame(request, frame_rejected)
+                .await
+            {
+                Ok(response) => {
+                    correlation_ids.push(response.correlation_id);
+                    valid_signals += response.valid_signals;
+                    invalid_signals += response.invalid_signals - frame_rejected.len();
+                }
+                // One bad frame should not cost the rest of the upload
+                Err(e @ (AppError::Rejected { .. } | AppError::Validation(_))) if !single_frame => {
+                    warn!(
+                        vessel_id = %vessel_id,
+                        timestamp_utc = %timestamp_utc,
+                        error = %e,
+                        "Decoded frame rejected"
+                    );
+                    frames_rejected += 1;
+                    first_rejection.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let Some(&correlation_id) = correlation_ids.first() else {
+            return Err(first_rejection.expect("every frame was rejected"));
+        };
+
+        info!(
+            correlation_id = %correlation_id,
+            source = %source,
+            frames = correlation_ids.len(),
+            frames_rejected,
+            rejected_messages = rejected.len(),
+            unmapped_fields = unmapped.len(),
+            "Decoded telemetry ingestion completed"
+        );
+
+        Ok(DecodedIngestResponse {
+            message: "Decoded telemetry ingested successfully".to_string(),
+            correlation_id,
+            correlation_ids,
+            frames_rejected,
+            messages_received: upload.lines_received,
+            messages_decoded,
+            messages_rejected: rejected.len(),
+            messages_ignored: upload.ignored,
+            valid_signals,
+            invalid_signals,
+            unmapped_fields: unmapped.into_iter().collect(),
+        })
+    }
+}
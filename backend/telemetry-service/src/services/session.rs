@@ -0,0 +1,153 @@
+use crate::error::AppError;
+use crate::models::session::{
+    SessionCounters, SessionEndReason, SessionQuery, SessionReply, SessionResponse,
+};
+use crate::models::telemetry::TelemetryRequest;
+use crate::repositories::session::SessionRepository;
+use crate::services::telemetry::TelemetryService;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Most sessions returned by one listing
+const MAX_SESSION_LIST: i64 = 1000;
+
+pub struct SessionService {
+    session_repo: Arc<SessionRepository>,
+    telemetry_service: Arc<TelemetryService>,
+    idle_timeout: Duration,
+}
+
+impl SessionService {
+    pub fn new(
+        session_repo: Arc<SessionRepository>,
+        telemetry_service: Arc<TelemetryService>,
+        idle_timeout: Duration,
+    ) -> Self {
+        Self {
+            session_repo,
+            telemetry_service,
+            idle_timeout,
+        }
+    }
+
+    /// How long a session may stay silent before it is closed
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    /// Records a connect event and returns the new session ID
+    pub async fn open_session(&self, vessel_id: &str, transport: &str) -> Result<Uuid, AppError> {
+        let session_id = Uuid::new_v4();
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Uuid::new_v4().to_string();
+
+        self.session_repo
+            .open(session_id, vessel_id, transport, correlation_id, &trace_id)
+            .await?;
+
+        info!(
+            session_id = %session_id,
+            vessel_id = %vessel_id,
+            transport = %transport,
+            "Ingestion session opened"
+        );
+
+        Ok(session_id)
+    }
+
+    /// Records a disconnect event along with the session's frame counts
+    pub async fn close_session(
+        &self,
+        session_id: Uuid,
+        reason: SessionEndReason,
+        counters: SessionCounters,
+    ) -> Result<(), AppError> {
+        self.session_repo
+            .close(session_id, reason.as_str(), &counters)
+            .await?;
+
+        info!(
+            session_id = %session_id,
+            reason = reason.as_str(),
+            frames_received = counters.frames_received,
+            frames_accepted = counters.frames_accepted,
+            frames_rejected = counters.frames_rejected,
+            "Ingestion session closed"
+        );
+
+        Ok(())
+    }
+
+    /// Closes sessions left open by a previous run; call before accepting connections
+    pub async fn close_stale_sessions(&self) -> Result<u64, AppError> {
+        let closed = self
+            .session_repo
+            .close_all_open(SessionEndReason::ServerRestart.as_str())
+            .await?;
+
+        if closed > 0 {
+            warn!(
+                sessions = closed,
+                "Closed sessions left open by previous run"
+            );
+        }
+
+        Ok(closed)
+    }
+
+    pub async fn list_sessions(
+        &self,
+        query: SessionQuery,
+    ) -> Result<Vec<SessionResponse>, AppError> {
+        let limit = query.limit.unwrap_or(100).clamp(1, MAX_SESSION_LIST);
+        let sessions = self
+            .session_repo
+            .find(query.vessel_id, query.active.unwrap_or(false), limit)
+            .await?;
+
+        Ok(sessions.into_iter().map(Into::into).collect())
+    }
+
+    /// Ingests one JSON frame received on a session authenticated as `vessel_id`
+    pub async fn ingest_frame(&self, vessel_id: &str, seq: i64, text: &str) -> SessionReply {
+        let request: TelemetryRequest = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(e) => {
+                return SessionReply::Error {
+                    seq,
+                    frame_id: None,
+                    message: format!("Invalid telemetry frame: {}", e),
+                    reason: None,
+                };
+            }
+        };
+        let frame_id = request.frame_id.clone();
+
+        let result = if request.vessel_id != vessel_id {
+            Err(AppError::Forbidden(format!(
+                "Vessel ID mismatch: authenticated as '{}' but payload contains '{}'",
+                vessel_id, request.vessel_id
+            )))
+        } else {
+            self.telemetry_service.ingest_telemetry(request).await
+        };
+
+        match result {
+            Ok(response) => SessionReply::Ack {
+                seq,
+                frame_id,
+                correlation_id: response.correlation_id,
+                valid_signals: response.valid_signals,
+                invalid_signals: response.invalid_signals,
+            },
+            Err(e) => SessionReply::Error {
+                seq,
+                frame_id,
+                reason: e.reason().map(str::to_string),
+                message: e.to_string(),
+            },
+        }
+    }
+}
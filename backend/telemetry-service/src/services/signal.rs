@@ -0,0 +1,443 @@
+use crate::error::AppError;
+use crate::models::signal::{
+    CatalogueSignal, CreateSignalRequest, SIGNAL_TYPE_ANALOG, SIGNAL_TYPE_DIGITAL, Signal,
+    SignalCatalogueDiff, SignalChange, SignalDefinition, SignalQuery, SignalResponse,
+};
+use crate::repositories::signal::SignalRepository;
+use crate::services::signal_registry::SignalRegistryCache;
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Longest name accepted (signal_register_table.signal_name is VARCHAR(100))
+pub const MAX_SIGNAL_NAME_LEN: usize = 100;
+
+/// Longest unit accepted (signal_register_table.unit is VARCHAR(30))
+pub const MAX_SIGNAL_UNIT_LEN: usize = 30;
+
+/// Prefix the protobuf endpoint gives samples whose `signal_id` is unknown
+const RESERVED_NAME_PREFIX: &str = "signal_id:";
+
+/// Bounds are stored as DECIMAL(10,2)
+const MAX_BOUND_SCALE: u32 = 2;
+
+/// Invalid catalogue entries listed in one error before the rest are only counted
+const MAX_CATALOGUE_ERRORS: usize = 20;
+
+pub struct SignalService {
+    signal_repo: Arc<SignalRepository>,
+    /// Reloaded after each change so this instance applies it to the next frame
+    signal_registry: Arc<SignalRegistryCache>,
+}
+
+impl SignalService {
+    pub fn new(
+        signal_repo: Arc<SignalRepository>,
+        signal_registry: Arc<SignalRegistryCache>,
+    ) -> Self {
+        Self {
+            signal_repo,
+            signal_registry,
+        }
+    }
+
+    pub async fn create_signal(
+        &self,
+        request: CreateSignalRequest,
+    ) -> Result<SignalResponse, AppError> {
+        validate_signal_name(&request.signal_name)?;
+        let definition = validate_definition(request.definition)?;
+
+        if let Some(existing) = self.signal_repo.find_by_name(&request.signal_name).await? {
+            let hint = if existing.retired_at.is_some() {
+                " (retired; update it to bring it back)"
+            } else {
+                ""
+            };
+            return Err(AppError::Conflict(format!(
+                "Signal {} already exists{}",
+                request.signal_name, hint
+            )));
+        }
+
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Some(Uuid::new_v4().to_string());
+
+        let signal = self
+            .signal_repo
+            .create(&request.signal_name, &definition, correlation_id, trace_id)
+            .await?;
+
+        info!("Signal created: {}", signal.signal_name);
+        self.signal_registry.refresh("signal_created").await;
+
+        Ok(signal.into())
+    }
+
+    pub async fn get_signal(&self, signal_name: &str) -> Result<SignalResponse, AppError> {
+        let signal = self
+            .signal_repo
+            .find_by_name(signal_name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Signal {} not found", signal_name)))?;
+
+        Ok(signal.into())
+    }
+
+    pub async fn list_signals(&self, query: SignalQuery) -> Result<Vec<SignalResponse>, AppError> {
+        let signal_type = query
+            .signal_type
+            .map(|signal_type| normalize_signal_type(&signal_type).map(str::to_string))
+            .transpose()?;
+        let name_like = query.name.as_deref().map(name_pattern_to_like);
+
+        let signals = self
+            .signal_repo
+            .list(signal_type, name_like, query.include_retired)
+            .await?;
+
+        Ok(signals.into_iter().map(Into::into).collect())
+    }
+
+    /// Replaces a signal's definition; updating a retired signal brings it back
+    pub async fn update_signal(
+        &self,
+        signal_name: &str,
+        definition: SignalDefinition,
+    ) -> Result<SignalResponse, AppError> {
+        let definition = validate_definition(definition)?;
+
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Some(Uuid::new_v4().to_string());
+
+        let signal = self
+            .signal_repo
+            .update(signal_name, &definition, correlation_id, trace_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Signal {} not found", signal_name)))?;
+
+        info!("Signal updated: {}", signal.signal_name);
+        self.signal_registry.refresh("signal_updated").await;
+
+        Ok(signal.into())
+    }
+
+    /// Stops accepting a signal while keeping its definition and history; retiring twice is a no-op
+    pub async fn retire_signal(&self, signal_name: &str) -> Result<(), AppError> {
+        self.signal_repo
+            .find_by_name(signal_name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Signal {} not found", signal_name)))?;
+
+        if self.signal_repo.retire(signal_name).await? {
+            info!("Signal retired: {}", signal_name);
+            self.signal_registry.refresh("signal_retired").await;
+        }
+
+        Ok(())
+    }
+
+    /// Compares a catalogue with the registry and, if `apply`, makes the registry match it.
+    ///
+    /// Catalogue signals are created or replaced, retired ones brought back, and active signals
+    /// the catalogue leaves out are retired, all in one transaction. Nothing is written if any
+    /// entry is invalid.
+    pub async fn import_catalogue(
+        &self,
+        entries: Vec<CatalogueSignal>,
+        apply: bool,
+    ) -> Result<SignalCatalogueDiff, AppError> {
+        let catalogue = validate_catalogue(entries)?;
+        let mut registry = self.signal_repo.find_all().await?;
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        let mut unchanged = 0;
+        for signal in catalogue {
+            match registry.remove(&signal.signal_name) {
+                None => added.push(signal),
+                Some(current) => {
+                    let fields = changed_fields(&current, &signal);
+                    if fields.is_empty() {
+                        unchanged += 1;
+                    } else {
+                        changed.push(SignalChange {
+                            signal_name: signal.signal_name.clone(),
+                            fields,
+                            before: current.into(),
+                            after: signal,
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut removed: Vec<Signal> = registry
+            .into_values()
+            .filter(|signal| signal.retired_at.is_none())
+            .collect();
+        removed.sort_by_key(|signal| signal.signal_id);
+        let removed: Vec<CatalogueSignal> = removed.into_iter().map(Into::into).collect();
+
+        let mut correlation_id = None;
+        if apply && !(added.is_empty() && changed.is_empty() && removed.is_empty()) {
+            let id = Uuid::new_v4();
+            let trace_id = Some(Uuid::new_v4().to_string());
+
+            let upserts: Vec<CatalogueSignal> = added
+                .iter()
+                .cloned()
+                .chain(changed.iter().map(|change| change.after.clone()))
+                .collect();
+            let retire: Vec<String> = removed.iter().map(|s| s.signal_name.clone()).collect();
+            self.signal_repo
+                .apply_catalogue(&upserts, &retire, id, trace_id)
+                .await?;
+
+            info!(
+                correlation_id = %id,
+                added = added.len(),
+                changed = changed.len(),
+                removed = removed.len(),
+                "Signal catalogue imported"
+            );
+            self.signal_registry.refresh("catalogue_imported").await;
+            correlation_id = Some(id);
+        }
+
+        Ok(SignalCatalogueDiff {
+            applied: apply,
+            correlation_id,
+            added,
+            changed,
+            removed,
+            unchanged,
+        })
+    }
+
+    /// Active signals in registration order
+    pub async fn export_catalogue(&self) -> Result<Vec<CatalogueSignal>, AppError> {
+        let signals = self.signal_repo.list(None, None, false).await?;
+        Ok(signals.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Checks every entry like `POST /signals` would, reporting all problems at once
+fn validate_catalogue(entries: Vec<CatalogueSignal>) -> Result<Vec<CatalogueSignal>, AppError> {
+    if entries.is_empty() {
+        return Err(AppError::Validation(
+            "Catalogue has no signals; importing it would retire every signal".to_string(),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+    let mut catalogue = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.into_iter().enumerate() {
+        let signal_name = entry.signal_name;
+        let definition = SignalDefinition {
+            signal_type: entry.signal_type,
+            min_value: entry.min_value,
+            max_value: entry.max_value,
+            description: entry.description,
+            unit: entry.unit,
+        };
+
+        let checked = validate_signal_name(&signal_name)
+            .and_then(|_| validate_definition(definition))
+            .and_then(|definition| {
+                if seen.insert(signal_name.clone()) {
+                    Ok(definition)
+                } else {
+                    Err(AppError::Validation("listed more than once".to_string()))
+                }
+            });
+        match checked {
+            Ok(definition) => catalogue.push(CatalogueSignal {
+                signal_name,
+                signal_type: definition.signal_type,
+                min_value: definition.min_value,
+                max_value: definition.max_value,
+                unit: definition.unit,
+                description: definition.description,
+            }),
+            Err(AppError::Validation(message)) => errors.push(format!(
+                "entry {} ({}): {}",
+                index + 1,
+                signal_name,
+                message
+            )),
+            Err(e) => return Err(e),
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(catalogue);
+    }
+
+    let total = errors.len();
+    errors.truncate(MAX_CATALOGUE_ERRORS);
+    let more = if total > MAX_CATALOGUE_ERRORS {
+        format!("; and {} more", total - MAX_CATALOGUE_ERRORS)
+    } else {
+        String::new()
+    };
+    Err(AppError::Validation(format!(
+        "Catalogue has {} invalid entries: {}{}",
+        total,
+        errors.join("; "),
+        more
+    )))
+}
+
+/// Catalogue field names that differ between the registry and a validated entry
+fn changed_fields(current: &Signal, signal: &CatalogueSignal) -> Vec<String> {
+    let mut fields = Vec::new();
+    if current.signal_type != signal.signal_type {
+        fields.push("signalType");
+    }
+    if current.min_value != signal.min_value {
+        fields.push("minValue");
+    }
+    if current.max_value != signal.max_value {
+        fields.push("maxValue");
+    }
+    if current.unit != signal.unit {
+        fields.push("unit");
+    }
+    if current.description != signal.description {
+        fields.push("description");
+    }
+    if current.retired_at.is_some() {
+        fields.push("retired");
+    }
+    fields.into_iter().map(str::to_string).collect()
+}
+
+pub fn validate_signal_name(signal_name: &str) -> Result<(), AppError> {
+    if signal_name.is_empty() || signal_name.len() > MAX_SIGNAL_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "signalName must be 1 to {} characters",
+            MAX_SIGNAL_NAME_LEN
+        )));
+    }
+    if !signal_name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(AppError::Validation(format!(
+            "signalName '{}' may only contain letters, digits, '_', '-' and '.'",
+            signal_name
+        )));
+    }
+    if signal_name.starts_with(RESERVED_NAME_PREFIX) {
+        return Err(AppError::Validation(format!(
+            "signalName cannot start with '{}'",
+            RESERVED_NAME_PREFIX
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks a definition and fills in what its type implies.
+///
+/// Digital signals take 0 or 1, so their bounds default to 0 and 1 and cannot be anything
+/// else. Analog signals need both bounds, with `minValue <= maxValue`.
+pub fn validate_definition(definition: SignalDefinition) -> Result<SignalDefinition, AppError> {
+    let signal_type = normalize_signal_type(&definition.signal_type)?;
+
+    let (min_value, max_value) = if signal_type == SIGNAL_TYPE_DIGITAL {
+        let min_value = definition.min_value.unwrap_or(Decimal::ZERO);
+        let max_value = definition.max_value.unwrap_or(Decimal::ONE);
+        if min_value != Decimal::ZERO || max_value != Decimal::ONE {
+            return Err(AppError::Validation(format!(
+                "Digital signals take 0 or 1: minValue must be 0 and maxValue 1, got {} and {}",
+                min_value, max_value
+            )));
+        }
+        (min_value, max_value)
+    } else {
+        let (Some(min_value), Some(max_value)) = (definition.min_value, definition.max_value)
+        else {
+            return Err(AppError::Validation(
+                "Analog signals need both minValue and maxValue".to_string(),
+            ));
+        };
+        check_bound("minValue", min_value)?;
+        check_bound("maxValue", max_value)?;
+        if min_value > max_value {
+            return Err(AppError::Validation(format!(
+                "minValue {} is greater than maxValue {}",
+                min_value, max_value
+            )));
+        }
+        (min_value, max_value)
+    };
+
+    let unit = trimmed(definition.unit);
+    if let Some(unit) = &unit
+        && unit.chars().count() > MAX_SIGNAL_UNIT_LEN
+    {
+        return Err(AppError::Validation(format!(
+            "unit must be at most {} characters",
+            MAX_SIGNAL_UNIT_LEN
+        )));
+    }
+
+    Ok(SignalDefinition {
+        signal_type: signal_type.to_string(),
+        min_value: Some(min_value),
+        max_value: Some(max_value),
+        description: trimmed(definition.description),
+        unit,
+    })
+}
+
+fn normalize_signal_type(signal_type: &str) -> Result<&'static str, AppError> {
+    match signal_type.trim().to_ascii_lowercase().as_str() {
+        SIGNAL_TYPE_DIGITAL => Ok(SIGNAL_TYPE_DIGITAL),
+        SIGNAL_TYPE_ANALOG => Ok(SIGNAL_TYPE_ANALOG),
+        other => Err(AppError::Validation(format!(
+            "Unknown signalType '{}', expected digital or analog",
+            other
+        ))),
+    }
+}
+
+/// Bounds must fit DECIMAL(10,2) exactly rather than be rounded by the database
+pub fn check_bound(field: &str, value: Decimal) -> Result<(), AppError> {
+    let limit = Decimal::from(100_000_000);
+    if value.abs() >= limit || value.normalize().scale() > MAX_BOUND_SCALE {
+        return Err(AppError::Validation(format!(
+            "{} {} must be below 100000000 in magnitude with at most {} decimal places",
+            field, value, MAX_BOUND_SCALE
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Turns a `*` wildcard pattern into a `LIKE` pattern, escaping `LIKE`'s own wildcards
+fn name_pattern_to_like(pattern: &str) -> String {
+    let mut like = String::with_capacity(pattern.len());
+    for c in pattern.chars() {
+        match c {
+            '*' => like.push('%'),
+            '%' | '_' | '\\' => {
+                like.push('\\');
+                like.push(c);
+            }
+            c => like.push(c),
+        }
+    }
+    like
+}
@@ -0,0 +1,118 @@
+use crate::error::AppError;
+use crate::models::signal::{CatalogueSignal, SignalCatalogue};
+use std::path::Path;
+
+/// CSV header row, matching the YAML keys
+const CSV_HEADER: [&str; 6] = [
+    "signalName",
+    "signalType",
+    "minValue",
+    "maxValue",
+    "unit",
+    "description",
+];
+
+/// File formats a signal catalogue is imported from and exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogueFormat {
+    Yaml,
+    Csv,
+}
+
+impl CatalogueFormat {
+    /// `yaml`, `yml` or `csv`, as given to `?format=` or `--format`
+    pub fn from_name(name: &str) -> Result<Self, AppError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Ok(CatalogueFormat::Yaml),
+            "csv" => Ok(CatalogueFormat::Csv),
+            _ => Err(AppError::Validation(format!(
+                "Unknown catalogue format '{}', expected yaml or csv",
+                name
+            ))),
+        }
+    }
+
+    pub fn from_content_type(content_type: Option<&str>) -> Result<Self, AppError> {
+        let mime = content_type
+            .unwrap_or_default()
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        match mime.as_str() {
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+                Ok(CatalogueFormat::Yaml)
+            }
+            "text/csv" => Ok(CatalogueFormat::Csv),
+            _ => Err(AppError::UnsupportedMediaType(format!(
+                "Content-Type '{}' is not supported, use application/yaml or text/csv",
+                content_type.unwrap_or_default()
+            ))),
+        }
+    }
+
+    /// Guesses the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| Self::from_name(ext).ok())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CatalogueFormat::Yaml => "yaml",
+            CatalogueFormat::Csv => "csv",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            CatalogueFormat::Yaml => "application/yaml",
+            CatalogueFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn parse(&self, bytes: &[u8]) -> Result<Vec<CatalogueSignal>, AppError> {
+        match self {
+            CatalogueFormat::Yaml => serde_yaml::from_slice::<SignalCatalogue>(bytes)
+                .map(|catalogue| catalogue.signals)
+                .map_err(|e| AppError::Validation(format!("Invalid YAML catalogue: {}", e))),
+            CatalogueFormat::Csv => csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(bytes)
+                .deserialize()
+                .collect::<Result<Vec<CatalogueSignal>, _>>()
+                .map_err(|e| AppError::Validation(format!("Invalid CSV catalogue: {}", e))),
+        }
+    }
+
+    pub fn render(&self, signals: Vec<CatalogueSignal>) -> Result<String, AppError> {
+        match self {
+            CatalogueFormat::Yaml => serde_yaml::to_string(&SignalCatalogue { signals })
+                .map_err(|e| AppError::Internal(format!("Failed to write YAML catalogue: {}", e))),
+            CatalogueFormat::Csv => {
+                let failed = |e: csv::Error| {
+                    AppError::Internal(format!("Failed to write CSV catalogue: {}", e))
+                };
+
+                // Header written by hand so an empty registry still exports one
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(Vec::new());
+                writer.write_record(CSV_HEADER).map_err(failed)?;
+                for signal in &signals {
+                    writer.serialize(signal).map_err(failed)?;
+                }
+
+                let bytes = writer.into_inner().map_err(|e| {
+                    AppError::Internal(format!("Failed to write CSV catalogue: {}", e))
+                })?;
+                String::from_utf8(bytes).map_err(|e| {
+                    AppError::Internal(format!("Failed to write CSV catalogue: {}", e))
+                })
+            }
+        }
+    }
+}
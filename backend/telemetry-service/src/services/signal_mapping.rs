@@ -0,0 +1,118 @@
+use crate::error::AppError;
+use crate::models::signal_mapping::{
+    SOURCE_AIS, SOURCE_NMEA0183, SOURCE_NMEA2000, SignalMappingResponse, UpsertSignalMappingRequest,
+};
+use crate::repositories::{signal::SignalRepository, signal_mapping::SignalMappingRepository};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Sources with a decoder behind them
+pub const KNOWN_SOURCES: [&str; 3] = [SOURCE_NMEA0183, SOURCE_NMEA2000, SOURCE_AIS];
+
+pub struct SignalMappingService {
+    mapping_repo: Arc<SignalMappingRepository>,
+    signal_repo: Arc<SignalRepository>,
+}
+
+impl SignalMappingService {
+    pub fn new(
+        mapping_repo: Arc<SignalMappingRepository>,
+        signal_repo: Arc<SignalRepository>,
+    ) -> Self {
+        Self {
+            mapping_repo,
+            signal_repo,
+        }
+    }
+
+    pub async fn list_mappings(
+        &self,
+        source: Option<String>,
+    ) -> Result<Vec<SignalMappingResponse>, AppError> {
+        let mappings = self.mapping_repo.find_all(source).await?;
+        Ok(mappings.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates or replaces the mapping for one decoded field
+    pub async fn upsert_mapping(
+        &self,
+        request: UpsertSignalMappingRequest,
+    ) -> Result<SignalMappingResponse, AppError> {
+        if !KNOWN_SOURCES.contains(&request.source.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Unknown source '{}', expected one of: {}",
+                request.source,
+                KNOWN_SOURCES.join(", ")
+            )));
+        }
+        if request.message_type.is_empty() || request.message_type.len() > 20 {
+            return Err(AppError::Validation(
+                "messageType must be 1 to 20 characters".to_string(),
+            ));
+        }
+        if request.field_name.is_empty() || request.field_name.len() > 100 {
+            return Err(AppError::Validation(
+                "fieldName must be 1 to 100 characters".to_string(),
+            ));
+        }
+
+        let signals = self.signal_repo.find_all().await?;
+        if signals
+            .get(&request.signal_name)
+            .is_none_or(|signal| signal.retired_at.is_some())
+        {
+            return Err(AppError::Validation(format!(
+                "Signal {} is not registered in signal_register_table or is retired",
+                request.signal_name
+            )));
+        }
+
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Some(Uuid::new_v4().to_string());
+
+        let mapping = self
+            .mapping_repo
+            .upsert(
+                &request.source,
+                &request.message_type,
+                &request.field_name,
+                &request.signal_name,
+                correlation_id,
+                trace_id,
+            )
+            .await?;
+
+        info!(
+            source = %mapping.source,
+            message_type = %mapping.message_type,
+            field_name = %mapping.field_name,
+            signal_name = %mapping.signal_name,
+            "Signal mapping saved"
+        );
+
+        Ok(mapping.into())
+    }
+
+    pub async fn delete_mapping(
+        &self,
+        source: &str,
+        message_type: &str,
+        field_name: &str,
+    ) -> Result<(), AppError> {
+        if !self
+            .mapping_repo
+            .delete(source, message_type, field_name)
+            .await?
+        {
+            return Err(AppError::NotFound(format!(
+                "No mapping for {} {}.{}",
+                source, message_type, field_name
+            )));
+        }
+
+        info!(source, message_type, field_name, "Signal mapping deleted");
+
+        Ok(())
+    }
+}
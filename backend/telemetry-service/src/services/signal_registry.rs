@@ -0,0 +1,382 @@
+use crate::error::AppError;
+use crate::models::metrics::SignalCacheMetrics;
+use crate::models::signal::Signal;
+use crate::models::vessel_class::SignalRangeOverride;
+use crate::repositories::signal::SignalRepository;
+use crate::repositories::vessel_class::VesselClassRepository;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use sqlx::postgres::{PgListener, PgPoolOptions};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Channel the signal_register_table and vessel signal set triggers notify on
+pub const SIGNAL_REGISTRY_CHANNEL: &str = "signal_registry_changed";
+
+/// Wait before listening again after the listener connection fails
+const LISTEN_RETRY: Duration = Duration::from_secs(5);
+
+/// How a signal a vessel sent stands against its signal set
+pub enum ResolvedSignal<'a> {
+    /// Not in signal_register_table
+    Unregistered,
+    /// Registered, but not one of the signals of the vessel's class
+    Unowned,
+    Retired(&'a Signal),
+    /// The definition to validate against, with the vessel's own range if it has one
+    Active(&'a Signal),
+}
+
+/// The signals one vessel may send: the registry, narrowed to its class's signals if it has a
+/// class, with its own ranges in place of the registered ones
+pub struct VesselSignals {
+    registry: Arc<HashMap<String, Signal>>,
+    /// `None` for vessels without a class, which may send every registered signal
+    class: Option<(String, Arc<HashSet<String>>)>,
+    /// Registered definitions with the vessel's own range in place of the registered one
+    overridden: HashMap<String, Signal>,
+}
+
+impl VesselSignals {
+    /// Every registered signal, retired ones and those outside the vessel's class included
+    pub fn registry(&self) -> &HashMap<String, Signal> {
+        &self.registry
+    }
+
+    pub fn class_name(&self) -> Option<&str> {
+        self.class
+            .as_ref()
+            .map(|(class_name, _)| class_name.as_str())
+    }
+
+    pub fn owns(&self, signal_name: &str) -> bool {
+        self.class
+            .as_ref()
+            .is_none_or(|(_, signals)| signals.contains(signal_name))
+    }
+
+    pub fn is_overridden(&self, signal_name: &str) -> bool {
+        self.overridden.contains_key(signal_name)
+    }
+
+    /// The effective definition of a signal for this vessel
+    pub fn resolve(&self, signal_name: &str) -> ResolvedSignal<'_> {
+        let Some(signal) = self.registry.get(signal_name) else {
+            return ResolvedSignal::Unregistered;
+        };
+        if !self.owns(signal_name) {
+            return ResolvedSignal::Unowned;
+        }
+        if signal.retired_at.is_some() {
+            return ResolvedSignal::Retired(signal);
+        }
+
+        ResolvedSignal::Active(self.overridden.get(signal_name).unwrap_or(signal))
+    }
+}
+
+struct Snapshot {
+    signals: Arc<HashMap<String, Signal>>,
+    /// Signal sets of vessels with a class or overrides
+    vessels: HashMap<String, Arc<VesselSignals>>,
+    /// Signal set of every other vessel: the whole registry
+    unrestricted: Arc<VesselSignals>,
+    loaded_at: DateTime<Utc>,
+    loaded: Instant,
+}
+
+impl Snapshot {
+    fn build(
+        signals: HashMap<String, Signal>,
+        class_signals: HashMap<String, HashSet<String>>,
+        vessel_classes: HashMap<String, String>,
+        overrides: Vec<SignalRangeOverride>,
+    ) -> Self {
+        let signals = Arc::new(signals);
+        let class_signals: HashMap<String, Arc<HashSet<String>>> = class_signals
+            .into_iter()
+            .map(|(class_name, names)| (class_name, Arc::new(names)))
+            .collect();
+
+        let mut overridden: HashMap<String, HashMap<String, Signal>> = HashMap::new();
+        for o in overrides {
+            let Some(signal) = signals.get(&o.signal_name) else {
+                continue;
+            };
+            overridden.entry(o.vessel_id).or_default().insert(
+                o.signal_name,
+                Signal {
+                    min_value: Some(o.min_value),
+                    max_value: Some(o.max_value),
+                    ..signal.clone()
+                },
+            );
+        }
+
+        let mut vessels = HashMap::new();
+        for (vessel_id, class_name) in vessel_classes {
+            // A class without signals lets its vessels send none
+            let names = class_signals.get(&class_name).cloned().unwrap_or_default();
+            vessels.insert(
+                vessel_id.clone(),
+                Arc::new(VesselSignals {
+                    registry: signals.clone(),
+                    class: Some((class_name, names)),
+                    overridden: overridden.remove(&vessel_id).unwrap_or_default(),
+                }),
+            );
+        }
+        for (vessel_id, overridden) in overridden {
+            vessels.insert(
+                vessel_id,
+                Arc::new(VesselSignals {
+                    registry: signals.clone(),
+                    class: None,
+                    overridden,
+                }),
+            );
+        }
+
+        Self {
+            unrestricted: Arc::new(VesselSignals {
+                registry: signals.clone(),
+                class: None,
+                overridden: HashMap::new(),
+            }),
+            signals,
+            vessels,
+            loaded_at: Utc::now(),
+            loaded: Instant::now(),
+        }
+    }
+
+    fn vessel_signals(&self, vessel_id: &str) -> Arc<VesselSignals> {
+        self.vessels
+            .get(vessel_id)
+            .unwrap_or(&self.unrestricted)
+            .clone()
+    }
+}
+
+/// Every registered signal and every vessel's signal set, kept in memory so frames are
+/// validated without a query.
+///
+/// The snapshot is replaced whole, so a frame sees either the old registry or the new one. It
+/// is reloaded when PostgreSQL notifies a change on [`SIGNAL_REGISTRY_CHANNEL`], after this
+/// instance changes the registry, and every `refresh_interval_secs` in case a notification was
+/// missed.
+pub struct SignalRegistryCache {
+    signal_repo: Arc<SignalRepository>,
+    vessel_class_repo: Arc<VesselClassRepository>,
+    pool: PgPool,
+    refresh_interval_secs: u64,
+    snapshot: RwLock<Option<Arc<Snapshot>>>,
+    /// Held while loading so an older load never replaces a newer one
+    loading: tokio::sync::Mutex<()>,
+    listening: AtomicBool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    reloads: AtomicU64,
+    reload_failures: AtomicU64,
+    notifications: AtomicU64,
+}
+
+impl SignalRegistryCache {
+    pub fn new(
+        signal_repo: Arc<SignalRepository>,
+        vessel_class_repo: Arc<VesselClassRepository>,
+        pool: PgPool,
+        refresh_interval_secs: u64,
+    ) -> Self {
+        Self {
+            signal_repo,
+            vessel_class_repo,
+            pool,
+            refresh_interval_secs,
+            snapshot: RwLock::new(None),
+            loading: tokio::sync::Mutex::new(()),
+            listening: AtomicBool::new(false),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            reloads: AtomicU64::new(0),
+            reload_failures: AtomicU64::new(0),
+            notifications: AtomicU64::new(0),
+        }
+    }
+
+    /// The signals a vessel may send; only queries before the first load
+    pub async fn vessel_signals(&self, vessel_id: &str) -> Result<Arc<VesselSignals>, AppError> {
+        let snapshot = match self.current() {
+            Some(snapshot) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                snapshot
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                self.reload().await?
+            }
+        };
+
+        Ok(snapshot.vessel_signals(vessel_id))
+    }
+
+    /// Loads the registry; call once at startup
+    pub async fn load(&self) -> Result<(), AppError> {
+        let snapshot = self.reload().await?;
+        info!(
+            signal_count = snapshot.signals.len(),
+            vessel_signal_sets = snapshot.vessels.len(),
+            "Signal registry loaded"
+        );
+        Ok(())
+    }
+
+    /// Reloads the registry, logging rather than returning a failure; the previous snapshot
+    /// stays in use until a load succeeds
+    pub async fn refresh(&self, trigger: &'static str) {
+        match self.reload().await {
+            Ok(snapshot) => {
+                debug!(
+                    trigger,
+                    signal_count = snapshot.signals.len(),
+                    vessel_signal_sets = snapshot.vessels.len(),
+                    "Signal registry reloaded"
+                )
+            }
+            Err(e) => warn!(trigger, error = %e, "Failed to reload signal registry"),
+        }
+    }
+
+    /// Starts listening for registry changes and the periodic reload; call once at startup
+    pub fn spawn_refresh(self: &Arc<Self>) {
+        let cache = self.clone();
+        tokio::spawn(async move { cache.listen().await });
+
+        let cache = self.clone();
+        let interval = Duration::from_secs(self.refresh_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                cache.refresh("periodic").await;
+            }
+        });
+    }
+
+    pub fn metrics(&self) -> SignalCacheMetrics {
+        let snapshot = self.current();
+
+        SignalCacheMetrics {
+            signals: snapshot.as_ref().map_or(0, |s| s.signals.len()),
+            vessel_signal_sets: snapshot.as_ref().map_or(0, |s| s.vessels.len()),
+            loaded_at: snapshot.as_ref().map(|s| s.loaded_at),
+            age_secs: snapshot.as_ref().map(|s| s.loaded.elapsed().as_secs_f64()),
+            listening: self.listening.load(Ordering::Relaxed),
+            refresh_interval_secs: self.refresh_interval_secs,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            reloads: self.reloads.load(Ordering::Relaxed),
+            reload_failures: self.reload_failures.load(Ordering::Relaxed),
+            notifications: self.notifications.load(Ordering::Relaxed),
+        }
+    }
+
+    fn current(&self) -> Option<Arc<Snapshot>> {
+        self.snapshot
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    async fn reload(&self) -> Result<Arc<Snapshot>, AppError> {
+        let _loading = self.loading.lock().await;
+
+        let snapshot = match self.load_snapshot().await {
+            Ok(snapshot) => Arc::new(snapshot),
+            Err(e) => {
+                self.reload_failures.fetch_add(1, Ordering::Relaxed);
+                return Err(e.into());
+            }
+        };
+
+        *self.snapshot.write().unwrap_or_else(|e| e.into_inner()) = Some(snapshot.clone());
+        self.reloads.fetch_add(1, Ordering::Relaxed);
+
+        Ok(snapshot)
+    }
+
+    async fn load_snapshot(&self) -> Result<Snapshot, sqlx::Error> {
+        let signals = self.signal_repo.find_all().await?;
+        let class_signals = self.vessel_class_repo.class_signals().await?;
+        let vessel_classes = self.vessel_class_repo.vessel_classes().await?;
+        let overrides = self.vessel_class_repo.find_all_overrides().await?;
+
+        Ok(Snapshot::build(
+            signals,
+            class_signals,
+            vessel_classes,
+            overrides,
+        ))
+    }
+
+    /// Reloads on every notification, for as long as the service runs.
+    ///
+    /// The listener has its own connection so it never holds one of the request pool's. The
+    /// registry is reloaded each time listening starts, since changes made while no listener
+    /// was subscribed were not notified.
+    async fn listen(&self) {
+        let listener_pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect_lazy_with(self.pool.connect_options().as_ref().clone());
+
+        loop {
+            let mut listener = match PgListener::connect_with(&listener_pool).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    warn!(error = %e, "Failed to connect signal registry listener");
+                    tokio::time::sleep(LISTEN_RETRY).await;
+                    continue;
+                }
+            };
+            if let Err(e) = listener.listen(SIGNAL_REGISTRY_CHANNEL).await {
+                warn!(error = %e, "Failed to listen for signal registry changes");
+                tokio::time::sleep(LISTEN_RETRY).await;
+                continue;
+            }
+
+            self.listening.store(true, Ordering::Relaxed);
+            info!(
+                channel = SIGNAL_REGISTRY_CHANNEL,
+                "Listening for signal registry changes"
+            );
+            self.refresh("listen").await;
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => {
+                        self.notifications.fetch_add(1, Ordering::Relaxed);
+                        debug!(
+                            operation = notification.payload(),
+                            "Signal registry change notified"
+                        );
+                        self.refresh("notify").await;
+                    }
+                    // Reconnected; notifications sent in between are lost
+                    Ok(None) => self.refresh("reconnect").await,
+                    Err(e) => {
+                        warn!(error = %e, "Signal registry listener failed");
+                        break;
+                    }
+                }
+            }
+
+            self.listening.store(false, Ordering::Relaxed);
+            tokio::time::sleep(LISTEN_RETRY).await;
+        }
+    }
+}
@@ -1,100 +1,1161 @@
+use crate::config::IngestionConfig;
 use crate::error::AppError;
+use crate::models::backfill::BackfillChunkResult;
+use crate::models::dead_letter::{DEAD_LETTER_MISSING_VESSEL, DEAD_LETTER_REJECTED, NewDeadLetter};
+use crate::models::metrics::MetricRecord;
 use crate::models::signal::Signal;
-use crate::models::telemetry::{TelemetryRequest, TelemetryResponse};
+use crate::models::telemetry::{
+    ClockDriftResponse, DecodeRejection, FilteredRecord, FrameClaim, INGESTION_BUFFERED,
+    INGESTION_COMMITTED, INGESTION_FAILED, IngestionStatusRecord, MalformedLine, QueuedFrame,
+    RawRecord, StoredCursor, StoredTelemetry, StoredTelemetryQuery, TelemetryBatchResponse,
+    TelemetryFrameResult, TelemetryRequest, TelemetryResponse, TelemetryStreamSummary, WalRecord,
+    WalReplayOutcome,
+};
+use crate::proto::telemetry::TelemetryFrame;
 use crate::repositories::{
-    metrics::MetricsRepository, signal::SignalRepository, telemetry::TelemetryRepository,
-    vessel::VesselRepository,
+    dead_letter::DeadLetterRepository,
+    metrics::MetricsRepository,
+    telemetry::{TelemetryRepository, TelemetryWriter},
 };
-use chrono::{DateTime, Utc};
+use crate::services::signal_registry::{ResolvedSignal, SignalRegistryCache, VesselSignals};
+use crate::services::vessel_lookup::VesselLookupCache;
+use crate::wal::WriteAheadLog;
+use chrono::{DateTime, Duration, Utc};
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
 use rust_decimal::Decimal;
-use serde::Deserialize;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{info, warn};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+/// How often frame ID claims past the dedup window are removed
+const CLAIM_PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
 pub struct TelemetryService {
-    vessel_repo: Arc<VesselRepository>,
-    signal_repo: Arc<SignalRepository>,
+    vessel_lookup: Arc<VesselLookupCache>,
+    signal_registry: Arc<SignalRegistryCache>,
     telemetry_repo: Arc<TelemetryRepository>,
     metrics_repo: Arc<MetricsRepository>,
+    dead_letter_repo: Arc<DeadLetterRepository>,
+    config: IngestionConfig,
+    /// Frames are buffered here when the database is unreachable, if enabled
+    wal: Option<Arc<WriteAheadLog>>,
+}
+
+/// IDs a frame is stored, buffered or dead-lettered under
+struct FrameIds {
+    correlation_id: Uuid,
+    trace_id: String,
+}
+
+impl FrameIds {
+    fn new() -> Self {
+        Self {
+            correlation_id: Uuid::new_v4(),
+            trace_id: Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+/// A frame stored after it was first acknowledged: replayed from the write-ahead log or
+/// retried from dead letters
+pub struct RecoveredFrame<'a> {
+    pub request: &'a TelemetryRequest,
+    pub rejected: &'a [DecodeRejection],
+    pub correlation_id: Uuid,
+    pub trace_id: &'a str,
+    /// Claimed in ingested_frames when the request has no `frameId`
+    pub fallback_frame_id: String,
+}
+
+/// Dead-letter class for a recovered frame that cannot be stored.
+///
+/// Besides what the database refuses, this covers a vessel that was deactivated or deleted and
+/// signals that no longer validate.
+pub fn recovery_failure_class(error: &AppError) -> &str {
+    match error {
+        AppError::Forbidden(_) => DEAD_LETTER_MISSING_VESSEL,
+        _ => error
+            .dead_letter_class()
+            .or(error.reason())
+            .unwrap_or(DEAD_LETTER_REJECTED),
+    }
 }
 
 // Struct to hold validated signal data
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct ValidatedSignals {
-    valid: Vec<(String, DateTime<Utc>, i64, String, Decimal, Uuid, String)>,
-    invalid: Vec<(
-        String,
-        DateTime<Utc>,
-        i64,
-        String,
-        Decimal,
-        String,
-        Uuid,
-        String,
-    )>,
+    valid: Vec<RawRecord>,
+    invalid: Vec<FilteredRecord>,
+}
+
+/// Widest time range a single stored telemetry query may cover
+pub const MAX_QUERY_RANGE_DAYS: i64 = 31;
+
+/// Stored samples fetched per query while streaming them to a client
+const STORED_PAGE_ROWS: i64 = 1_000;
+
+/// Upper bound on frames accepted in a single batch request
+pub const MAX_BATCH_FRAMES: usize = 10_000;
+
+/// Longest client frame ID accepted (ingested_frames.frame_id is VARCHAR(100))
+pub const MAX_FRAME_ID_LEN: usize = 100;
+
+/// Frames buffered by a streaming upload before they are committed to the database
+pub const STREAM_CHUNK_FRAMES: usize = 500;
+
+/// Malformed lines listed individually in a stream summary; the rest are only counted
+pub const MAX_REPORTED_MALFORMED_LINES: usize = 1_000;
+
+/// Result of validating a frame for the async queue
+pub enum PreparedFrame {
+    /// The frame ID was already ingested; answer with the stored response
+    Replayed(TelemetryResponse),
+    /// Ready to enqueue, with the response for the caller
+    Queued(Box<QueuedFrame>, TelemetryResponse),
+    /// The database was unreachable, so the frame went to the write-ahead log instead of the
+    /// queue; the status to report for it and the response for the caller
+    Buffered(Box<IngestionStatusRecord>, TelemetryResponse),
+}
+
+/// State of an in-progress NDJSON upload.
+///
+/// Created by `TelemetryService::open_stream`, fed one line at a time and closed with
+/// `TelemetryService::finish_stream`. Only one chunk of validated frames is held in memory.
+pub struct TelemetryStream {
+    vessel_id: String,
+    trace_id: String,
+    registered_signals: Arc<VesselSignals>,
+    pending: TelemetryWriter,
+    /// The frames buffered in `pending`, in the same order
+    pending_frames: Vec<StreamFrame>,
+    validation_ms: u128,
+    ingestion_ms: u128,
+    started: Instant,
+    summary: TelemetryStreamSummary,
 }
 
-impl TelemetryService {
-    pub fn new(
-        vessel_repo: Arc<VesselRepository>,
-        signal_repo: Arc<SignalRepository>,
-        telemetry_repo: Arc<TelemetryRepository>,
-        metrics_repo: Arc<MetricsRepository>,
-    ) -> Self {
-        Self {
-            vessel_repo,
-            signal_repo,
-            telemetry_repo,
-            metrics_repo,
+/// A frame of an NDJSON upload waiting in the pending chunk
+struct StreamFrame {
+    line_number: usize,
+    /// Kept so the frame can be dead-lettered if the database refuses it
+    request: TelemetryRequest,
+    valid_signals: usize,
+    invalid_signals: usize,
+}
+
+/// What became of a frame written on its own after the database refused its chunk
+enum FrameOutcome {
+    /// Written, or `false` when its frame ID was already ingested
+    Written(bool),
+    /// Refused for its contents and kept as a dead letter; holds the rejection naming it
+    DeadLettered(AppError),
+}
+
+impl TelemetryService {
+    pub fn new(
+        vessel_lookup: Arc<VesselLookupCache>,
+        signal_registry: Arc<SignalRegistryCache>,
+        telemetry_repo: Arc<TelemetryRepository>,
+        metrics_repo: Arc<MetricsRepository>,
+        dead_letter_repo: Arc<DeadLetterRepository>,
+        config: IngestionConfig,
+        wal: Option<Arc<WriteAheadLog>>,
+    ) -> Self {
+        Self {
+            vessel_lookup,
+            signal_registry,
+            telemetry_repo,
+            metrics_repo,
+            dead_letter_repo,
+            config,
+            wal,
+        }
+    }
+
+    pub async fn ingest_telemetry(
+        &self,
+        request: TelemetryRequest,
+    ) -> Result<TelemetryResponse, AppError> {
+        let ids = FrameIds::new();
+        let result = self.ingest_request(&request, &ids).await;
+        self.settle_frame(request, &[], &ids, result).await
+    }
+
+    async fn ingest_request(
+        &self,
+        request: &TelemetryRequest,
+        ids: &FrameIds,
+    ) -> Result<TelemetryResponse, AppError> {
+        let correlation_id = ids.correlation_id;
+        let trace_id = ids.trace_id.clone();
+        let total_start = Instant::now();
+
+        info!(
+            correlation_id = %correlation_id,
+            vessel_id = %request.vessel_id,
+            "Starting telemetry ingestion"
+        );
+
+        // Retries of an already ingested frame are answered from storage
+        if let Some(response) = self.find_replayed_frame(request).await? {
+            return Ok(response);
+        }
+
+        // STEP 1: Record Request Volume
+        self.record_request_volume(&request.vessel_id, correlation_id, trace_id.clone())
+            .await?;
+
+        // STEP 2: VALIDATION LAYER
+        let validation_start = Instant::now();
+        let epoch_utc = self.validate_timestamps(request)?;
+        let registered_signals = self
+            .validate_vessel_and_load_signals(&request.vessel_id)
+            .await?;
+        let validation_duration = validation_start.elapsed().as_millis();
+
+        info!(
+            validation_duration_ms = validation_duration,
+            "Validation layer completed"
+        );
+
+        // STEP 3: SIGNAL VALIDATION (Part of Validation Layer)
+        let validated = self.validate_all_signals(
+            request,
+            epoch_utc,
+            &registered_signals,
+            correlation_id,
+            trace_id.clone(),
+        );
+
+        info!(
+            valid_count = validated.valid.len(),
+            invalid_count = validated.invalid.len(),
+            "Signal validation completed"
+        );
+        self.record_validation_latency(
+            &request.vessel_id,
+            validation_duration,
+            correlation_id,
+            trace_id.clone(),
+        )
+        .await?;
+
+        let response = TelemetryResponse {
+            message: "Telemetry ingested successfully".to_string(),
+            correlation_id,
+            valid_signals: validated.valid.len(),
+            invalid_signals: validated.invalid.len(),
+        };
+
+        // STEP 4: INGESTION LAYER
+        let ingestion_start = Instant::now();
+        let claim = self.frame_claim(request, &response)?;
+        if !self.ingest_to_database(&validated, claim.as_ref()).await? {
+            return self.replayed_after_race(request).await;
+        }
+        let ingestion_duration = ingestion_start.elapsed().as_millis();
+
+        info!(
+            ingestion_duration_ms = ingestion_duration,
+            "Ingestion layer completed"
+        );
+
+        // The frame is stored from here on; a metrics failure must not make the client resend it
+        self.record_ingestion_latency(
+            &request.vessel_id,
+            ingestion_duration,
+            correlation_id,
+            trace_id.clone(),
+        )
+        .await
+        .unwrap_or_else(|e| warn!(error = %e, "Failed to record ingestion latency"));
+
+        // STEP 5: Record Total Duration
+        let total_duration = total_start.elapsed().as_millis();
+        self.record_total_latency(
+            &request.vessel_id,
+            total_duration,
+            correlation_id,
+            trace_id.clone(),
+        )
+        .await
+        .unwrap_or_else(|e| warn!(error = %e, "Failed to record total latency"));
+
+        info!(
+            total_duration_ms = total_duration,
+            "Telemetry ingestion completed successfully"
+        );
+
+        Ok(response)
+    }
+
+    /// Validates a frame for the async queue without writing anything.
+    ///
+    /// Runs the same checks as `ingest_telemetry`; storage and metrics are left to
+    /// `commit_queued_frames`. When the checks cannot reach the database, the frame is buffered
+    /// in the write-ahead log like a synchronous request.
+    pub async fn prepare_queued_frame(
+        &self,
+        request: TelemetryRequest,
+    ) -> Result<PreparedFrame, AppError> {
+        let received = Instant::now();
+
+        let replayed = match self.find_replayed_frame(&request).await {
+            Ok(replayed) => replayed,
+            Err(e) => return self.buffer_unqueued_frame(request, e).await,
+        };
+        if let Some(response) = replayed {
+            return Ok(PreparedFrame::Replayed(response));
+        }
+
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Uuid::new_v4().to_string();
+
+        let epoch_utc = self.validate_timestamps(&request)?;
+        let registered_signals = match self
+            .validate_vessel_and_load_signals(&request.vessel_id)
+            .await
+        {
+            Ok(registered_signals) => registered_signals,
+            Err(e) => return self.buffer_unqueued_frame(request, e).await,
+        };
+        let validated = self.validate_all_signals(
+            &request,
+            epoch_utc,
+            &registered_signals,
+            correlation_id,
+            trace_id.clone(),
+        );
+
+        // Retries after the commit are answered with the response a synchronous request gets
+        let mut response = TelemetryResponse {
+            message: "Telemetry ingested successfully".to_string(),
+            correlation_id,
+            valid_signals: validated.valid.len(),
+            invalid_signals: validated.invalid.len(),
+        };
+        let claim = self.frame_claim(&request, &response)?;
+        response.message = "Telemetry accepted for ingestion".to_string();
+
+        let frame = QueuedFrame {
+            vessel_id: request.vessel_id.clone(),
+            frame_id: request.frame_id.clone(),
+            correlation_id,
+            trace_id,
+            raw: validated.valid,
+            filtered: validated.invalid,
+            claim,
+            accepted_at: Utc::now(),
+            received,
+            validation_ms: received.elapsed().as_millis(),
+            request,
+        };
+
+        Ok(PreparedFrame::Queued(Box::new(frame), response))
+    }
+
+    /// Buffers a frame whose checks hit a database outage, like `settle_frame`; any other error is
+    /// returned as is
+    async fn buffer_unqueued_frame(
+        &self,
+        request: TelemetryRequest,
+        error: AppError,
+    ) -> Result<PreparedFrame, AppError> {
+        if self.wal.is_none() || !error.is_database_outage() {
+            return Err(error);
+        }
+        warn!(
+            vessel_id = %request.vessel_id,
+            error = %error,
+            "Database unavailable, buffering frame in the write-ahead log instead of the queue"
+        );
+
+        let vessel_id = request.vessel_id.clone();
+        let frame_id = request.frame_id.clone();
+        let accepted_at = Utc::now();
+        let response = self.buffer_frame(request, &[], &FrameIds::new()).await?;
+
+        let status = IngestionStatusRecord {
+            correlation_id: response.correlation_id,
+            vessel_id,
+            frame_id,
+            status: INGESTION_BUFFERED.to_string(),
+            valid_signals: 0,
+            invalid_signals: 0,
+            error: None,
+            accepted_at,
+            completed_at: Some(Utc::now()),
+        };
+        Ok(PreparedFrame::Buffered(Box::new(status), response))
+    }
+
+    /// Whether frames are buffered in a write-ahead log while the database is unreachable
+    pub fn has_write_ahead_log(&self) -> bool {
+        self.wal.is_some()
+    }
+
+    /// Appends a queued frame the database could not take to the write-ahead log, under the
+    /// correlation_id it was accepted with
+    pub async fn buffer_queued_frame(&self, frame: &QueuedFrame) -> Result<(), AppError> {
+        let ids = FrameIds {
+            correlation_id: frame.correlation_id,
+            trace_id: frame.trace_id.clone(),
+        };
+        self.buffer_frame(frame.request.clone(), &[], &ids).await?;
+
+        Ok(())
+    }
+
+    /// Writes queued frames in one transaction, then their metrics in one statement.
+    ///
+    /// Returns the status recorded for each frame. Metric failures are logged, not returned,
+    /// since the frames are already committed.
+    pub async fn commit_queued_frames(
+        &self,
+        frames: &[QueuedFrame],
+    ) -> Result<Vec<IngestionStatusRecord>, AppError> {
+        let ingestion_start = Instant::now();
+        let statuses = self
+            .telemetry_repo
+            .insert_queued_frames(frames, self.config.conflict_policy)
+            .await?;
+        let ingestion_ms = ingestion_start.elapsed().as_millis();
+
+        let metric = |frame: &QueuedFrame, metric_type: &str, value: u128| MetricRecord {
+            vessel_id: Some(frame.vessel_id.clone()),
+            metric_type: metric_type.to_string(),
+            metric_value: Decimal::from(value),
+            correlation_id: frame.correlation_id,
+            trace_id: frame.trace_id.clone(),
+        };
+        let metrics: Vec<MetricRecord> = frames
+            .iter()
+            .zip(&statuses)
+            .filter(|(_, status)| status.status == INGESTION_COMMITTED)
+            .flat_map(|(frame, _)| {
+                [
+                    metric(frame, "request_volume", 1),
+                    metric(frame, "latency_validation", frame.validation_ms),
+                    metric(frame, "latency_ingestion", ingestion_ms),
+                    metric(frame, "latency_total", frame.received.elapsed().as_millis()),
+                ]
+            })
+            .collect();
+        if let Err(e) = self.metrics_repo.insert_metrics(&metrics).await {
+            warn!(error = %e, "Failed to record async ingestion metrics");
+        }
+
+        info!(
+            frames = frames.len(),
+            ingestion_duration_ms = ingestion_ms,
+            "Queued telemetry batch committed"
+        );
+
+        Ok(statuses)
+    }
+
+    /// Records queued frames that could not be written
+    pub async fn record_failed_frames(
+        &self,
+        frames: &[QueuedFrame],
+        error: &AppError,
+    ) -> Result<Vec<IngestionStatusRecord>, AppError> {
+        let statuses: Vec<IngestionStatusRecord> = frames
+            .iter()
+            .map(|frame| {
+                IngestionStatusRecord::for_frame(frame, INGESTION_FAILED, Some(error.to_string()))
+            })
+            .collect();
+        self.telemetry_repo
+            .insert_ingestion_statuses(&statuses)
+            .await?;
+
+        Ok(statuses)
+    }
+
+    pub async fn find_ingestion_status(
+        &self,
+        correlation_id: Uuid,
+    ) -> Result<Option<IngestionStatusRecord>, AppError> {
+        Ok(self
+            .telemetry_repo
+            .find_ingestion_status(correlation_id)
+            .await?)
+    }
+
+    pub async fn purge_ingestion_statuses(&self, retention_secs: u64) -> Result<u64, AppError> {
+        Ok(self
+            .telemetry_repo
+            .purge_ingestion_statuses(retention_secs as f64)
+            .await?)
+    }
+
+    /// Starts removing frame ID claims past the dedup window every hour; call once at startup.
+    ///
+    /// Expired claims are otherwise only replaced when the same frame ID comes back, and WAL
+    /// replays and dead-letter retries add fallback claims of their own.
+    pub fn spawn_claim_purge(self: &Arc<Self>) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(CLAIM_PURGE_INTERVAL);
+
+            loop {
+                ticker.tick().await;
+                match service
+                    .telemetry_repo
+                    .purge_ingested_frames(service.config.dedup_window_secs)
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(purged) => info!(purged, "Purged expired frame ID claims"),
+                    Err(e) => warn!(error = %e, "Failed to purge expired frame ID claims"),
+                }
+            }
+        });
+    }
+
+    /// Ingests a store-and-forward backlog for one vessel in a single call.
+    ///
+    /// The vessel and signal registry are loaded once for the whole batch. Each frame is then
+    /// validated and written on its own, so a failing frame is reported without rejecting the rest.
+    pub async fn ingest_telemetry_batch(
+        &self,
+        vessel_id: &str,
+        frames: Vec<TelemetryRequest>,
+    ) -> Result<TelemetryBatchResponse, AppError> {
+        if frames.is_empty() {
+            return Err(AppError::Validation(
+                "Batch must contain at least one frame".to_string(),
+            ));
+        }
+        if frames.len() > MAX_BATCH_FRAMES {
+            return Err(AppError::Validation(format!(
+                "Batch contains {} frames, maximum is {}",
+                frames.len(),
+                MAX_BATCH_FRAMES
+            )));
+        }
+
+        info!(
+            vessel_id = %vessel_id,
+            frame_count = frames.len(),
+            "Starting batch telemetry ingestion"
+        );
+
+        // Without the registry every frame goes to the write-ahead log, when there is one
+        let registered_signals = match self.validate_vessel_and_load_signals(vessel_id).await {
+            Ok(registered_signals) => Some(registered_signals),
+            Err(e) if self.wal.is_some() && e.is_database_outage() => None,
+            Err(e) => return Err(e),
+        };
+
+        let mut results = Vec::with_capacity(frames.len());
+        for (index, frame) in frames.into_iter().enumerate() {
+            if frame.vessel_id != vessel_id {
+                warn!(index, payload_vessel = %frame.vessel_id, "Batch frame vessel mismatch");
+                results.push(TelemetryFrameResult::rejected(
+                    index,
+                    format!(
+                        "Vessel ID mismatch: authenticated as '{}' but frame contains '{}'",
+                        vessel_id, frame.vessel_id
+                    ),
+                ));
+                continue;
+            }
+
+            let ids = FrameIds::new();
+            let result = match &registered_signals {
+                Some(registered_signals) => {
+                    let result = self
+                        .ingest_frame(&frame, registered_signals, &[], &ids)
+                        .await;
+                    self.settle_frame(frame, &[], &ids, result).await
+                }
+                None => self.buffer_frame(frame, &[], &ids).await,
+            };
+            match result {
+                Ok(response) => results.push(TelemetryFrameResult::accepted(index, &response)),
+                Err(e) => {
+                    warn!(index, error = %e, "Batch frame ingestion failed");
+                    results.push(TelemetryFrameResult::failed(index, &e));
+                }
+            }
+        }
+
+        let accepted_frames = results.iter().filter(|r| r.accepted).count();
+        let rejected_frames = results.len() - accepted_frames;
+
+        info!(
+            accepted_frames,
+            rejected_frames, "Batch telemetry ingestion completed"
+        );
+
+        Ok(TelemetryBatchResponse {
+            message: "Telemetry batch processed".to_string(),
+            accepted_frames,
+            rejected_frames,
+            results,
+        })
+    }
+
+    /// Ingests a protobuf frame whose samples reference signals by `signal_id`.
+    ///
+    /// IDs are resolved against the signal registry before the usual validation. An unknown ID
+    /// is kept under the placeholder name `signal_id:<id>`, so it lands in telemetry_filtered as
+    /// `unregistered_signal` exactly like an unknown name would.
+    pub async fn ingest_telemetry_proto(
+        &self,
+        frame: TelemetryFrame,
+    ) -> Result<TelemetryResponse, AppError> {
+        let timestamp_utc =
+            DateTime::from_timestamp_millis(frame.timestamp_utc_ms).ok_or_else(|| {
+                AppError::Validation(format!(
+                    "timestamp_utc_ms {} is out of range",
+                    frame.timestamp_utc_ms
+                ))
+            })?;
+
+        info!(
+            vessel_id = %frame.vessel_id,
+            sample_count = frame.samples.len(),
+            "Starting protobuf telemetry ingestion"
+        );
+
+        // signal_ids resolve against the in-memory registry, so the frame can still be buffered
+        // if the vessel lookup below fails
+        let known_signals = self
+            .signal_registry
+            .vessel_signals(&frame.vessel_id)
+            .await?;
+        let names_by_id: HashMap<i32, &str> = known_signals
+            .registry()
+            .values()
+            .map(|s| (s.signal_id, s.signal_name.as_str()))
+            .collect();
+
+        let signals = frame
+            .samples
+            .iter()
+            .map(|sample| {
+                let name = i32::try_from(sample.signal_id)
+                    .ok()
+                    .and_then(|id| names_by_id.get(&id))
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| {
+                        warn!(
+                            signal_id = sample.signal_id,
+                            "Unknown signal_id in protobuf frame"
+                        );
+                        format!("signal_id:{}", sample.signal_id)
+                    });
+                (name, serde_json::Value::from(sample.value))
+            })
+            .collect();
+
+        let request = TelemetryRequest {
+            vessel_id: frame.vessel_id,
+            timestamp_utc,
+            epoch_utc: frame.epoch_utc.to_string(),
+            signals,
+            frame_id: (!frame.frame_id.is_empty()).then_some(frame.frame_id),
+        };
+
+        let ids = FrameIds::new();
+        let result = match self
+            .validate_vessel_and_load_signals(&request.vessel_id)
+            .await
+        {
+            Ok(registered_signals) => {
+                self.ingest_frame(&request, &registered_signals, &[], &ids)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        self.settle_frame(request, &[], &ids, result).await
+    }
+
+    /// Ingests a frame decoded from a wire protocol.
+    ///
+    /// Messages that failed to decode are written to telemetry_filtered under the frame's
+    /// correlation_id, so they are traceable alongside the signals that did decode.
+    pub async fn ingest_decoded_frame(
+        &self,
+        request: TelemetryRequest,
+        rejected: &[DecodeRejection],
+    ) -> Result<TelemetryResponse, AppError> {
+        let ids = FrameIds::new();
+        let result = match self
+            .validate_vessel_and_load_signals(&request.vessel_id)
+            .await
+        {
+            Ok(registered_signals) => {
+                self.ingest_frame(&request, &registered_signals, rejected, &ids)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        self.settle_frame(request, rejected, &ids, result).await
+    }
+
+    /// Validates and stores historical rows without recording request or latency metrics.
+    ///
+    /// Rows get the same checks as live frames except the maximum age, since backfilled data is
+    /// old by definition. Accepted rows are written in one transaction under `correlation_id`.
+    /// When the database refuses that transaction because of a row's contents, the rows are
+    /// written one at a time and the refused ones are kept as dead letters and reported as
+    /// rejected.
+    pub async fn ingest_backfill_chunk(
+        &self,
+        vessel_id: &str,
+        rows: &[TelemetryRequest],
+        correlation_id: Uuid,
+        trace_id: &str,
+    ) -> Result<BackfillChunkResult, AppError> {
+        let registered_signals = self.validate_vessel_and_load_signals(vessel_id).await?;
+
+        let mut result = BackfillChunkResult::default();
+        let mut writer = self
+            .telemetry_repo
+            .writer(self.config.conflict_policy, rows.len());
+        // Position and (valid, invalid) signal counts of each row handed to the writer
+        let mut pending = Vec::with_capacity(rows.len());
+        for (index, row) in rows.iter().enumerate() {
+            let epoch_utc = match self.check_timestamps(row, None) {
+                Ok(epoch_utc) => epoch_utc,
+                Err(e) => {
+                    result.rejected.push((index, e));
+                    continue;
+                }
+            };
+
+            let row_signals = self.validate_all_signals(
+                row,
+                epoch_utc,
+                &registered_signals,
+                correlation_id,
+                trace_id.to_string(),
+            );
+            pending.push((index, row_signals.valid.len(), row_signals.invalid.len()));
+            writer.push(row_signals.valid, row_signals.invalid, None);
+        }
+
+        if let Err(e) = writer.flush().await {
+            let e = AppError::from(e);
+            if e.dead_letter_class().is_none() {
+                return Err(e);
+            }
+            warn!(
+                vessel_id = %vessel_id,
+                rows = writer.len(),
+                error = %e,
+                "Backfill chunk refused, writing rows one at a time"
+            );
+
+            let mut written = Vec::with_capacity(pending.len());
+            for (index, valid_signals, invalid_signals) in pending {
+                match self
+                    .write_next_frame(&mut writer, &rows[index], correlation_id, trace_id)
+                    .await?
+                {
+                    FrameOutcome::Written(_) => {
+                        written.push((index, valid_signals, invalid_signals))
+                    }
+                    FrameOutcome::DeadLettered(rejection) => {
+                        result.rejected.push((index, rejection))
+                    }
+                }
+            }
+            pending = written;
+        }
+
+        for (_, valid_signals, invalid_signals) in pending {
+            result.rows_accepted += 1;
+            result.valid_signals += valid_signals;
+            result.invalid_signals += invalid_signals;
+        }
+
+        Ok(result)
+    }
+
+    /// Starts an NDJSON upload for an authenticated vessel.
+    ///
+    /// The vessel and signal registry are checked once; every frame in the stream shares the
+    /// returned correlation_id.
+    pub async fn open_stream(&self, vessel_id: &str) -> Result<TelemetryStream, AppError> {
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Uuid::new_v4().to_string();
+
+        info!(
+            correlation_id = %correlation_id,
+            vessel_id = %vessel_id,
+            "Starting streaming telemetry ingestion"
+        );
+
+        self.record_request_volume(vessel_id, correlation_id, trace_id.clone())
+            .await?;
+
+        let validation_start = Instant::now();
+        let registered_signals = self.validate_vessel_and_load_signals(vessel_id).await?;
+
+        Ok(TelemetryStream {
+            vessel_id: vessel_id.to_string(),
+            trace_id,
+            registered_signals,
+            pending: self
+                .telemetry_repo
+                .writer(self.config.conflict_policy, STREAM_CHUNK_FRAMES),
+            pending_frames: Vec::with_capacity(STREAM_CHUNK_FRAMES),
+            validation_ms: validation_start.elapsed().as_millis(),
+            ingestion_ms: 0,
+            started: Instant::now(),
+            summary: TelemetryStreamSummary {
+                correlation_id,
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Parses and validates one NDJSON line, committing the pending chunk once it is full.
+    ///
+    /// Malformed lines are recorded in the summary and never abort the stream; only a
+    /// database failure is returned as an error.
+    pub async fn push_stream_line(
+        &self,
+        stream: &mut TelemetryStream,
+        line_number: usize,
+        line: &[u8],
+    ) -> Result<(), AppError> {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(());
+        }
+
+        stream.summary.frames_received += 1;
+
+        let request = match serde_json::from_slice::<TelemetryRequest>(line) {
+            Ok(request) => request,
+            Err(e) => {
+                Self::record_malformed_line(stream, line_number, e.to_string(), None);
+                return Ok(());
+            }
+        };
+
+        if request.vessel_id != stream.vessel_id {
+            let error = format!(
+                "Vessel ID mismatch: authenticated as '{}' but frame contains '{}'",
+                stream.vessel_id, request.vessel_id
+            );
+            Self::record_malformed_line(stream, line_number, error, None);
+            return Ok(());
+        }
+
+        let epoch_utc = match self.validate_timestamps(&request) {
+            Ok(epoch_utc) => epoch_utc,
+            Err(e) => {
+                let reason = e.reason().map(str::to_string);
+                Self::record_malformed_line(stream, line_number, e.to_string(), reason);
+                return Ok(());
+            }
+        };
+
+        let validation_start = Instant::now();
+        let validated = self.validate_all_signals(
+            &request,
+            epoch_utc,
+            &stream.registered_signals,
+            stream.summary.correlation_id,
+            stream.trace_id.clone(),
+        );
+        stream.validation_ms += validation_start.elapsed().as_millis();
+
+        // A client resuming after a failed upload may resend frames that were already stored
+        let response = TelemetryResponse {
+            message: "Telemetry ingested successfully".to_string(),
+            correlation_id: stream.summary.correlation_id,
+            valid_signals: validated.valid.len(),
+            invalid_signals: validated.invalid.len(),
+        };
+        let claim = match self.frame_claim(&request, &response) {
+            Ok(claim) => claim,
+            Err(e) => {
+                Self::record_malformed_line(stream, line_number, e.to_string(), None);
+                return Ok(());
+            }
+        };
+
+        stream
+            .pending
+            .push(validated.valid, validated.invalid, claim);
+        stream.pending_frames.push(StreamFrame {
+            line_number,
+            request,
+            valid_signals: response.valid_signals,
+            invalid_signals: response.invalid_signals,
+        });
+
+        if stream.pending.is_full() {
+            self.flush_stream(stream).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Marks a line as malformed without reading it past the point of failure
+    pub fn reject_stream_line(stream: &mut TelemetryStream, line_number: usize, error: String) {
+        stream.summary.frames_received += 1;
+        Self::record_malformed_line(stream, line_number, error, None);
+    }
+
+    /// Commits whatever is still pending and produces the final summary.
+    ///
+    /// `error` carries a failure that stopped the upload early (a read error or a failed
+    /// chunk commit); frames up to `last_committed_line` remain stored either way.
+    pub async fn finish_stream(
+        &self,
+        mut stream: TelemetryStream,
+        mut error: Option<String>,
+    ) -> TelemetryStreamSummary {
+        if error.is_none()
+            && let Err(e) = self.flush_stream(&mut stream).await
+        {
+            error = Some(e.to_string());
+        }
+
+        let correlation_id = stream.summary.correlation_id;
+        let vessel_id = stream.vessel_id.as_str();
+
+        let metrics = async {
+            self.record_validation_latency(
+                vessel_id,
+                stream.validation_ms,
+                correlation_id,
+                stream.trace_id.clone(),
+            )
+            .await?;
+            self.record_ingestion_latency(
+                vessel_id,
+                stream.ingestion_ms,
+                correlation_id,
+                stream.trace_id.clone(),
+            )
+            .await?;
+            self.record_total_latency(
+                vessel_id,
+                stream.started.elapsed().as_millis(),
+                correlation_id,
+                stream.trace_id.clone(),
+            )
+            .await
+        };
+        if let Err(e) = metrics.await {
+            warn!(error = %e, "Failed to record streaming ingestion metrics");
+        }
+
+        info!(
+            correlation_id = %correlation_id,
+            frames_received = stream.summary.frames_received,
+            frames_committed = stream.summary.frames_committed,
+            malformed_frames = stream.summary.malformed_frames,
+            "Streaming telemetry ingestion finished"
+        );
+
+        let mut summary = stream.summary;
+        summary.message = match error {
+            Some(_) => "Telemetry stream stopped early".to_string(),
+            None => "Telemetry stream ingested successfully".to_string(),
+        };
+        summary.error = error;
+        summary
+    }
+
+    /// Writes the pending chunk and advances the committed counters.
+    ///
+    /// When the database refuses the chunk because of a frame's contents, the frames are written
+    /// one at a time instead and the refused ones are kept as dead letters and reported as
+    /// malformed lines.
+    async fn flush_stream(&self, stream: &mut TelemetryStream) -> Result<(), AppError> {
+        if stream.pending.is_empty() {
+            return Ok(());
+        }
+
+        let ingestion_start = Instant::now();
+        let written = match stream.pending.flush().await {
+            Ok(written) => written,
+            Err(e) => {
+                let e = AppError::from(e);
+                if e.dead_letter_class().is_none() {
+                    return Err(e);
+                }
+                warn!(
+                    frames = stream.pending.len(),
+                    error = %e,
+                    "Stream chunk refused, writing frames one at a time"
+                );
+                return self.flush_stream_frames(stream).await;
+            }
+        };
+        stream.ingestion_ms += ingestion_start.elapsed().as_millis();
+
+        for (frame, written) in std::mem::take(&mut stream.pending_frames)
+            .into_iter()
+            .zip(written)
+        {
+            Self::record_committed_frame(stream, &frame, written);
+        }
+
+        info!(
+            frames_committed = stream.summary.frames_committed,
+            last_committed_line = stream.summary.last_committed_line,
+            "Stream chunk committed"
+        );
+
+        Ok(())
+    }
+
+    /// Writes the pending chunk frame by frame, stopping at the first failure that is not about
+    /// the frame's contents
+    async fn flush_stream_frames(&self, stream: &mut TelemetryStream) -> Result<(), AppError> {
+        let ingestion_start = Instant::now();
+        let mut frames = std::mem::take(&mut stream.pending_frames).into_iter();
+        while let Some(frame) = frames.next() {
+            let outcome = self
+                .write_next_frame(
+                    &mut stream.pending,
+                    &frame.request,
+                    stream.summary.correlation_id,
+                    &stream.trace_id,
+                )
+                .await;
+            match outcome {
+                Ok(FrameOutcome::Written(written)) => {
+                    Self::record_committed_frame(stream, &frame, written)
+                }
+                Ok(FrameOutcome::DeadLettered(rejection)) => {
+                    let reason = rejection.reason().map(str::to_string);
+                    Self::record_malformed_line(
+                        stream,
+                        frame.line_number,
+                        rejection.to_string(),
+                        reason,
+                    );
+                    stream.summary.last_committed_line = frame.line_number;
+                }
+                Err(e) => {
+                    // Keeps the unwritten frames lined up with what is left in the writer
+                    stream.pending_frames = frames.collect();
+                    stream.ingestion_ms += ingestion_start.elapsed().as_millis();
+                    return Err(e);
+                }
+            }
+        }
+        stream.ingestion_ms += ingestion_start.elapsed().as_millis();
+
+        info!(
+            frames_committed = stream.summary.frames_committed,
+            last_committed_line = stream.summary.last_committed_line,
+            "Stream chunk committed frame by frame"
+        );
+
+        Ok(())
+    }
+
+    fn record_committed_frame(stream: &mut TelemetryStream, frame: &StreamFrame, written: bool) {
+        if written {
+            stream.summary.frames_committed += 1;
+            stream.summary.valid_signals += frame.valid_signals;
+            stream.summary.invalid_signals += frame.invalid_signals;
+        } else {
+            stream.summary.duplicate_frames += 1;
+        }
+        stream.summary.last_committed_line = frame.line_number;
+    }
+
+    /// Writes the oldest frame buffered in `writer` on its own, for a chunk the database refused.
+    ///
+    /// Any failure other than the database refusing the frame's contents, including one to store
+    /// the dead letter, is returned as the error.
+    async fn write_next_frame(
+        &self,
+        writer: &mut TelemetryWriter,
+        request: &TelemetryRequest,
+        correlation_id: Uuid,
+        trace_id: &str,
+    ) -> Result<FrameOutcome, AppError> {
+        let error = match writer.flush_next().await {
+            Some(Ok(written)) => return Ok(FrameOutcome::Written(written)),
+            Some(Err(e)) => AppError::from(e),
+            None => return Err(AppError::Internal("No frame left to write".to_string())),
+        };
+        if error.dead_letter_class().is_none() {
+            return Err(error);
+        }
+
+        match self
+            .keep_refused_frame(request, &[], correlation_id, trace_id, error)
+            .await
+        {
+            rejection @ AppError::Rejected { .. } => Ok(FrameOutcome::DeadLettered(rejection)),
+            e => Err(e),
         }
     }
 
-    pub async fn ingest_telemetry(
+    fn record_malformed_line(
+        stream: &mut TelemetryStream,
+        line_number: usize,
+        error: String,
+        reason: Option<String>,
+    ) {
+        warn!(line = line_number, error = %error, "Malformed telemetry line");
+        stream.summary.malformed_frames += 1;
+        if stream.summary.malformed_lines.len() < MAX_REPORTED_MALFORMED_LINES {
+            stream.summary.malformed_lines.push(MalformedLine {
+                line: line_number,
+                error,
+                reason,
+            });
+        }
+    }
+
+    /// Validates and stores one frame against an already loaded signal registry
+    async fn ingest_frame(
         &self,
-        request: TelemetryRequest,
+        request: &TelemetryRequest,
+        registered_signals: &VesselSignals,
+        rejected: &[DecodeRejection],
+        ids: &FrameIds,
     ) -> Result<TelemetryResponse, AppError> {
-        let correlation_id = Uuid::new_v4();
-        let trace_id = Uuid::new_v4().to_string();
-        let total_start = Instant::now();
+        if let Some(response) = self.find_replayed_frame(request).await? {
+            return Ok(response);
+        }
 
-        info!(
-            correlation_id = %correlation_id,
-            vessel_id = %request.vessel_id,
-            "Starting telemetry ingestion"
-        );
+        let correlation_id = ids.correlation_id;
+        let trace_id = ids.trace_id.clone();
+        let total_start = Instant::now();
 
-        // STEP 1: Record Request Volume
         self.record_request_volume(&request.vessel_id, correlation_id, trace_id.clone())
             .await?;
 
-        // STEP 2: VALIDATION LAYER
         let validation_start = Instant::now();
-        let registered_signals = self
-            .validate_vessel_and_load_signals(&request.vessel_id)
-            .await?;
-        let validation_duration = validation_start.elapsed().as_millis();
-
-        info!(
-            validation_duration_ms = validation_duration,
-            "Validation layer completed"
-        );
-
-        // STEP 3: SIGNAL VALIDATION (Part of Validation Layer)
-        let validated = self.validate_all_signals(
-            &request,
-            &registered_signals,
+        let epoch_utc = self.validate_timestamps(request)?;
+        let mut validated = self.validate_all_signals(
+            request,
+            epoch_utc,
+            registered_signals,
             correlation_id,
             trace_id.clone(),
         );
-
-        info!(
-            valid_count = validated.valid.len(),
-            invalid_count = validated.invalid.len(),
-            "Signal validation completed"
+        Self::add_decode_rejections(
+            &mut validated,
+            request,
+            epoch_utc,
+            rejected,
+            correlation_id,
+            &trace_id,
         );
+        let validation_duration = validation_start.elapsed().as_millis();
         self.record_validation_latency(
             &request.vessel_id,
             validation_duration,
@@ -103,45 +1164,407 @@ impl TelemetryService {
         )
         .await?;
 
-        // STEP 4: INGESTION LAYER
+        let response = TelemetryResponse {
+            message: "Telemetry ingested successfully".to_string(),
+            correlation_id,
+            valid_signals: validated.valid.len(),
+            invalid_signals: validated.invalid.len(),
+        };
+
         let ingestion_start = Instant::now();
-        self.ingest_to_database(&validated).await?;
+        let claim = self.frame_claim(request, &response)?;
+        if !self.ingest_to_database(&validated, claim.as_ref()).await? {
+            return self.replayed_after_race(request).await;
+        }
         let ingestion_duration = ingestion_start.elapsed().as_millis();
-
-        info!(
-            ingestion_duration_ms = ingestion_duration,
-            "Ingestion layer completed"
-        );
-
         self.record_ingestion_latency(
             &request.vessel_id,
             ingestion_duration,
             correlation_id,
             trace_id.clone(),
         )
-        .await?;
+        .await
+        .unwrap_or_else(|e| warn!(error = %e, "Failed to record ingestion latency"));
 
-        // STEP 5: Record Total Duration
         let total_duration = total_start.elapsed().as_millis();
-        self.record_total_latency(
-            &request.vessel_id,
-            total_duration,
-            correlation_id,
-            trace_id.clone(),
+        self.record_total_latency(&request.vessel_id, total_duration, correlation_id, trace_id)
+            .await
+            .unwrap_or_else(|e| warn!(error = %e, "Failed to record total latency"));
+
+        Ok(response)
+    }
+
+    /// Stores decode failures in telemetry_filtered under the frame's correlation_id
+    fn add_decode_rejections(
+        validated: &mut ValidatedSignals,
+        request: &TelemetryRequest,
+        epoch_utc: i64,
+        rejected: &[DecodeRejection],
+        correlation_id: Uuid,
+        trace_id: &str,
+    ) {
+        validated.invalid.extend(rejected.iter().map(|rejection| {
+            (
+                request.vessel_id.clone(),
+                request.timestamp_utc,
+                epoch_utc,
+                rejection.label.clone(),
+                Decimal::ZERO,
+                rejection.reason.clone(),
+                correlation_id,
+                trace_id.to_string(),
+            )
+        }));
+    }
+
+    /// Returns the stored response when the frame's client ID was already ingested inside the
+    /// dedup window
+    async fn find_replayed_frame(
+        &self,
+        request: &TelemetryRequest,
+    ) -> Result<Option<TelemetryResponse>, AppError> {
+        let Some(frame_id) = request.frame_id.as_deref() else {
+            return Ok(None);
+        };
+
+        let stored = self
+            .telemetry_repo
+            .find_ingested_frame(&request.vessel_id, frame_id, self.config.dedup_window_secs)
+            .await?;
+
+        match stored {
+            Some(response) => {
+                info!(
+                    vessel_id = %request.vessel_id,
+                    frame_id = %frame_id,
+                    "Replayed frame, returning original response"
+                );
+                serde_json::from_value(response).map(Some).map_err(|e| {
+                    AppError::Internal(format!(
+                        "Stored response for frame {} is corrupt: {}",
+                        frame_id, e
+                    ))
+                })
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// A concurrent request committed the same frame ID first; answer with its response
+    async fn replayed_after_race(
+        &self,
+        request: &TelemetryRequest,
+    ) -> Result<TelemetryResponse, AppError> {
+        self.find_replayed_frame(request).await?.ok_or_else(|| {
+            AppError::Conflict(format!(
+                "Frame {} is already being ingested",
+                request.frame_id.as_deref().unwrap_or_default()
+            ))
+        })
+    }
+
+    fn frame_claim(
+        &self,
+        request: &TelemetryRequest,
+        response: &TelemetryResponse,
+    ) -> Result<Option<FrameClaim>, AppError> {
+        request
+            .frame_id
+            .as_deref()
+            .map(|frame_id| self.claim_for(&request.vessel_id, frame_id, response))
+            .transpose()
+    }
+
+    fn claim_for(
+        &self,
+        vessel_id: &str,
+        frame_id: &str,
+        response: &TelemetryResponse,
+    ) -> Result<FrameClaim, AppError> {
+        Self::check_frame_id(frame_id)?;
+
+        let stored_response = serde_json::to_value(response)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize response: {}", e)))?;
+
+        Ok(FrameClaim {
+            vessel_id: vessel_id.to_string(),
+            frame_id: frame_id.to_string(),
+            correlation_id: response.correlation_id,
+            response: stored_response,
+            window_secs: self.config.dedup_window_secs,
+        })
+    }
+
+    fn check_frame_id(frame_id: &str) -> Result<(), AppError> {
+        if frame_id.is_empty() || frame_id.len() > MAX_FRAME_ID_LEN {
+            return Err(AppError::Validation(format!(
+                "frameId must be 1 to {} characters",
+                MAX_FRAME_ID_LEN
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Handles a failed ingestion: the frame is buffered in the write-ahead log when the database
+    /// is unreachable, or kept as a dead letter when the database refused to store it. Any other
+    /// result is returned as is.
+    async fn settle_frame(
+        &self,
+        request: TelemetryRequest,
+        rejected: &[DecodeRejection],
+        ids: &FrameIds,
+        result: Result<TelemetryResponse, AppError>,
+    ) -> Result<TelemetryResponse, AppError> {
+        let error = match result {
+            Err(e) if self.wal.is_some() && e.is_database_outage() => {
+                warn!(
+                    vessel_id = %request.vessel_id,
+                    error = %e,
+                    "Database unavailable, buffering frame in the write-ahead log"
+                );
+                return self.buffer_frame(request, rejected, ids).await;
+            }
+            Err(e) => e,
+            result => return result,
+        };
+
+        Err(self
+            .keep_refused_frame(&request, rejected, ids.correlation_id, &ids.trace_id, error)
+            .await)
+    }
+
+    /// Keeps a queued frame the database refused as a dead letter, like a synchronous request
+    pub async fn dead_letter_queued_frame(&self, frame: &QueuedFrame, error: AppError) -> AppError {
+        self.keep_refused_frame(
+            &frame.request,
+            &[],
+            frame.correlation_id,
+            &frame.trace_id,
+            error,
         )
-        .await?;
+        .await
+    }
+
+    /// Stores a dead letter when the database refused the frame because of its contents.
+    ///
+    /// Returns the error for the client: a rejection naming the dead letter, or `error` itself
+    /// when it is of another kind or the dead letter could not be stored.
+    async fn keep_refused_frame(
+        &self,
+        request: &TelemetryRequest,
+        rejected: &[DecodeRejection],
+        correlation_id: Uuid,
+        trace_id: &str,
+        error: AppError,
+    ) -> AppError {
+        let Some(error_class) = error.dead_letter_class() else {
+            return error;
+        };
+
+        let stored = match NewDeadLetter::for_frame(
+            request,
+            rejected,
+            correlation_id,
+            trace_id,
+            &error,
+            error_class,
+        ) {
+            Ok(dead_letter) => self.store_dead_letter(dead_letter).await,
+            Err(e) => Err(e),
+        };
+        match stored {
+            Ok(id) => AppError::Rejected {
+                reason: error_class.to_string(),
+                message: format!(
+                    "Frame could not be stored ({}); kept as dead letter {}",
+                    error, id
+                ),
+            },
+            Err(e) => {
+                error!(
+                    correlation_id = %correlation_id,
+                    error = %e,
+                    "Failed to keep refused frame as a dead letter"
+                );
+                error
+            }
+        }
+    }
+
+    /// Keeps a frame that cannot be stored for inspection and retry; returns the dead letter ID
+    pub async fn store_dead_letter(&self, dead_letter: NewDeadLetter) -> Result<Uuid, AppError> {
+        self.dead_letter_repo.insert(&dead_letter).await?;
+
+        warn!(
+            dead_letter_id = %dead_letter.id,
+            correlation_id = %dead_letter.correlation_id,
+            vessel_id = %dead_letter.vessel_id,
+            error_class = %dead_letter.error_class,
+            error = %dead_letter.error_message,
+            "Frame kept as dead letter"
+        );
+
+        Ok(dead_letter.id)
+    }
+
+    /// Appends a frame to the write-ahead log and acknowledges it.
+    ///
+    /// Timestamps and the frame ID are checked first so a bad frame is still refused now; the
+    /// signals are validated when the frame is replayed, so the response counts no valid signals.
+    async fn buffer_frame(
+        &self,
+        request: TelemetryRequest,
+        rejected: &[DecodeRejection],
+        ids: &FrameIds,
+    ) -> Result<TelemetryResponse, AppError> {
+        let wal = self
+            .wal
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Write-ahead log is not enabled".to_string()))?;
+
+        self.validate_timestamps(&request)?;
+        if let Some(frame_id) = request.frame_id.as_deref() {
+            Self::check_frame_id(frame_id)?;
+        }
+
+        let record = WalRecord {
+            correlation_id: ids.correlation_id,
+            trace_id: ids.trace_id.clone(),
+            accepted_at: Utc::now(),
+            request,
+            rejected: rejected.to_vec(),
+        };
+        wal.append(&record).await?;
 
         info!(
-            total_duration_ms = total_duration,
-            "Telemetry ingestion completed successfully"
+            correlation_id = %record.correlation_id,
+            vessel_id = %record.request.vessel_id,
+            "Frame buffered in the write-ahead log"
         );
 
         Ok(TelemetryResponse {
+            message: "Telemetry buffered until the database is available".to_string(),
+            correlation_id: record.correlation_id,
+            valid_signals: 0,
+            invalid_signals: record.rejected.len(),
+        })
+    }
+
+    /// Stores frames read back from the write-ahead log.
+    ///
+    /// Each frame keeps the correlation_id it was acknowledged with and is claimed under its
+    /// `frameId`, or `wal:<correlation_id>` without one, so replaying it again writes nothing.
+    /// Frames that can no longer be stored are kept as dead letters; an outage stops the replay.
+    /// Replayed frames record no `server_metrics` rows.
+    pub async fn replay_buffered_frames(
+        &self,
+        records: &[WalRecord],
+    ) -> Result<WalReplayOutcome, AppError> {
+        let mut outcome = WalReplayOutcome::default();
+        let mut registries = HashMap::new();
+
+        for record in records {
+            let frame = RecoveredFrame {
+                request: &record.request,
+                rejected: &record.rejected,
+                correlation_id: record.correlation_id,
+                trace_id: &record.trace_id,
+                fallback_frame_id: format!("wal:{}", record.correlation_id),
+            };
+            let error = match self.store_recovered_frame(&frame, &mut registries).await {
+                Ok(Some(_)) => {
+                    outcome.replayed += 1;
+                    continue;
+                }
+                Ok(None) => {
+                    outcome.duplicates += 1;
+                    continue;
+                }
+                Err(e) if e.is_database_outage() => return Err(e),
+                Err(e) => e,
+            };
+
+            let stored = match NewDeadLetter::for_frame(
+                &record.request,
+                &record.rejected,
+                record.correlation_id,
+                &record.trace_id,
+                &error,
+                recovery_failure_class(&error),
+            ) {
+                Ok(dead_letter) => self.store_dead_letter(dead_letter).await,
+                Err(e) => Err(e),
+            };
+            match stored {
+                Ok(_) => outcome.dead_lettered += 1,
+                Err(e) if e.is_database_outage() => return Err(e),
+                Err(e) => {
+                    error!(
+                        correlation_id = %record.correlation_id,
+                        vessel_id = %record.request.vessel_id,
+                        error = %error,
+                        dead_letter_error = %e,
+                        "Dropping buffered frame that cannot be stored"
+                    );
+                    outcome.dropped += 1;
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Validates and stores a frame that was acknowledged earlier, skipping the age limit.
+    ///
+    /// `registries` caches signal registries by vessel across calls. Returns `None` when the
+    /// frame had already been ingested.
+    pub async fn store_recovered_frame(
+        &self,
+        frame: &RecoveredFrame<'_>,
+        registries: &mut HashMap<String, Arc<VesselSignals>>,
+    ) -> Result<Option<TelemetryResponse>, AppError> {
+        let request = frame.request;
+        let registered_signals = match registries.entry(request.vessel_id.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(
+                self.validate_vessel_and_load_signals(&request.vessel_id)
+                    .await?,
+            ),
+        };
+
+        // The frame was fresh when it was accepted, so the age limit no longer applies
+        let epoch_utc = self.check_timestamps(request, None)?;
+        let mut validated = self.validate_all_signals(
+            request,
+            epoch_utc,
+            registered_signals,
+            frame.correlation_id,
+            frame.trace_id.to_string(),
+        );
+        Self::add_decode_rejections(
+            &mut validated,
+            request,
+            epoch_utc,
+            frame.rejected,
+            frame.correlation_id,
+            frame.trace_id,
+        );
+
+        let response = TelemetryResponse {
             message: "Telemetry ingested successfully".to_string(),
-            correlation_id,
+            correlation_id: frame.correlation_id,
             valid_signals: validated.valid.len(),
             invalid_signals: validated.invalid.len(),
-        })
+        };
+        let frame_id = request
+            .frame_id
+            .as_deref()
+            .unwrap_or(&frame.fallback_frame_id);
+        let claim = self.claim_for(&request.vessel_id, frame_id, &response)?;
+
+        let written = self.ingest_to_database(&validated, Some(&claim)).await?;
+        Ok(written.then_some(response))
     }
 
     async fn record_request_volume(
@@ -165,17 +1588,15 @@ impl TelemetryService {
         Ok(())
     }
 
-    /// Validates vessel exists and loads all registered signals
+    /// Validates vessel exists and returns the signals it may send
     async fn validate_vessel_and_load_signals(
         &self,
         vessel_id: &str,
-    ) -> Result<HashMap<String, Signal>, AppError> {
+    ) -> Result<Arc<VesselSignals>, AppError> {
         info!(vessel_id = %vessel_id, "Validating vessel existence");
 
-        // Check if vessel exists in vessel_register_table
-        let vessel = self.vessel_repo.find_by_id(vessel_id).await?;
-
-        if vessel.is_none() {
+        // Check if vessel exists in vessel_register_table, or was seen active moments ago
+        if !self.vessel_lookup.is_active_vessel(vessel_id).await? {
             warn!(vessel_id = %vessel_id, "Vessel not registered");
             return Err(AppError::Forbidden(format!(
                 "Vessel {} is not registered in vessel_register_table",
@@ -185,23 +1606,232 @@ impl TelemetryService {
 
         info!(vessel_id = %vessel_id, "Vessel validated successfully");
 
-        // Load all registered signals from signal_register_table
-        info!("Loading registered signals from signal_register_table");
-        let registered_signals = self.signal_repo.find_all().await?;
+        // Registered signals and the vessel's class come from the in-memory registry snapshot
+        let registered_signals = self.signal_registry.vessel_signals(vessel_id).await?;
 
-        info!(
-            signal_count = registered_signals.len(),
+        debug!(
+            signal_count = registered_signals.registry().len(),
+            vessel_class = registered_signals.class_name(),
             "Registered signals loaded"
         );
 
         Ok(registered_signals)
     }
 
-    /// Validates all signals in the request against registered signals
+    /// Checks `epochUTC` against `timestampUTC` and both against the server clock.
+    ///
+    /// Returns the parsed epoch so callers never fall back to a placeholder value.
+    fn validate_timestamps(&self, request: &TelemetryRequest) -> Result<i64, AppError> {
+        self.check_timestamps(request, Some(self.config.max_timestamp_age_secs))
+    }
+
+    /// Timestamp checks, with the age limit left out when `max_age_secs` is `None`
+    fn check_timestamps(
+        &self,
+        request: &TelemetryRequest,
+        max_age_secs: Option<i64>,
+    ) -> Result<i64, AppError> {
+        let epoch_utc = request.epoch_utc.trim().parse::<i64>().map_err(|_| {
+            Self::timestamp_rejection(
+                request,
+                "invalid_epoch",
+                format!(
+                    "epochUTC '{}' is not an integer number of seconds",
+                    request.epoch_utc
+                ),
+            )
+        })?;
+
+        let timestamp_secs = request.timestamp_utc.timestamp();
+        let difference = epoch_utc.abs_diff(timestamp_secs);
+        if difference > self.config.epoch_tolerance_secs.unsigned_abs() {
+            return Err(Self::timestamp_rejection(
+                request,
+                "epoch_mismatch",
+                format!(
+                    "epochUTC {} differs from timestampUTC {} by {}s (tolerance {}s)",
+                    epoch_utc,
+                    request.timestamp_utc.to_rfc3339(),
+                    difference,
+                    self.config.epoch_tolerance_secs
+                ),
+            ));
+        }
+
+        let now = Utc::now();
+        let ahead_secs = (request.timestamp_utc - now).num_seconds();
+        if ahead_secs > self.config.max_future_skew_secs {
+            return Err(Self::timestamp_rejection(
+                request,
+                "timestamp_in_future",
+                format!(
+                    "timestampUTC {} is {}s ahead of server time (limit {}s)",
+                    request.timestamp_utc.to_rfc3339(),
+                    ahead_secs,
+                    self.config.max_future_skew_secs
+                ),
+            ));
+        }
+
+        let age_secs = (now - request.timestamp_utc).num_seconds();
+        if let Some(max_age_secs) = max_age_secs
+            && age_secs > max_age_secs
+        {
+            return Err(Self::timestamp_rejection(
+                request,
+                "timestamp_too_old",
+                format!(
+                    "timestampUTC {} is {}s old (limit {}s)",
+                    request.timestamp_utc.to_rfc3339(),
+                    age_secs,
+                    max_age_secs
+                ),
+            ));
+        }
+
+        Ok(epoch_utc)
+    }
+
+    /// Refuses frames whose frame ID claim could lapse while a replay would still be accepted.
+    ///
+    /// Transports without a per-request credential (UDP) rely on the claim to stop replays, so they
+    /// must not accept anything the claim no longer covers. The claim lasts the dedup window from
+    /// ingestion, and a frame may be ingested up to `max_future_skew_secs` before its timestamp, so
+    /// frames are accepted only while younger than the window less that skew. A replay is then
+    /// always refused before the claim of the original expires.
+    pub fn check_replay_window(&self, timestamp_utc_ms: i64) -> Result<(), AppError> {
+        let age_ms = Utc::now()
+            .timestamp_millis()
+            .saturating_sub(timestamp_utc_ms);
+        let max_future_skew_ms = self.config.max_future_skew_secs.saturating_mul(1000);
+        if -age_ms > max_future_skew_ms {
+            return Err(AppError::Rejected {
+                reason: "timestamp_in_future".to_string(),
+                message: format!(
+                    "Frame is {}s ahead of server time (limit {}s)",
+                    -age_ms / 1000,
+                    self.config.max_future_skew_secs
+                ),
+            });
+        }
+
+        let window_secs = self.config.dedup_window_secs - self.config.max_future_skew_secs as f64;
+        if (age_ms as f64 / 1000.0) < window_secs {
+            return Ok(());
+        }
+
+        Err(AppError::Rejected {
+            reason: "outside_replay_window".to_string(),
+            message: format!(
+                "Frame is {}s old, beyond the {}s replay window",
+                age_ms / 1000,
+                window_secs.max(0.0)
+            ),
+        })
+    }
+
+    fn timestamp_rejection(request: &TelemetryRequest, reason: &str, message: String) -> AppError {
+        warn!(
+            vessel_id = %request.vessel_id,
+            reason = %reason,
+            message = %message,
+            "Frame rejected by timestamp validation"
+        );
+        AppError::Rejected {
+            reason: reason.to_string(),
+            message,
+        }
+    }
+
+    /// Estimates per-vessel clock drift from `ingested_at - timestamp_utc`.
+    ///
+    /// Vessels whose estimate is further than `threshold_secs` from zero are flagged.
+    pub async fn get_clock_drift(
+        &self,
+        vessel_id: Option<String>,
+        hours: f64,
+        threshold_secs: f64,
+    ) -> Result<Vec<ClockDriftResponse>, AppError> {
+        let stats = self
+            .telemetry_repo
+            .get_ingestion_delays(vessel_id, hours)
+            .await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|s| ClockDriftResponse {
+                vessel_id: s.vessel_id,
+                time_range: format!("Last {} hours", hours),
+                frames: s.frames,
+                estimated_drift_secs: s.p05_delay_secs,
+                min_delay_secs: s.min_delay_secs,
+                median_delay_secs: s.median_delay_secs,
+                max_delay_secs: s.max_delay_secs,
+                suspect_clock: s.p05_delay_secs.abs() > threshold_secs,
+            })
+            .collect())
+    }
+
+    /// Streams a vessel's stored samples in timestamp order.
+    ///
+    /// Rows are read in pages of `STORED_PAGE_ROWS`, and the next page is only fetched once the
+    /// consumer has taken the last one, so a slow client never holds a pooled connection.
+    pub async fn stream_stored_telemetry<'a>(
+        &'a self,
+        query: &'a StoredTelemetryQuery,
+    ) -> Result<BoxStream<'a, Result<StoredTelemetry, AppError>>, AppError> {
+        if query.to <= query.from {
+            return Err(AppError::Validation(
+                "Query end must be after its start".to_string(),
+            ));
+        }
+        if query.to - query.from > Duration::days(MAX_QUERY_RANGE_DAYS) {
+            return Err(AppError::Validation(format!(
+                "Query range must not exceed {} days",
+                MAX_QUERY_RANGE_DAYS
+            )));
+        }
+        if !self
+            .vessel_lookup
+            .is_active_vessel(&query.vessel_id)
+            .await?
+        {
+            return Err(AppError::NotFound(format!(
+                "Vessel {} not found",
+                query.vessel_id
+            )));
+        }
+
+        info!(
+            vessel_id = %query.vessel_id,
+            from = %query.from,
+            to = %query.to,
+            "Streaming stored telemetry"
+        );
+
+        // `None` once the last page came back short; `Some(None)` before the first page
+        let pages = stream::try_unfold(Some(None), move |cursor| async move {
+            let Some(after) = cursor else {
+                return Ok::<_, AppError>(None);
+            };
+            let page = self
+                .telemetry_repo
+                .find_stored_page(query, after.as_ref(), STORED_PAGE_ROWS)
+                .await?;
+            let next = (page.len() as i64 == STORED_PAGE_ROWS)
+                .then(|| page.last().map(StoredCursor::from));
+            Ok(Some((stream::iter(page.into_iter().map(Ok)), next)))
+        });
+
+        Ok(pages.try_flatten().boxed())
+    }
+
+    /// Validates all signals in the request against the vessel's effective signal definitions
     fn validate_all_signals(
         &self,
         request: &TelemetryRequest,
-        registered_signals: &HashMap<String, Signal>,
+        epoch_utc: i64,
+        registered_signals: &VesselSignals,
         correlation_id: Uuid,
         trace_id: String,
     ) -> ValidatedSignals {
@@ -222,7 +1852,7 @@ impl TelemetryService {
                     invalid_records.push((
                         request.vessel_id.clone(),
                         request.timestamp_utc,
-                        request.epoch_utc.parse::<i64>().unwrap_or(0),
+                        epoch_utc,
                         signal_name.clone(),
                         Decimal::ZERO,
                         reason,
@@ -233,9 +1863,39 @@ impl TelemetryService {
                 }
             };
 
-            // Check if signal exists in signal_register_table
-            match registered_signals.get(signal_name) {
-                Some(signal) => {
+            // Resolve the signal from signal_register_table, the vessel's class and its overrides
+            match registered_signals.resolve(signal_name) {
+                ResolvedSignal::Unowned => {
+                    warn!(
+                        signal = %signal_name,
+                        vessel_class = registered_signals.class_name(),
+                        "Signal is not one of the vessel class's signals"
+                    );
+                    invalid_records.push((
+                        request.vessel_id.clone(),
+                        request.timestamp_utc,
+                        epoch_utc,
+                        signal_name.clone(),
+                        value_decimal,
+                        "unowned_signal".to_string(),
+                        correlation_id,
+                        trace_id.clone(),
+                    ));
+                }
+                ResolvedSignal::Retired(_) => {
+                    warn!(signal = %signal_name, "Signal is retired");
+                    invalid_records.push((
+                        request.vessel_id.clone(),
+                        request.timestamp_utc,
+                        epoch_utc,
+                        signal_name.clone(),
+                        value_decimal,
+                        "retired_signal".to_string(),
+                        correlation_id,
+                        trace_id.clone(),
+                    ));
+                }
+                ResolvedSignal::Active(signal) => {
                     // Signal is registered, now validate its value
                     match self.validate_signal_value(signal, value_decimal) {
                         Ok(_) => {
@@ -248,7 +1908,7 @@ impl TelemetryService {
                             valid_records.push((
                                 request.vessel_id.clone(),
                                 request.timestamp_utc,
-                                request.epoch_utc.parse::<i64>().unwrap_or(0),
+                                epoch_utc,
                                 signal_name.clone(),
                                 value_decimal,
                                 correlation_id,
@@ -266,7 +1926,7 @@ impl TelemetryService {
                             invalid_records.push((
                                 request.vessel_id.clone(),
                                 request.timestamp_utc,
-                                request.epoch_utc.parse::<i64>().unwrap_or(0),
+                                epoch_utc,
                                 signal_name.clone(),
                                 value_decimal,
                                 reason,
@@ -276,7 +1936,7 @@ impl TelemetryService {
                         }
                     }
                 }
-                None => {
+                ResolvedSignal::Unregistered => {
                     // Signal not found in signal_register_table
                     warn!(
                         signal = %signal_name,
@@ -285,7 +1945,7 @@ impl TelemetryService {
                     invalid_records.push((
                         request.vessel_id.clone(),
                         request.timestamp_utc,
-                        request.epoch_utc.parse::<i64>().unwrap_or(0),
+                        epoch_utc,
                         signal_name.clone(),
                         value_decimal,
                         "unregistered_signal".to_string(),
@@ -334,21 +1994,21 @@ impl TelemetryService {
             }
             "analog" => {
                 // Analog signals must be within min/max range
-                if let Some(min) = signal.min_value {
-                    if value < min {
-                        return Err(format!(
-                            "Analog signal '{}' value {} is below minimum {}",
-                            signal.signal_name, value, min
-                        ));
-                    }
+                if let Some(min) = signal.min_value
+                    && value < min
+                {
+                    return Err(format!(
+                        "Analog signal '{}' value {} is below minimum {}",
+                        signal.signal_name, value, min
+                    ));
                 }
-                if let Some(max) = signal.max_value {
-                    if value > max {
-                        return Err(format!(
-                            "Analog signal '{}' value {} is above maximum {}",
-                            signal.signal_name, value, max
-                        ));
-                    }
+                if let Some(max) = signal.max_value
+                    && value > max
+                {
+                    return Err(format!(
+                        "Analog signal '{}' value {} is above maximum {}",
+                        signal.signal_name, value, max
+                    ));
                 }
                 Ok(())
             }
@@ -379,33 +2039,33 @@ impl TelemetryService {
         Ok(())
     }
 
-    /// Writes validated signals to database
-    async fn ingest_to_database(&self, validated: &ValidatedSignals) -> Result<(), AppError> {
-        // Write valid signals to telemetry_raw table
-        if !validated.valid.is_empty() {
-            info!(
-                count = validated.valid.len(),
-                "Writing valid signals to telemetry_raw"
-            );
-            self.telemetry_repo
-                .insert_raw_batch(&validated.valid)
-                .await?;
-            info!("Valid signals written successfully");
-        }
+    /// Writes a frame's valid and invalid signals in one transaction.
+    ///
+    /// With a frame claim the claim is committed with the rows; returns `false` when the frame ID
+    /// had already been ingested and nothing was written.
+    async fn ingest_to_database(
+        &self,
+        validated: &ValidatedSignals,
+        claim: Option<&FrameClaim>,
+    ) -> Result<bool, AppError> {
+        info!(
+            frame_id = ?claim.map(|c| c.frame_id.as_str()),
+            valid_count = validated.valid.len(),
+            invalid_count = validated.invalid.len(),
+            "Writing frame to telemetry_raw and telemetry_filtered"
+        );
 
-        // Write invalid signals to telemetry_filtered table
-        if !validated.invalid.is_empty() {
-            info!(
-                count = validated.invalid.len(),
-                "Writing invalid signals to telemetry_filtered"
-            );
-            self.telemetry_repo
-                .insert_filtered_batch(&validated.invalid)
-                .await?;
-            info!("Invalid signals written successfully");
-        }
+        let written = self
+            .telemetry_repo
+            .insert_frame(
+                &validated.valid,
+                &validated.invalid,
+                self.config.conflict_policy,
+                claim,
+            )
+            .await?;
 
-        Ok(())
+        Ok(written)
     }
 
     async fn record_ingestion_latency(
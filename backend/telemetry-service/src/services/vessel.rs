@@ -1,25 +1,44 @@
 use crate::error::AppError;
 use crate::models::vessel::{CreateVesselRequest, VesselResponse};
 use crate::repositories::vessel::VesselRepository;
+use crate::services::vessel_lookup::VesselLookupCache;
 use std::sync::Arc;
 use tracing::info;
 use uuid::Uuid;
 
 pub struct VesselService {
     vessel_repo: Arc<VesselRepository>,
+    vessel_lookup: Arc<VesselLookupCache>,
 }
 
 impl VesselService {
-    pub fn new(vessel_repo: Arc<VesselRepository>) -> Self {
-        Self { vessel_repo }
+    pub fn new(vessel_repo: Arc<VesselRepository>, vessel_lookup: Arc<VesselLookupCache>) -> Self {
+        Self {
+            vessel_repo,
+            vessel_lookup,
+        }
     }
 
     pub async fn create_vessel(
         &self,
         request: CreateVesselRequest,
     ) -> Result<VesselResponse, AppError> {
+        if let Some(mmsi) = request.mmsi
+            && !(100_000_000..=999_999_999).contains(&mmsi)
+        {
+            return Err(AppError::Validation(format!(
+                "MMSI {} is not a 9-digit number",
+                mmsi
+            )));
+        }
+
         // Check if vessel already exists
-        if let Some(_) = self.vessel_repo.find_by_id(&request.vessel_id).await? {
+        if self
+            .vessel_repo
+            .find_by_id(&request.vessel_id)
+            .await?
+            .is_some()
+        {
             return Err(AppError::Conflict(format!(
                 "Vessel {} already exists",
                 request.vessel_id
@@ -34,6 +53,7 @@ impl VesselService {
             .create(
                 &request.vessel_id,
                 &request.vessel_name,
+                request.mmsi,
                 correlation_id,
                 trace_id,
             )
@@ -41,13 +61,7 @@ impl VesselService {
 
         info!("Vessel created: {}", vessel.vessel_id);
 
-        Ok(VesselResponse {
-            vessel_id: vessel.vessel_id,
-            vessel_name: vessel.vessel_name,
-            is_active: vessel.is_active,
-            created_at: vessel.created_at,
-            updated_at: vessel.updated_at,
-        })
+        Ok(vessel.into())
     }
 
     pub async fn get_vessel(&self, vessel_id: &str) -> Result<VesselResponse, AppError> {
@@ -57,28 +71,13 @@ impl VesselService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Vessel {} not found", vessel_id)))?;
 
-        Ok(VesselResponse {
-            vessel_id: vessel.vessel_id,
-            vessel_name: vessel.vessel_name,
-            is_active: vessel.is_active,
-            created_at: vessel.created_at,
-            updated_at: vessel.updated_at,
-        })
+        Ok(vessel.into())
     }
 
     pub async fn list_vessels(&self) -> Result<Vec<VesselResponse>, AppError> {
         let vessels = self.vessel_repo.find_all().await?;
 
-        Ok(vessels
-            .into_iter()
-            .map(|v| VesselResponse {
-                vessel_id: v.vessel_id,
-                vessel_name: v.vessel_name,
-                is_active: v.is_active,
-                created_at: v.created_at,
-                updated_at: v.updated_at,
-            })
-            .collect())
+        Ok(vessels.into_iter().map(Into::into).collect())
     }
 
     pub async fn deactivate_vessel(&self, vessel_id: &str) -> Result<(), AppError> {
@@ -89,6 +88,9 @@ impl VesselService {
             .ok_or_else(|| AppError::NotFound(format!("Vessel {} not found", vessel_id)))?;
 
         self.vessel_repo.deactivate(vessel_id).await?;
+        // Drops the cached lookups so this instance refuses the vessel's frames now rather than
+        // after the TTL; its keys still authenticate
+        self.vessel_lookup.invalidate_vessel(vessel_id);
 
         info!("Vessel deactivated: {}", vessel_id);
 
@@ -0,0 +1,424 @@
+use crate::error::AppError;
+use crate::models::signal::{SIGNAL_TYPE_ANALOG, Signal};
+use crate::models::vessel::VesselResponse;
+use crate::models::vessel_class::{
+    CreateVesselClassRequest, SignalRangeRequest, SignalRangeResponse, VesselClass,
+    VesselClassDefinition, VesselClassResponse, VesselSignalResponse, VesselSignalsResponse,
+};
+use crate::repositories::signal::SignalRepository;
+use crate::repositories::vessel::VesselRepository;
+use crate::repositories::vessel_class::VesselClassRepository;
+use crate::services::signal::{check_bound, trimmed};
+use crate::services::signal_registry::{ResolvedSignal, SignalRegistryCache};
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Longest name accepted (vessel_class_table.class_name is VARCHAR(50))
+pub const MAX_CLASS_NAME_LEN: usize = 50;
+
+/// Vessel classes, the vessels assigned to them and per-vessel signal ranges
+pub struct VesselClassService {
+    vessel_class_repo: Arc<VesselClassRepository>,
+    vessel_repo: Arc<VesselRepository>,
+    signal_repo: Arc<SignalRepository>,
+    /// Reloaded after each change so this instance applies it to the next frame
+    signal_registry: Arc<SignalRegistryCache>,
+}
+
+impl VesselClassService {
+    pub fn new(
+        vessel_class_repo: Arc<VesselClassRepository>,
+        vessel_repo: Arc<VesselRepository>,
+        signal_repo: Arc<SignalRepository>,
+        signal_registry: Arc<SignalRegistryCache>,
+    ) -> Self {
+        Self {
+            vessel_class_repo,
+            vessel_repo,
+            signal_repo,
+            signal_registry,
+        }
+    }
+
+    pub async fn create_class(
+        &self,
+        request: CreateVesselClassRequest,
+    ) -> Result<VesselClassResponse, AppError> {
+        validate_class_name(&request.class_name)?;
+        let signals = self.validate_signals(request.definition.signals).await?;
+
+        if self
+            .vessel_class_repo
+            .find_by_name(&request.class_name)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::Conflict(format!(
+                "Vessel class {} already exists",
+                request.class_name
+            )));
+        }
+
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Some(Uuid::new_v4().to_string());
+        let description = trimmed(request.definition.description);
+
+        let class = self
+            .vessel_class_repo
+            .create(
+                &request.class_name,
+                description.as_deref(),
+                &signals,
+                correlation_id,
+                trace_id,
+            )
+            .await?;
+
+        info!(
+            "Vessel class created: {} ({} signals)",
+            class.class_name,
+            signals.len()
+        );
+
+        Ok(class_response(class, signals, 0))
+    }
+
+    pub async fn get_class(&self, class_name: &str) -> Result<VesselClassResponse, AppError> {
+        let class = self.find_class(class_name).await?;
+        let signals = self.vessel_class_repo.signals_of(class_name).await?;
+        let vessel_count = self.vessel_count(class_name).await?;
+
+        Ok(class_response(class, signals, vessel_count))
+    }
+
+    pub async fn list_classes(&self) -> Result<Vec<VesselClassResponse>, AppError> {
+        let classes = self.vessel_class_repo.find_all().await?;
+        let mut class_signals = self.vessel_class_repo.class_signals().await?;
+        let vessel_counts = self.vessel_class_repo.vessel_counts().await?;
+
+        Ok(classes
+            .into_iter()
+            .map(|class| {
+                let mut signals: Vec<String> = class_signals
+                    .remove(&class.class_name)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect();
+                signals.sort();
+                let vessel_count = vessel_counts.get(&class.class_name).copied().unwrap_or(0);
+                class_response(class, signals, vessel_count)
+            })
+            .collect())
+    }
+
+    /// Replaces a class's description and signals; its vessels are held to the new signals
+    /// from the next frame
+    pub async fn update_class(
+        &self,
+        class_name: &str,
+        definition: VesselClassDefinition,
+    ) -> Result<VesselClassResponse, AppError> {
+        let signals = self.validate_signals(definition.signals).await?;
+
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Some(Uuid::new_v4().to_string());
+        let description = trimmed(definition.description);
+
+        let class = self
+            .vessel_class_repo
+            .update(
+                class_name,
+                description.as_deref(),
+                &signals,
+                correlation_id,
+                trace_id,
+            )
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Vessel class {} not found", class_name)))?;
+
+        info!(
+            "Vessel class updated: {} ({} signals)",
+            class.class_name,
+            signals.len()
+        );
+        self.signal_registry.refresh("vessel_class_updated").await;
+
+        let vessel_count = self.vessel_count(class_name).await?;
+        Ok(class_response(class, signals, vessel_count))
+    }
+
+    /// Deletes a class no vessel is assigned to
+    pub async fn delete_class(&self, class_name: &str) -> Result<(), AppError> {
+        self.find_class(class_name).await?;
+
+        let vessel_count = self.vessel_count(class_name).await?;
+        if vessel_count > 0 {
+            return Err(AppError::Conflict(format!(
+                "Vessel class {} is assigned to {} vessels; assign them another class first",
+                class_name, vessel_count
+            )));
+        }
+
+        if self.vessel_class_repo.delete(class_name).await? {
+            info!("Vessel class deleted: {}", class_name);
+        }
+
+        Ok(())
+    }
+
+    /// Puts a vessel in a class, or with `None` lets it send every registered signal again
+    pub async fn assign_class(
+        &self,
+        vessel_id: &str,
+        class_name: Option<String>,
+    ) -> Result<VesselResponse, AppError> {
+        if let Some(class_name) = &class_name {
+            self.find_class(class_name).await?;
+        }
+
+        if !self
+            .vessel_repo
+            .set_class(vessel_id, class_name.as_deref())
+            .await?
+        {
+            return Err(AppError::NotFound(format!(
+                "Vessel {} not found",
+                vessel_id
+            )));
+        }
+
+        info!(
+            "Vessel {} assigned class: {}",
+            vessel_id,
+            class_name.as_deref().unwrap_or("none")
+        );
+        self.signal_registry.refresh("vessel_class_assigned").await;
+
+        let vessel = self
+            .vessel_repo
+            .find_by_id(vessel_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Vessel {} not found", vessel_id)))?;
+        Ok(vessel.into())
+    }
+
+    /// The active signals a vessel may send, with the ranges its frames are validated against
+    pub async fn vessel_signals(&self, vessel_id: &str) -> Result<VesselSignalsResponse, AppError> {
+        self.find_vessel(vessel_id).await?;
+
+        let vessel_signals = self.signal_registry.vessel_signals(vessel_id).await?;
+        let mut signals: Vec<(i32, VesselSignalResponse)> = vessel_signals
+            .registry()
+            .keys()
+            .filter_map(|signal_name| match vessel_signals.resolve(signal_name) {
+                ResolvedSignal::Active(signal) => Some((
+                    signal.signal_id,
+                    VesselSignalResponse {
+                        signal_name: signal.signal_name.clone(),
+                        signal_type: signal.signal_type.clone(),
+                        min_value: signal.min_value.and_then(|v| v.to_f64()),
+                        max_value: signal.max_value.and_then(|v| v.to_f64()),
+                        unit: signal.unit.clone(),
+                        overridden: vessel_signals.is_overridden(signal_name),
+                    },
+                )),
+                _ => None,
+            })
+            .collect();
+        signals.sort_by_key(|(signal_id, _)| *signal_id);
+
+        Ok(VesselSignalsResponse {
+            vessel_id: vessel_id.to_string(),
+            vessel_class: vessel_signals.class_name().map(str::to_string),
+            signals: signals.into_iter().map(|(_, signal)| signal).collect(),
+        })
+    }
+
+    /// Gives a vessel its own range for an analog signal it may send
+    pub async fn set_signal_range(
+        &self,
+        vessel_id: &str,
+        signal_name: &str,
+        range: SignalRangeRequest,
+    ) -> Result<SignalRangeResponse, AppError> {
+        self.find_vessel(vessel_id).await?;
+        let signal = self.find_active_signal(signal_name).await?;
+
+        if signal.signal_type != SIGNAL_TYPE_ANALOG {
+            return Err(AppError::Validation(format!(
+                "Signal {} is {}; only analog signals have a range",
+                signal_name, signal.signal_type
+            )));
+        }
+        check_bound("minValue", range.min_value)?;
+        check_bound("maxValue", range.max_value)?;
+        if range.min_value > range.max_value {
+            return Err(AppError::Validation(format!(
+                "minValue {} is greater than maxValue {}",
+                range.min_value, range.max_value
+            )));
+        }
+
+        let vessel_signals = self.signal_registry.vessel_signals(vessel_id).await?;
+        if !vessel_signals.owns(signal_name) {
+            return Err(AppError::Validation(format!(
+                "Vessel {} cannot send {}: its class {} does not include it",
+                vessel_id,
+                signal_name,
+                vessel_signals.class_name().unwrap_or_default()
+            )));
+        }
+
+        let correlation_id = Uuid::new_v4();
+        let trace_id = Some(Uuid::new_v4().to_string());
+
+        let range = self
+            .vessel_class_repo
+            .upsert_override(
+                vessel_id,
+                signal_name,
+                range.min_value,
+                range.max_value,
+                correlation_id,
+                trace_id,
+            )
+            .await?;
+
+        info!(
+            "Signal range overridden for vessel {}: {} [{}, {}]",
+            vessel_id, signal_name, range.min_value, range.max_value
+        );
+        self.signal_registry.refresh("signal_range_set").await;
+
+        Ok(range.into())
+    }
+
+    /// Goes back to the registered range
+    pub async fn clear_signal_range(
+        &self,
+        vessel_id: &str,
+        signal_name: &str,
+    ) -> Result<(), AppError> {
+        if !self
+            .vessel_class_repo
+            .delete_override(vessel_id, signal_name)
+            .await?
+        {
+            return Err(AppError::NotFound(format!(
+                "Vessel {} has no range override for {}",
+                vessel_id, signal_name
+            )));
+        }
+
+        info!(
+            "Signal range override removed for vessel {}: {}",
+            vessel_id, signal_name
+        );
+        self.signal_registry.refresh("signal_range_cleared").await;
+
+        Ok(())
+    }
+
+    async fn find_class(&self, class_name: &str) -> Result<VesselClass, AppError> {
+        self.vessel_class_repo
+            .find_by_name(class_name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Vessel class {} not found", class_name)))
+    }
+
+    async fn find_vessel(&self, vessel_id: &str) -> Result<(), AppError> {
+        self.vessel_repo
+            .find_by_id(vessel_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Vessel {} not found", vessel_id)))?;
+
+        Ok(())
+    }
+
+    async fn find_active_signal(&self, signal_name: &str) -> Result<Signal, AppError> {
+        let signal = self
+            .signal_repo
+            .find_by_name(signal_name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Signal {} not found", signal_name)))?;
+
+        if signal.retired_at.is_some() {
+            return Err(AppError::Validation(format!(
+                "Signal {} is retired",
+                signal_name
+            )));
+        }
+
+        Ok(signal)
+    }
+
+    async fn vessel_count(&self, class_name: &str) -> Result<i64, AppError> {
+        let vessel_counts = self.vessel_class_repo.vessel_counts().await?;
+        Ok(vessel_counts.get(class_name).copied().unwrap_or(0))
+    }
+
+    /// Checks every signal is registered and active, returning them sorted without duplicates
+    async fn validate_signals(&self, signals: Vec<String>) -> Result<Vec<String>, AppError> {
+        let signals: BTreeSet<String> = signals
+            .into_iter()
+            .map(|signal_name| signal_name.trim().to_string())
+            .collect();
+        let registry: HashMap<String, Signal> = self.signal_repo.find_all().await?;
+
+        let unknown: Vec<&str> = signals
+            .iter()
+            .filter(|signal_name| {
+                registry
+                    .get(*signal_name)
+                    .is_none_or(|signal| signal.retired_at.is_some())
+            })
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Signals not registered or retired: {}",
+                unknown.join(", ")
+            )));
+        }
+
+        Ok(signals.into_iter().collect())
+    }
+}
+
+fn validate_class_name(class_name: &str) -> Result<(), AppError> {
+    if class_name.is_empty() || class_name.len() > MAX_CLASS_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "className must be 1 to {} characters",
+            MAX_CLASS_NAME_LEN
+        )));
+    }
+    if !class_name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(AppError::Validation(format!(
+            "className '{}' may only contain letters, digits, '_', '-' and '.'",
+            class_name
+        )));
+    }
+
+    Ok(())
+}
+
+fn class_response(
+    class: VesselClass,
+    signals: Vec<String>,
+    vessel_count: i64,
+) -> VesselClassResponse {
+    VesselClassResponse {
+        class_name: class.class_name,
+        description: class.description,
+        signals,
+        vessel_count,
+        created_at: class.created_at,
+        updated_at: class.updated_at,
+    }
+}
@@ -0,0 +1,149 @@
+use crate::models::api_key::AuthenticatedKey;
+use crate::repositories::vessel::VesselRepository;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A validated API key
+struct CachedKey {
+    key: AuthenticatedKey,
+    /// The key's own expiry, checked on every use
+    expires_at: Option<DateTime<Utc>>,
+    cached: Instant,
+}
+
+/// How a cached API key stands
+pub enum KeyLookup {
+    /// Validated within the TTL
+    Fresh(AuthenticatedKey),
+    /// Validated since startup but past the TTL; only trusted while the database is unreachable
+    Stale(AuthenticatedKey),
+    Missing,
+}
+
+/// API key to vessel lookups shared by authentication and frame validation.
+///
+/// Only positive answers are cached: valid keys and active vessels. Unknown keys and vessels
+/// always go to the database, so a key or vessel created elsewhere works straight away. Entries
+/// are trusted for `ttl`; revoking a key or deactivating a vessel on this instance drops them
+/// at once, other instances notice within `ttl`.
+///
+/// A lookup that started before an invalidation must not cache its answer afterwards, so
+/// callers take [`generation`](Self::generation) before querying and pass it to the insert,
+/// which is skipped if anything was invalidated in between.
+pub struct VesselLookupCache {
+    vessel_repo: Arc<VesselRepository>,
+    ttl: Duration,
+    /// Bumped before every invalidation
+    generation: AtomicU64,
+    keys: Mutex<HashMap<String, CachedKey>>,
+    /// Active vessels, with when they were last seen active
+    vessels: Mutex<HashMap<String, Instant>>,
+}
+
+impl VesselLookupCache {
+    pub fn new(vessel_repo: Arc<VesselRepository>, ttl: Duration) -> Self {
+        Self {
+            vessel_repo,
+            ttl,
+            generation: AtomicU64::new(0),
+            keys: Mutex::new(HashMap::new()),
+            vessels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn key(&self, api_key: &str) -> KeyLookup {
+        let mut keys = lock(&self.keys);
+        let Some(cached) = keys.get(api_key) else {
+            return KeyLookup::Missing;
+        };
+
+        if cached
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+        {
+            keys.remove(api_key);
+            return KeyLookup::Missing;
+        }
+
+        if cached.cached.elapsed() < self.ttl {
+            KeyLookup::Fresh(cached.key.clone())
+        } else {
+            KeyLookup::Stale(cached.key.clone())
+        }
+    }
+
+    /// Take before looking a key or vessel up in the database
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Caches a key looked up at `generation`, unless something was invalidated since
+    pub fn insert_key(
+        &self,
+        api_key: &str,
+        key: AuthenticatedKey,
+        expires_at: Option<DateTime<Utc>>,
+        generation: u64,
+    ) {
+        let mut keys = lock(&self.keys);
+        if self.generation() != generation {
+            return;
+        }
+        keys.insert(
+            api_key.to_string(),
+            CachedKey {
+                key,
+                expires_at,
+                cached: Instant::now(),
+            },
+        );
+    }
+
+    pub fn remove_key(&self, api_key: &str) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        lock(&self.keys).remove(api_key);
+    }
+
+    /// Whether the vessel is registered and active, answered from memory if it was seen active
+    /// within the TTL
+    pub async fn is_active_vessel(&self, vessel_id: &str) -> Result<bool, sqlx::Error> {
+        let cached = lock(&self.vessels)
+            .get(vessel_id)
+            .is_some_and(|seen| seen.elapsed() < self.ttl);
+        if cached {
+            return Ok(true);
+        }
+
+        let generation = self.generation();
+        let active = self.vessel_repo.find_by_id(vessel_id).await?.is_some();
+        if active {
+            self.insert_vessel(vessel_id, generation);
+        }
+        Ok(active)
+    }
+
+    /// Caches a vessel seen active at `generation`, unless something was invalidated since
+    pub fn insert_vessel(&self, vessel_id: &str, generation: u64) {
+        let mut vessels = lock(&self.vessels);
+        if self.generation() != generation {
+            return;
+        }
+        vessels.insert(vessel_id.to_string(), Instant::now());
+    }
+
+    /// Forgets a vessel and every key of it
+    pub fn invalidate_vessel(&self, vessel_id: &str) {
+        // Bumped before removing, under the same locks the inserts check it under, so a lookup
+        // either lands before the removal or is skipped
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        lock(&self.vessels).remove(vessel_id);
+        lock(&self.keys).retain(|_, cached| cached.key.vessel_id != vessel_id);
+    }
+}
+
+fn lock<T>(map: &Mutex<T>) -> MutexGuard<'_, T> {
+    map.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,122 @@
+use crate::error::AppError;
+use crate::models::telemetry::WalBacklogResponse;
+use crate::services::telemetry::TelemetryService;
+use crate::wal::WriteAheadLog;
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+#[derive(Default)]
+struct ReplayStats {
+    last_replay_at: Option<DateTime<Utc>>,
+    last_replay_error: Option<String>,
+    frames_replayed: u64,
+    frames_dead_lettered: u64,
+    frames_dropped: u64,
+}
+
+/// Replays frames buffered in the write-ahead log once the database is reachable again
+pub struct WalService {
+    telemetry_service: Arc<TelemetryService>,
+    wal: Arc<WriteAheadLog>,
+    stats: Mutex<ReplayStats>,
+}
+
+impl WalService {
+    pub fn new(telemetry_service: Arc<TelemetryService>, wal: Arc<WriteAheadLog>) -> Self {
+        Self {
+            telemetry_service,
+            wal,
+            stats: Mutex::new(ReplayStats::default()),
+        }
+    }
+
+    /// Starts the periodic replay; call once at startup
+    pub fn spawn_replay(self: &Arc<Self>) {
+        let service = self.clone();
+        let interval = Duration::from_secs(self.wal.config().replay_interval_secs.max(1));
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                service.replay().await;
+            }
+        });
+    }
+
+    pub async fn backlog(&self) -> WalBacklogResponse {
+        let depth = self.wal.depth().await;
+        let stats = self.replay_stats();
+
+        WalBacklogResponse {
+            frames: depth.frames,
+            segments: depth.segments,
+            bytes: depth.bytes,
+            max_bytes: self.wal.config().max_bytes,
+            oldest_accepted_at: depth.oldest_accepted_at,
+            last_replay_at: stats.last_replay_at,
+            last_replay_error: stats.last_replay_error.clone(),
+            frames_replayed: stats.frames_replayed,
+            frames_dead_lettered: stats.frames_dead_lettered,
+            frames_dropped: stats.frames_dropped,
+        }
+    }
+
+    /// Replays segments oldest first until the log is empty or the database fails again
+    async fn replay(&self) {
+        loop {
+            match self.replay_oldest_segment().await {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) => {
+                    if e.is_database_outage() {
+                        warn!(error = %e, "Database still unavailable, write-ahead log replay postponed");
+                    } else {
+                        error!(error = %e, "Write-ahead log replay failed");
+                    }
+                    let mut stats = self.replay_stats();
+                    stats.last_replay_at = Some(Utc::now());
+                    stats.last_replay_error = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns `false` when there was nothing to replay
+    async fn replay_oldest_segment(&self) -> Result<bool, AppError> {
+        let Some((seq, records)) = self.wal.oldest_segment().await? else {
+            return Ok(false);
+        };
+
+        let outcome = self
+            .telemetry_service
+            .replay_buffered_frames(&records)
+            .await?;
+        self.wal.remove_segment(seq).await?;
+
+        info!(
+            segment = seq,
+            replayed = outcome.replayed,
+            duplicates = outcome.duplicates,
+            dead_lettered = outcome.dead_lettered,
+            dropped = outcome.dropped,
+            "Write-ahead log segment replayed"
+        );
+
+        let mut stats = self.replay_stats();
+        stats.last_replay_at = Some(Utc::now());
+        stats.last_replay_error = None;
+        stats.frames_replayed += outcome.replayed as u64;
+        stats.frames_dead_lettered += outcome.dead_lettered as u64;
+        stats.frames_dropped += outcome.dropped as u64;
+
+        Ok(true)
+    }
+
+    fn replay_stats(&self) -> std::sync::MutexGuard<'_, ReplayStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -1,11 +1,7 @@
-use crate::repositories::{
-    auth::AuthRepository, // Add this
-    metrics::MetricsRepository,
-    signal::SignalRepository,
-    telemetry::TelemetryRepository,
-    vessel::VesselRepository,
-};
+use crate::config::{AdmissionConfig, AsyncIngestionConfig, CacheConfig, IngestionConfig};
+use crate::repositories::Repositories;
 use crate::services::Services;
+use crate::wal::WriteAheadLog;
 use sqlx::PgPool;
 use std::sync::Arc;
 
@@ -27,6 +23,11 @@ impl AppState {
 #[derive(Default)]
 pub struct AppStateBuilder {
     db: Option<PgPool>,
+    ingestion_config: Option<IngestionConfig>,
+    async_ingestion: Option<AsyncIngestionConfig>,
+    wal: Option<Arc<WriteAheadLog>>,
+    admission: Option<AdmissionConfig>,
+    cache: Option<CacheConfig>,
 }
 
 impl AppStateBuilder {
@@ -35,21 +36,45 @@ impl AppStateBuilder {
         self
     }
 
+    pub fn ingestion_config(mut self, config: IngestionConfig) -> Self {
+        self.ingestion_config = Some(config);
+        self
+    }
+
+    /// Enables `202 Accepted` ingestion; `None` keeps every write synchronous
+    pub fn async_ingestion(mut self, config: Option<AsyncIngestionConfig>) -> Self {
+        self.async_ingestion = config;
+        self
+    }
+
+    /// Buffers frames on disk while the database is unreachable; `None` fails them instead
+    pub fn write_ahead_log(mut self, wal: Option<Arc<WriteAheadLog>>) -> Self {
+        self.wal = wal;
+        self
+    }
+
+    pub fn admission(mut self, config: AdmissionConfig) -> Self {
+        self.admission = Some(config);
+        self
+    }
+
+    pub fn cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(config);
+        self
+    }
+
     pub fn build(self) -> AppState {
         let db = self.db.expect("Database pool is required");
-
-        let vessel_repo = Arc::new(VesselRepository::new(db.clone()));
-        let signal_repo = Arc::new(SignalRepository::new(db.clone()));
-        let telemetry_repo = Arc::new(TelemetryRepository::new(db.clone()));
-        let metrics_repo = Arc::new(MetricsRepository::new(db.clone()));
-        let auth_repo = Arc::new(AuthRepository::new(db.clone())); // Add this
+        let ingestion_config = self.ingestion_config.unwrap_or_default();
 
         let services = Services::new(
-            vessel_repo,
-            signal_repo,
-            telemetry_repo,
-            metrics_repo,
-            auth_repo, // Add this
+            Repositories::new(db.clone()),
+            ingestion_config,
+            self.async_ingestion,
+            self.wal,
+            self.admission.unwrap_or_default(),
+            self.cache.unwrap_or_default(),
+            db,
         );
 
         AppState { services }
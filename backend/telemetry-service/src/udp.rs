@@ -0,0 +1,277 @@
+//! Optional UDP listener for compact signed telemetry datagrams
+//!
+//! Each datagram carries one protobuf `TelemetryFrame`, framed as:
+//!
+//! ```text
+//! "NT" | version (1) | vessel_id length | vessel_id | TelemetryFrame | tag (16 bytes)
+//! ```
+//!
+//! The tag is HMAC-SHA256 over every preceding byte, truncated to 16 bytes, keyed with
+//! `HMAC-SHA256(api_key, "nautilus-udp-v1")`. It stands in for the `x-api-key` header. Nothing is
+//! sent back, so a spoofed source address cannot be used for reflection. Rejected datagrams are
+//! counted per vessel and reason, and the counters are written to server_metrics periodically.
+
+use crate::config::UdpConfig;
+use crate::error::AppError;
+use crate::models::telemetry::TelemetryResponse;
+use crate::proto::telemetry::TelemetryFrame;
+use crate::state::AppState;
+use prost::Message;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+const MAGIC: &[u8; 2] = b"NT";
+const VERSION: u8 = 1;
+const TAG_LEN: usize = 16;
+/// Magic, version and vessel_id length
+const HEADER_LEN: usize = 4;
+/// Largest payload a UDP datagram can carry
+const MAX_DATAGRAM: usize = 65_507;
+
+/// Rejected datagram counts keyed by authenticated vessel and reason
+type Counters = Arc<Mutex<HashMap<(Option<String>, String), u64>>>;
+
+/// A datagram split into its parts; `signed` is everything before the tag
+struct Datagram<'a> {
+    vessel_id: &'a str,
+    payload: &'a [u8],
+    signed: &'a [u8],
+    tag: &'a [u8],
+}
+
+struct Rejection {
+    /// Only set once the signature checked out, so spoofed IDs never reach the metrics
+    vessel_id: Option<String>,
+    reason: String,
+    message: String,
+}
+
+impl Rejection {
+    fn new(reason: &str, message: impl Into<String>) -> Self {
+        Self {
+            vessel_id: None,
+            reason: reason.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn from_error(vessel_id: &str, error: AppError) -> Self {
+        let reason = match &error {
+            AppError::Rejected { reason, .. } => reason.as_str(),
+            AppError::Validation(_) => "validation_error",
+            AppError::Forbidden(_) | AppError::NotFound(_) => "vessel_not_accepted",
+            AppError::Database(_) | AppError::Internal(_) => "storage_error",
+            _ => "rejected",
+        };
+
+        Self {
+            vessel_id: Some(vessel_id.to_string()),
+            reason: reason.to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Binds the socket and starts the listener in the background
+pub async fn spawn_listener(config: UdpConfig, state: AppState) -> std::io::Result<JoinHandle<()>> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
+    let socket = UdpSocket::bind(addr).await?;
+
+    info!("UDP listener on {}", addr);
+
+    let counters = Counters::default();
+    tokio::spawn(flush_counters(
+        counters.clone(),
+        state.clone(),
+        Duration::from_secs(config.metrics_flush_secs),
+    ));
+
+    Ok(tokio::spawn(run_listener(
+        socket,
+        state,
+        counters,
+        Arc::new(Semaphore::new(config.max_concurrent)),
+    )))
+}
+
+async fn run_listener(
+    socket: UdpSocket,
+    state: AppState,
+    counters: Counters,
+    permits: Arc<Semaphore>,
+) {
+    let mut buf = vec![0u8; MAX_DATAGRAM];
+
+    loop {
+        let (len, peer) = match socket.recv_from(&mut buf).await {
+            Ok(received) => received,
+            Err(e) => {
+                warn!(error = %e, "Failed to receive UDP datagram");
+                continue;
+            }
+        };
+
+        // Waiting for a permit would only move the loss into the kernel buffer, where it is invisible
+        let Ok(permit) = permits.clone().try_acquire_owned() else {
+            count(
+                &counters,
+                Rejection::new("listener_busy", "All UDP workers are busy"),
+            );
+            continue;
+        };
+
+        let datagram = buf[..len].to_vec();
+        let state = state.clone();
+        let counters = counters.clone();
+        tokio::spawn(async move {
+            match handle_datagram(&state, &datagram).await {
+                Ok(response) => {
+                    debug!(
+                        peer = %peer,
+                        correlation_id = %response.correlation_id,
+                        "UDP telemetry ingested"
+                    );
+                }
+                Err(rejection) => {
+                    warn!(
+                        peer = %peer,
+                        vessel_id = ?rejection.vessel_id,
+                        reason = %rejection.reason,
+                        "UDP datagram rejected: {}",
+                        rejection.message
+                    );
+                    count(&counters, rejection);
+                }
+            }
+            drop(permit);
+        });
+    }
+}
+
+async fn handle_datagram(
+    state: &AppState,
+    datagram: &[u8],
+) -> Result<TelemetryResponse, Rejection> {
+    let Datagram {
+        vessel_id,
+        payload,
+        signed,
+        tag,
+    } = split_datagram(datagram)?;
+
+    state
+        .services()
+        .auth_service()
+        .verify_datagram(vessel_id, signed, tag)
+        .await
+        .map_err(|e| match e {
+            AppError::Unauthorized(message) => Rejection::new("unauthenticated", message),
+            other => Rejection::new("storage_error", other.to_string()),
+        })?;
+
+    let mut frame = TelemetryFrame::decode(payload).map_err(|e| {
+        Rejection::from_error(
+            vessel_id,
+            AppError::Validation(format!("Failed to decode protobuf frame: {}", e)),
+        )
+    })?;
+
+    // The signature covers the frame, but only the frame ID stops a captured datagram being replayed,
+    // and only for as long as its claim is kept
+    if frame.frame_id.is_empty() {
+        return Err(Rejection::from_error(
+            vessel_id,
+            AppError::Validation("UDP frames must carry a frame_id".to_string()),
+        ));
+    }
+    state
+        .services()
+        .telemetry_service()
+        .check_replay_window(frame.timestamp_utc_ms)
+        .map_err(|e| Rejection::from_error(vessel_id, e))?;
+    if frame.vessel_id.is_empty() {
+        frame.vessel_id = vessel_id.to_string();
+    } else if frame.vessel_id != vessel_id {
+        return Err(Rejection::from_error(
+            vessel_id,
+            AppError::Forbidden(format!(
+                "Vessel ID mismatch: signed for '{}' but frame contains '{}'",
+                vessel_id, frame.vessel_id
+            )),
+        ));
+    }
+
+    state
+        .services()
+        .telemetry_service()
+        .ingest_telemetry_proto(frame)
+        .await
+        .map_err(|e| Rejection::from_error(vessel_id, e))
+}
+
+fn split_datagram(datagram: &[u8]) -> Result<Datagram<'_>, Rejection> {
+    let malformed = |message: &str| Rejection::new("malformed_datagram", message);
+
+    if datagram.len() < HEADER_LEN + TAG_LEN || &datagram[..2] != MAGIC {
+        return Err(malformed("Not a telemetry datagram"));
+    }
+    if datagram[2] != VERSION {
+        return Err(malformed("Unsupported datagram version"));
+    }
+
+    let (signed, tag) = datagram.split_at(datagram.len() - TAG_LEN);
+    let id_len = signed[3] as usize;
+    if id_len == 0 || signed.len() < HEADER_LEN + id_len {
+        return Err(malformed("Vessel ID length does not fit the datagram"));
+    }
+
+    let vessel_id = std::str::from_utf8(&signed[HEADER_LEN..HEADER_LEN + id_len])
+        .map_err(|_| malformed("Vessel ID is not valid UTF-8"))?;
+
+    Ok(Datagram {
+        vessel_id,
+        payload: &signed[HEADER_LEN + id_len..],
+        signed,
+        tag,
+    })
+}
+
+fn count(counters: &Counters, rejection: Rejection) {
+    let mut counters = counters.lock().unwrap_or_else(|e| e.into_inner());
+    *counters
+        .entry((rejection.vessel_id, rejection.reason))
+        .or_insert(0) += 1;
+}
+
+/// Writes the counters to server_metrics once per interval, keeping them if the write fails
+async fn flush_counters(counters: Counters, state: AppState, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        let pending = std::mem::take(&mut *counters.lock().unwrap_or_else(|e| e.into_inner()));
+        for ((vessel_id, reason), rejected) in pending {
+            if let Err(e) = state
+                .services()
+                .metrics_service()
+                .record_udp_rejections(vessel_id.clone(), &reason, rejected)
+                .await
+            {
+                error!(error = %e, "Failed to record UDP rejection metrics");
+                *counters
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .entry((vessel_id, reason))
+                    .or_insert(0) += rejected;
+            }
+        }
+    }
+}
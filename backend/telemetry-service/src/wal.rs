@@ -0,0 +1,247 @@
+//! On-disk write-ahead log for frames accepted while PostgreSQL is unreachable
+//!
+//! Records are JSON lines appended to numbered segment files (`<seq>.wal`) and synced to disk
+//! before the frame is acknowledged. Replay takes the oldest segment, sealing it first if it is
+//! still being appended to, and deletes it once every record in it has been handled. Replaying
+//! a segment a second time, e.g. after a crash, writes nothing new: every replayed frame is
+//! claimed in ingested_frames together with its rows.
+
+use crate::config::WalConfig;
+use crate::error::AppError;
+use crate::models::telemetry::WalRecord;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// A segment is sealed and a new one started once it reaches this size
+const SEGMENT_MAX_BYTES: u64 = 16 * 1024 * 1024;
+const SEGMENT_EXTENSION: &str = "wal";
+
+#[derive(Debug, Clone, Copy, Default)]
+struct SegmentInfo {
+    frames: usize,
+    bytes: u64,
+    oldest_accepted_at: Option<DateTime<Utc>>,
+}
+
+struct LogState {
+    segments: BTreeMap<u64, SegmentInfo>,
+    /// Segment currently appended to
+    active: Option<(u64, File)>,
+    next_seq: u64,
+}
+
+/// What is waiting on disk
+#[derive(Debug, Clone, Copy)]
+pub struct WalDepth {
+    pub frames: usize,
+    pub segments: usize,
+    pub bytes: u64,
+    pub oldest_accepted_at: Option<DateTime<Utc>>,
+}
+
+pub struct WriteAheadLog {
+    config: WalConfig,
+    state: Mutex<LogState>,
+}
+
+impl WriteAheadLog {
+    /// Opens the log directory, creating it if needed, and picks up segments left by earlier runs
+    pub async fn open(config: WalConfig) -> Result<Self, AppError> {
+        fs::create_dir_all(&config.dir).await.map_err(io_error)?;
+
+        let mut segments = BTreeMap::new();
+        let mut entries = fs::read_dir(&config.dir).await.map_err(io_error)?;
+        while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+            let path = entry.path();
+            let Some(seq) = segment_seq(&path) else {
+                continue;
+            };
+
+            let records = read_records(&path).await?;
+            let bytes = entry.metadata().await.map_err(io_error)?.len();
+            segments.insert(
+                seq,
+                SegmentInfo {
+                    frames: records.len(),
+                    bytes,
+                    oldest_accepted_at: records.first().map(|r| r.accepted_at),
+                },
+            );
+        }
+
+        let next_seq = segments.keys().next_back().map_or(1, |seq| seq + 1);
+        let frames: usize = segments.values().map(|s| s.frames).sum();
+        info!(
+            dir = %config.dir.display(),
+            segments = segments.len(),
+            frames,
+            "Write-ahead log opened"
+        );
+
+        Ok(Self {
+            config,
+            state: Mutex::new(LogState {
+                segments,
+                active: None,
+                next_seq,
+            }),
+        })
+    }
+
+    pub fn config(&self) -> &WalConfig {
+        &self.config
+    }
+
+    /// Appends a frame and syncs it to disk; refused with 503 once the log is full
+    pub async fn append(&self, record: &WalRecord) -> Result<(), AppError> {
+        let mut line = serde_json::to_vec(record).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize write-ahead log record: {}", e))
+        })?;
+        line.push(b'\n');
+        let len = line.len() as u64;
+
+        let mut state = self.state.lock().await;
+        let total: u64 = state.segments.values().map(|s| s.bytes).sum();
+        if total + len > self.config.max_bytes {
+            return Err(AppError::ServiceUnavailable(
+                "Database is unavailable and the write-ahead log is full".to_string(),
+            ));
+        }
+
+        let active_full = state
+            .active
+            .as_ref()
+            .is_some_and(|(seq, _)| state.segments[seq].bytes >= SEGMENT_MAX_BYTES);
+        if active_full {
+            state.active = None;
+        }
+        if state.active.is_none() {
+            let seq = state.next_seq;
+            let file = self.create_segment(seq).await?;
+            state.next_seq += 1;
+            state.segments.insert(seq, SegmentInfo::default());
+            state.active = Some((seq, file));
+        }
+
+        let (seq, file) = state.active.as_mut().expect("a segment was opened above");
+        let seq = *seq;
+        let written = match file.write_all(&line).await {
+            Ok(()) => file.sync_data().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            // A partial line is skipped when read back; later records go to a fresh segment
+            state.active = None;
+            return Err(io_error(e));
+        }
+
+        let segment = state.segments.entry(seq).or_default();
+        segment.frames += 1;
+        segment.bytes += len;
+        segment.oldest_accepted_at.get_or_insert(record.accepted_at);
+
+        Ok(())
+    }
+
+    pub async fn depth(&self) -> WalDepth {
+        let state = self.state.lock().await;
+
+        WalDepth {
+            frames: state.segments.values().map(|s| s.frames).sum(),
+            segments: state.segments.len(),
+            bytes: state.segments.values().map(|s| s.bytes).sum(),
+            oldest_accepted_at: state.segments.values().find_map(|s| s.oldest_accepted_at),
+        }
+    }
+
+    /// Oldest segment and its records; new frames go to another segment from here on
+    pub async fn oldest_segment(&self) -> Result<Option<(u64, Vec<WalRecord>)>, AppError> {
+        let seq = {
+            let mut state = self.state.lock().await;
+            let Some(&seq) = state.segments.keys().next() else {
+                return Ok(None);
+            };
+            if state
+                .active
+                .as_ref()
+                .is_some_and(|(active, _)| *active == seq)
+            {
+                state.active = None;
+            }
+            seq
+        };
+
+        let records = read_records(&self.segment_path(seq)).await?;
+        Ok(Some((seq, records)))
+    }
+
+    /// Deletes a segment whose records have all been handled
+    pub async fn remove_segment(&self, seq: u64) -> Result<(), AppError> {
+        fs::remove_file(self.segment_path(seq))
+            .await
+            .map_err(io_error)?;
+        self.state.lock().await.segments.remove(&seq);
+
+        Ok(())
+    }
+
+    async fn create_segment(&self, seq: u64) -> Result<File, AppError> {
+        let file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(self.segment_path(seq))
+            .await
+            .map_err(io_error)?;
+
+        // Make the new directory entry durable along with the records written to it
+        let dir = File::open(&self.config.dir).await.map_err(io_error)?;
+        dir.sync_all().await.map_err(io_error)?;
+
+        Ok(file)
+    }
+
+    fn segment_path(&self, seq: u64) -> PathBuf {
+        self.config
+            .dir
+            .join(format!("{:020}.{}", seq, SEGMENT_EXTENSION))
+    }
+}
+
+fn segment_seq(path: &Path) -> Option<u64> {
+    if path.extension()? != SEGMENT_EXTENSION {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Reads a segment, skipping lines that cannot be parsed, such as a write cut off by a crash
+async fn read_records(path: &Path) -> Result<Vec<WalRecord>, AppError> {
+    let contents = fs::read(path).await.map_err(io_error)?;
+
+    let mut records = Vec::new();
+    for (index, line) in contents.split(|b| *b == b'\n').enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_slice(line) {
+            Ok(record) => records.push(record),
+            Err(e) => warn!(
+                path = %path.display(),
+                line = index + 1,
+                error = %e,
+                "Skipping unreadable write-ahead log record"
+            ),
+        }
+    }
+
+    Ok(records)
+}
+
+fn io_error(e: std::io::Error) -> AppError {
+    AppError::Internal(format!("Write-ahead log error: {}", e))
+}
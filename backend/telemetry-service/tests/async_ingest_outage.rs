@@ -0,0 +1,79 @@
+use chrono::Utc;
+use serde_json::json;
+use sqlx::postgres::PgPoolOptions;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use telemetry_service::config::{
+    AdmissionConfig, AsyncIngestionConfig, CacheConfig, IngestionConfig, WalConfig,
+};
+use telemetry_service::models::telemetry::{INGESTION_BUFFERED, TelemetryRequest};
+use telemetry_service::repositories::Repositories;
+use telemetry_service::services::Services;
+use telemetry_service::services::ingest_queue::Enqueued;
+use telemetry_service::wal::WriteAheadLog;
+use uuid::Uuid;
+
+/// With the database down, a frame handed to the async queue lands in the write-ahead log and
+/// reports `buffered` instead of failing
+#[tokio::test]
+async fn async_queue_buffers_frames_while_database_is_down() {
+    // Nothing listens on port 1, so every connection attempt fails
+    let pool = PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(500))
+        .connect_lazy("postgres://telemetry@127.0.0.1:1/telemetry")
+        .expect("lazy pool");
+
+    let wal_dir = std::env::temp_dir().join(format!("telemetry-wal-{}", Uuid::new_v4()));
+    let wal = Arc::new(
+        WriteAheadLog::open(WalConfig {
+            dir: wal_dir.clone(),
+            max_bytes: 1024 * 1024,
+            replay_interval_secs: 3600,
+            acquire_timeout_secs: 1,
+        })
+        .await
+        .expect("open write-ahead log"),
+    );
+
+    let services = Services::new(
+        Repositories::new(pool.clone()),
+        IngestionConfig::default(),
+        Some(AsyncIngestionConfig {
+            queue_capacity: 16,
+            workers: 1,
+            batch_frames: 8,
+            status_retention_secs: 60,
+        }),
+        Some(wal.clone()),
+        AdmissionConfig::default(),
+        CacheConfig::default(),
+        pool,
+    );
+    let queue = services.ingest_queue().expect("async ingestion enabled");
+    queue.spawn_workers();
+
+    let timestamp_utc = Utc::now();
+    let request = TelemetryRequest {
+        vessel_id: "V1".to_string(),
+        timestamp_utc,
+        epoch_utc: timestamp_utc.timestamp().to_string(),
+        signals: HashMap::from([("Signal_1".to_string(), json!(42))]),
+        frame_id: None,
+    };
+
+    let response = match queue.enqueue(request).await.expect("frame accepted") {
+        Enqueued::Accepted(response) => response,
+        Enqueued::Replayed(_) => panic!("frame without an ID was replayed"),
+    };
+
+    let status = queue
+        .get_status("V1", response.correlation_id)
+        .await
+        .expect("status of the accepted frame");
+    assert_eq!(status.status, INGESTION_BUFFERED);
+    assert_eq!(wal.depth().await.frames, 1);
+
+    queue.shutdown().await;
+    let _ = tokio::fs::remove_dir_all(&wal_dir).await;
+}